@@ -0,0 +1,40 @@
+//! Sand und Wasser auf einen Boden schütten, bis alles liegt – ganz ohne Bevy.
+//!
+//! `cargo run --example headless_pour --no-default-features`
+
+use world::{MaterialTyp, ParticleRef, Simulation};
+
+const MAX_TICKS: usize = 500;
+const QUIET_TICKS: usize = 10;
+
+fn main() -> Result<(), String> {
+    let mut sim = Simulation::new(30, 40, [0.0, -1.0]);
+
+    // Boden
+    for x in 0..sim.world.width {
+        sim.world.update_occupation_on_position([x as f32, 0.0], ParticleRef::Static);
+        sim.world.update_mass_on_position([x as f32, 0.0], 1000.0);
+    }
+
+    sim.spawn_particles_rect((8, 15), (15, 24), MaterialTyp::Sand);
+    sim.spawn_particles_rect((24, 15), (31, 24), MaterialTyp::Wasser);
+    sim.spawn_object([18.0, 20.0], MaterialTyp::Stein, 3, 3)?;
+
+    // Ruhe: einige Ticks hintereinander bewegt sich nichts mehr
+    let mut quiet = 0;
+    let mut ticks = 0;
+    while ticks < MAX_TICKS && quiet < QUIET_TICKS {
+        sim.step();
+        ticks += 1;
+        quiet = if sim.activity() > 0 { 0 } else { quiet + 1 };
+    }
+
+    println!("Nach {} Ticks:", ticks);
+    print!("{}", sim.render_ascii());
+
+    let counts = sim.count_by_material();
+    for material in [MaterialTyp::Sand, MaterialTyp::Wasser, MaterialTyp::Beton, MaterialTyp::Stein] {
+        println!("{:?}: {}", material, counts.get(&material).copied().unwrap_or(0));
+    }
+    Ok(())
+}
@@ -0,0 +1,46 @@
+//! Benchmark für Kollisionen, Brüche und Fragmente: ein Raster aus Quadranten-Blöcken auf den Boden schießen
+//! und die Schritte stoppen.
+//!
+//! `cargo run --release --example stress_objects --no-default-features -- 10 10 3`
+//! (Spalten, Zeilen, Startgeschwindigkeit nach unten)
+
+use std::time::Instant;
+use world::{ParticleRef, Simulation};
+
+const STEPS: usize = 100;
+
+fn arg(index: usize, default: usize) -> Result<usize, String> {
+    match std::env::args().nth(index) {
+        Some(value) => value.parse().map_err(|_| format!("Ungültige Zahl: {}", value)),
+        None => Ok(default),
+    }
+}
+
+fn main() -> Result<(), String> {
+    let cols = arg(1, 10)?;
+    let rows = arg(2, 10)?;
+    let launch = arg(3, 0)? as f32;
+
+    let mut sim = Simulation::new(rows * 5 + 20, cols * 5 + 10, [0.0, -1.0]);
+    for x in 0..sim.world.width {
+        sim.world.update_occupation_on_position([x as f32, 0.0], ParticleRef::Static);
+        sim.world.update_mass_on_position([x as f32, 0.0], 1000.0);
+    }
+
+    let spawned = sim.spawn_object_grid((5, 15), cols, rows, 1, launch);
+    println!("{} Objekte gespawnt", spawned.len());
+
+    let mut slowest = 0.0f64;
+    let mut splits = 0;
+    let start = Instant::now();
+    for _ in 0..STEPS {
+        let step_start = Instant::now();
+        splits += sim.step().splits.len();
+        slowest = slowest.max(step_start.elapsed().as_secs_f64() * 1000.0);
+    }
+    let total = start.elapsed().as_secs_f64() * 1000.0;
+
+    println!("{} Schritte: gesamt {:.1} ms, Ø {:.3} ms, langsamster {:.3} ms", STEPS, total, total / STEPS as f64, slowest);
+    println!("{} Brüche, {} Objekte, {} Partikel", splits, sim.objects.len(), sim.particles.len());
+    Ok(())
+}
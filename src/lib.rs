@@ -1,15 +1,24 @@
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
 
 /// Referenz auf ein Partikel im World-Grid.
-#[derive(Debug, Clone, Copy)]
+/// `Free(index, generation)`: die Generation erkennt veraltete Referenzen nach dem Entfernen von Partikeln.
+/// `InObject(object_id, i, j)`: die stabile `object_id`, den Index liefert `Simulation::object_index`.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ParticleRef {
-    Free(usize),
-    InObject(usize, usize, usize),
+    Free(usize, u32),
+    InObject(i32, usize, usize),
     Static,
 }
 
 /// Materialtypen für Partikel.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
 pub enum MaterialTyp {
     Sand,
     Stein,
@@ -17,9 +26,39 @@ pub enum MaterialTyp {
     Luft,
     Wasser,
     Holz,
+    Eis,
+    /// Entsteht aus Sand und Wasser (siehe `Simulation::mixing_recipes`), verhält sich bis zum Aushärten wie nasser Sand.
+    Beton,
+    /// Fließt wie eine Flüssigkeit und erstarrt mit der Zeit zu Stein (siehe `ages_into`).
+    Lava,
 }
 
 impl MaterialTyp {
+    pub const ALL: [MaterialTyp; 9] = [
+        MaterialTyp::Sand,
+        MaterialTyp::Stein,
+        MaterialTyp::Metall,
+        MaterialTyp::Luft,
+        MaterialTyp::Wasser,
+        MaterialTyp::Holz,
+        MaterialTyp::Eis,
+        MaterialTyp::Beton,
+        MaterialTyp::Lava,
+    ];
+
+    /// Alle Materialien außer Luft in der Reihenfolge der Zifferntasten: die ursprünglichen fünf zuerst,
+    /// neue Materialien werden hinten angehängt, damit bestehende Tasten bleiben.
+    pub const PAINTABLE: [MaterialTyp; 8] = [
+        MaterialTyp::Sand,
+        MaterialTyp::Stein,
+        MaterialTyp::Metall,
+        MaterialTyp::Holz,
+        MaterialTyp::Wasser,
+        MaterialTyp::Eis,
+        MaterialTyp::Beton,
+        MaterialTyp::Lava,
+    ];
+
     pub fn binding_strength(&self) -> f32 {
         match self {
             MaterialTyp::Sand => 2.0,
@@ -28,6 +67,9 @@ impl MaterialTyp {
             MaterialTyp::Luft => 0.0,
             MaterialTyp::Wasser => 0.0,
             MaterialTyp::Holz => 40.0,
+            MaterialTyp::Eis => 5.0,
+            MaterialTyp::Beton => 150.0,
+            MaterialTyp::Lava => 0.0,
         }
     }
 
@@ -39,14 +81,14 @@ impl MaterialTyp {
             MaterialTyp::Luft => 0.001,
             MaterialTyp::Wasser => 1.0,
             MaterialTyp::Holz => 0.6,
+            MaterialTyp::Eis => 0.9,
+            MaterialTyp::Beton => 1.25,
+            MaterialTyp::Lava => 2.4,
         }
     }
 
     pub fn is_solid(&self) -> bool {
-        match self {
-            MaterialTyp::Luft | MaterialTyp::Wasser => false,
-            _ => true,
-        }
+        !matches!(self, MaterialTyp::Luft | MaterialTyp::Wasser | MaterialTyp::Lava)
     }
 
     pub fn impact_dampening(&self) -> f32 {
@@ -57,9 +99,155 @@ impl MaterialTyp {
             MaterialTyp::Luft => 0.0,
             MaterialTyp::Wasser => 0.2,
             MaterialTyp::Holz => 0.6,
+            MaterialTyp::Eis => 0.8,
+            MaterialTyp::Beton => 1.0,
+            MaterialTyp::Lava => 0.2,
+        }
+    }
+
+    /// Gleitreibung für Objekte, die auf diesem Material aufliegen.
+    pub fn friction(&self) -> f32 {
+        match self {
+            MaterialTyp::Sand => 0.8,
+            MaterialTyp::Stein => 0.5,
+            MaterialTyp::Metall => 0.3,
+            MaterialTyp::Luft => 0.0,
+            MaterialTyp::Wasser => 0.1,
+            MaterialTyp::Holz => 0.6,
+            MaterialTyp::Eis => 0.02,
+            MaterialTyp::Beton => 0.7,
+            MaterialTyp::Lava => 0.2,
+        }
+    }
+
+    /// Wie schnell sich ein Riss durch das Material frisst (Schaden pro Tick, 0 = duktil).
+    pub fn brittleness(&self) -> f32 {
+        match self {
+            MaterialTyp::Sand => 1.0,
+            MaterialTyp::Stein => 0.5,
+            MaterialTyp::Metall => 0.0,
+            MaterialTyp::Luft => 0.0,
+            MaterialTyp::Wasser => 0.0,
+            MaterialTyp::Holz => 0.2,
+            MaterialTyp::Eis => 1.0,
+            MaterialTyp::Beton => 0.3,
+            MaterialTyp::Lava => 0.0,
+        }
+    }
+
+    /// Wie leicht schnell fließendes Wasser das Material mitreißt (0 = gar nicht).
+    pub fn erodibility(&self) -> f32 {
+        match self {
+            MaterialTyp::Sand => 0.3,
+            MaterialTyp::Stein => 0.0,
+            MaterialTyp::Metall => 0.0,
+            MaterialTyp::Luft => 0.0,
+            MaterialTyp::Wasser => 0.0,
+            MaterialTyp::Holz => 0.0,
+            MaterialTyp::Eis => 0.0,
+            MaterialTyp::Beton => 0.0,
+            MaterialTyp::Lava => 0.0,
+        }
+    }
+
+    /// Höchste frei stehende Säule (ohne seitlichen Halt) aus losen Partikeln; darüber kippt die Spitze (0 = unbegrenzt).
+    pub fn stack_limit(&self) -> usize {
+        match self {
+            MaterialTyp::Sand => 4,
+            MaterialTyp::Stein => 20,
+            MaterialTyp::Metall => 0,
+            MaterialTyp::Luft => 0,
+            MaterialTyp::Wasser => 0,
+            MaterialTyp::Holz => 0,
+            MaterialTyp::Eis => 10,
+            MaterialTyp::Beton => 6,
+            MaterialTyp::Lava => 0,
+        }
+    }
+
+    /// Größter Höhenunterschied zur Nachbarspalte, den eine Oberfläche aus losen Partikeln dieses Materials hält,
+    /// siehe `Simulation::relax_terrain` (0 = rutscht nie).
+    pub fn max_slope(&self) -> usize {
+        match self {
+            MaterialTyp::Sand => 1,
+            MaterialTyp::Beton => 2,
+            _ => 0,
+        }
+    }
+
+    /// Last pro Spalte, die lose Partikel dieses Materials unter einem Objekt tragen, bevor es einsinkt
+    /// (unendlich = trägt immer).
+    pub fn bearing_capacity(&self) -> f32 {
+        match self {
+            MaterialTyp::Sand => 6.0,
+            MaterialTyp::Beton => 10.0,
+            _ => f32::INFINITY,
+        }
+    }
+
+    /// Haftkraft an Wänden, Decken und Objekten. Ein Partikel bleibt kleben, solange sein Gewicht samt der
+    /// Partikel darüber die Haftkraft nicht übersteigt, siehe `Particle::adheres`. Nur nasser Beton klebt.
+    pub fn adhesion(&self) -> f32 {
+        match self {
+            MaterialTyp::Beton => 4.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Kollisionsebenen als Bitmaske; standardmäßig liegen alle Materialien auf Ebene 1.
+    pub fn collision_layer(&self) -> u32 {
+        1
+    }
+
+    /// Ebenen, mit denen dieses Material zusammenstößt; standardmäßig alle.
+    pub fn collides_with(&self) -> u32 {
+        u32::MAX
+    }
+
+    /// Ob ein Partikel dieses Materials von `other` aufgehalten wird. Sonst zieht es mit
+    /// `Simulation::pass_through` durch `other` hindurch.
+    pub fn collides(&self, other: MaterialTyp) -> bool {
+        self.collides_with() & other.collision_layer() != 0
+    }
+
+    /// Zeichen für die ASCII-Ausgabe.
+    pub fn symbol(&self) -> char {
+        match self {
+            MaterialTyp::Sand => 's',
+            MaterialTyp::Stein => 'o',
+            MaterialTyp::Metall => 'm',
+            MaterialTyp::Luft => '.',
+            MaterialTyp::Wasser => '~',
+            MaterialTyp::Holz => 'h',
+            MaterialTyp::Eis => 'e',
+            MaterialTyp::Beton => 'b',
+            MaterialTyp::Lava => 'l',
+        }
+    }
+
+    /// Eingebaute Darstellung im Fenster, pro Simulation überschreibbar (siehe `MaterialTable`). Gase diffus, Flüssigkeiten
+    /// leicht durchscheinend, Sand als Körner, Metall mit Glanzpunkt, alles andere flach und deckend.
+    pub fn sprite_style(&self) -> SpriteStyle {
+        let flat = SpriteStyle::default();
+        match self {
+            MaterialTyp::Luft => SpriteStyle { scale: 1.3, alpha: 0.3, ..flat },
+            MaterialTyp::Wasser => SpriteStyle { scale: 1.05, alpha: 0.85, ..flat },
+            MaterialTyp::Sand => SpriteStyle { shape: SpriteShape::Dot, ..flat },
+            MaterialTyp::Metall => SpriteStyle { highlight: 0.3, ..flat },
+            _ => flat,
         }
     }
 
+    /// Sprite-Größe relativ zur Zelle; die Physik bleibt immer bei einer Zelle.
+    pub fn render_scale(&self) -> f32 {
+        self.sprite_style().scale
+    }
+
+    /// Deckkraft beim Rendern, siehe `sprite_style`.
+    pub fn render_alpha(&self) -> f32 {
+        self.sprite_style().alpha
+    }
+
     pub fn color(&self) -> (f32, f32, f32) {
         match self {
             MaterialTyp::Sand => (0.9, 0.75, 0.4),
@@ -68,10 +256,181 @@ impl MaterialTyp {
             MaterialTyp::Luft => (0.9, 0.95, 1.0),
             MaterialTyp::Wasser => (0.2, 0.5, 0.8),
             MaterialTyp::Holz => (0.55, 0.35, 0.15),
+            MaterialTyp::Eis => (0.75, 0.9, 1.0),
+            MaterialTyp::Beton => (0.62, 0.62, 0.58),
+            MaterialTyp::Lava => (1.0, 0.35, 0.05),
+        }
+    }
+
+    /// Material, zu dem ein ruhendes freies Partikel nach so vielen Ticks wird (siehe `Simulation::age_particles`).
+    pub fn ages_into(&self) -> Option<(MaterialTyp, u32)> {
+        match self {
+            MaterialTyp::Lava => Some((MaterialTyp::Stein, LAVA_COOL_TICKS)),
+            _ => None,
         }
     }
 }
 
+/// Eingebaute Mischregeln (Zutat, Zutat, Ergebnis), mit denen `Simulation::mixing_recipes` startet: Zwei benachbarte
+/// freie Partikel unter Druck werden beide zum Ergebnis. Die Dichte des Ergebnisses ist der Mittelwert der Zutaten,
+/// damit die Gesamtmasse erhalten bleibt.
+pub const MIXING_RECIPES: [(MaterialTyp, MaterialTyp, MaterialTyp); 1] = [
+    (MaterialTyp::Sand, MaterialTyp::Wasser, MaterialTyp::Beton),
+];
+
+/// Grundform eines Zell-Sprites.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+pub enum SpriteShape {
+    #[default]
+    Square,
+    /// Rundes Korn statt Quadrat.
+    Dot,
+}
+
+/// Beschreibung eines Zell-Sprites; der Standard ist ein flaches, deckendes Quadrat in `MaterialTyp::color`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SpriteStyle {
+    pub shape: SpriteShape,
+    /// Größe relativ zur Zelle; die Physik bleibt immer bei einer Zelle.
+    pub scale: f32,
+    pub alpha: f32,
+    /// Wie stark der Rest der Zelle gegenüber einem Glanzpunkt oben links abgedunkelt wird (0 = kein Glanz).
+    pub highlight: f32,
+}
+
+impl Default for SpriteStyle {
+    fn default() -> Self {
+        SpriteStyle { shape: SpriteShape::Square, scale: 1.0, alpha: 1.0, highlight: 0.0 }
+    }
+}
+
+impl SpriteStyle {
+    /// Flaches Quadrat, braucht keine Textur.
+    pub fn is_flat(&self) -> bool {
+        self.shape == SpriteShape::Square && self.highlight <= 0.0
+    }
+}
+
+// ============== MATERIAL-CONFIG ==============
+
+/// Überschreibt einzelne Eigenschaften eines Materials; fehlende Felder behalten den eingebauten Wert.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default)]
+pub struct MaterialOverride {
+    pub density: Option<f32>,
+    pub binding_strength: Option<f32>,
+    pub impact_dampening: Option<f32>,
+    pub friction: Option<f32>,
+    pub brittleness: Option<f32>,
+    pub erodibility: Option<f32>,
+    pub stack_limit: Option<usize>,
+    pub max_slope: Option<usize>,
+    pub bearing_capacity: Option<f32>,
+    pub adhesion: Option<f32>,
+    pub color: Option<(f32, f32, f32)>,
+    pub sprite: Option<SpriteStyle>,
+    pub collision_layer: Option<u32>,
+    pub collides_with: Option<u32>,
+}
+
+/// Materialwerte einer Simulation: die eingebauten Werte von `MaterialTyp`, teilweise überschrieben
+/// per `MaterialOverride`. Liegt in der `World`, damit jede Simulation ihre eigenen Werte hat.
+#[derive(Debug, Clone, Default)]
+pub struct MaterialTable {
+    overrides: [MaterialOverride; MaterialTyp::ALL.len()],
+    count: usize,
+}
+
+impl MaterialTable {
+    /// Liest Materialwerte im RON-Format, z.B. `{ Stein: (binding_strength: Some(500.0)) }`.
+    pub fn from_config(text: &str) -> Result<MaterialTable, String> {
+        let config: HashMap<MaterialTyp, MaterialOverride> = ron::from_str(text).map_err(|e| e.to_string())?;
+        let mut table = MaterialTable { count: config.len(), ..MaterialTable::default() };
+        for (material, entry) in config {
+            table.overrides[material as usize] = entry;
+        }
+        Ok(table)
+    }
+
+    /// Anzahl der Materialien mit Overrides.
+    pub fn overridden_count(&self) -> usize {
+        self.count
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<MaterialTable, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        MaterialTable::from_config(&text)
+    }
+
+    fn get(&self, material: MaterialTyp) -> &MaterialOverride {
+        &self.overrides[material as usize]
+    }
+
+    pub fn density(&self, material: MaterialTyp) -> f32 {
+        self.get(material).density.unwrap_or_else(|| material.density())
+    }
+
+    pub fn binding_strength(&self, material: MaterialTyp) -> f32 {
+        self.get(material).binding_strength.unwrap_or_else(|| material.binding_strength())
+    }
+
+    pub fn impact_dampening(&self, material: MaterialTyp) -> f32 {
+        self.get(material).impact_dampening.unwrap_or_else(|| material.impact_dampening())
+    }
+
+    pub fn friction(&self, material: MaterialTyp) -> f32 {
+        self.get(material).friction.unwrap_or_else(|| material.friction())
+    }
+
+    pub fn brittleness(&self, material: MaterialTyp) -> f32 {
+        self.get(material).brittleness.unwrap_or_else(|| material.brittleness())
+    }
+
+    pub fn erodibility(&self, material: MaterialTyp) -> f32 {
+        self.get(material).erodibility.unwrap_or_else(|| material.erodibility())
+    }
+
+    pub fn stack_limit(&self, material: MaterialTyp) -> usize {
+        self.get(material).stack_limit.unwrap_or_else(|| material.stack_limit())
+    }
+
+    pub fn max_slope(&self, material: MaterialTyp) -> usize {
+        self.get(material).max_slope.unwrap_or_else(|| material.max_slope())
+    }
+
+    pub fn bearing_capacity(&self, material: MaterialTyp) -> f32 {
+        self.get(material).bearing_capacity.unwrap_or_else(|| material.bearing_capacity())
+    }
+
+    pub fn adhesion(&self, material: MaterialTyp) -> f32 {
+        self.get(material).adhesion.unwrap_or_else(|| material.adhesion())
+    }
+
+    /// Wie `MaterialTyp::collides`, mit den Kollisionsebenen dieser Tabelle.
+    pub fn collides(&self, material: MaterialTyp, other: MaterialTyp) -> bool {
+        let collides_with = self.get(material).collides_with.unwrap_or_else(|| material.collides_with());
+        let layer = self.get(other).collision_layer.unwrap_or_else(|| other.collision_layer());
+        collides_with & layer != 0
+    }
+
+    pub fn sprite_style(&self, material: MaterialTyp) -> SpriteStyle {
+        self.get(material).sprite.unwrap_or_else(|| material.sprite_style())
+    }
+
+    pub fn render_scale(&self, material: MaterialTyp) -> f32 {
+        self.sprite_style(material).scale
+    }
+
+    pub fn render_alpha(&self, material: MaterialTyp) -> f32 {
+        self.sprite_style(material).alpha
+    }
+
+    pub fn color(&self, material: MaterialTyp) -> (f32, f32, f32) {
+        self.get(material).color.unwrap_or_else(|| material.color())
+    }
+}
+
 // ============== PARTICLE ==============
 
 #[derive(Debug, Clone)]
@@ -81,20 +440,45 @@ pub struct Particle {
     pub velocity: [f32; 2],
     pub material: MaterialTyp,
     pub particle_ref: ParticleRef,
+    /// Von Wasser mitgeführtes Korn (Erosion): Die Zelle wiegt so viel wie das Korn und wird beim Ablagern wieder dazu.
+    pub sediment: Option<MaterialTyp>,
+    /// Ticks in Ruhe seit dem Entstehen bzw. dem letzten Materialwechsel, siehe `MaterialTyp::ages_into`.
+    pub age: u32,
+    /// Masse aufgenommener Nachbarn (`CapPolicy::Merge`), zusätzlich zur Dichte des Materials.
+    pub merged_mass: f32,
 }
 
 impl Particle {
     pub fn new(id: i32, position: [f32; 2], velocity: [f32; 2], material: MaterialTyp, particle_ref: ParticleRef) -> Particle {
-        Particle { id, position, velocity, material, particle_ref }
+        Particle { id, position, velocity, material, particle_ref, sediment: None, age: 0, merged_mass: 0.0 }
     }
 
-    pub fn mass(&self) -> f32 {
-        self.material.density()
+    pub fn mass(&self, materials: &MaterialTable) -> f32 {
+        materials.density(self.sediment.unwrap_or(self.material)) + self.merged_mass
     }
 
-    fn check_way(&self, world: &World) -> Option<(f32, i32, i32)> {
-        let own_x_pos = self.position[0] as i32;
-        let own_y_pos = self.position[1] as i32;
+    /// Anzeigefarbe: Alternde Materialien gehen mit dem Alter in die Farbe ihres Zielmaterials über.
+    pub fn color(&self, materials: &MaterialTable) -> (f32, f32, f32) {
+        let (r, g, b) = materials.color(self.material);
+        let Some((target, ticks)) = self.material.ages_into() else { return (r, g, b); };
+        let t = (self.age as f32 / ticks.max(1) as f32).min(1.0);
+        let (tr, tg, tb) = materials.color(target);
+        (r + (tr - r) * t, g + (tg - g) * t, b + (tb - b) * t)
+    }
+
+    /// Äußerer Impuls (Wind, Explosion, Werkzeug): `velocity += force / mass`, schwere Partikel bewegen sich weniger.
+    /// Je Achse höchstens `max_speed` (siehe `Simulation::max_speed`).
+    pub fn apply_force(&mut self, force: [f32; 2], max_speed: f32, materials: &MaterialTable) {
+        let mass = self.mass(materials);
+        if mass <= 0.0 { return; }
+        self.velocity[0] += force[0] / mass;
+        self.velocity[1] += force[1] / mass;
+        self.velocity = clamp_speed(self.velocity, max_speed);
+    }
+
+    fn check_way(&self, world: &World, rng: &mut impl Rng) -> Option<(f32, i32, i32)> {
+        let (own_x_pos, own_y_pos) = World::cell_of(self.position);
+        let (own_x_pos, own_y_pos) = (own_x_pos as i32, own_y_pos as i32);
 
         let can_go_down = own_y_pos > 0;
         let can_go_up = own_y_pos < (world.height - 1) as i32;
@@ -102,155 +486,161 @@ impl Particle {
         let can_go_right = own_x_pos < (world.width - 1) as i32;
 
         let mut values: Vec<(f32, i32, i32)> = vec![];
+        let mut push = |x: i32, y: i32| values.push((world.cell(x as usize, y as usize).map_or(0.0, |c| c.pressure), x, y));
 
-        if can_go_right && can_go_down {
-            let pressure = world.grid[(own_y_pos - 1) as usize][(own_x_pos + 1) as usize].2;
-            values.push((pressure, own_x_pos + 1, own_y_pos - 1));
-        }
-        if can_go_right {
-            let pressure = world.grid[(own_y_pos) as usize][(own_x_pos + 1) as usize].2;
-            values.push((pressure, own_x_pos + 1, own_y_pos));
-        }
-        if can_go_up && can_go_right {
-            let pressure = world.grid[(own_y_pos + 1) as usize][(own_x_pos + 1) as usize].2;
-            values.push((pressure, own_x_pos + 1, own_y_pos + 1));
-        }
-        if can_go_down {
-            let pressure = world.grid[(own_y_pos - 1) as usize][(own_x_pos) as usize].2;
-            values.push((pressure, own_x_pos, own_y_pos - 1));
-        }
-        if can_go_down && can_go_left {
-            let pressure = world.grid[(own_y_pos - 1) as usize][(own_x_pos - 1) as usize].2;
-            values.push((pressure, own_x_pos - 1, own_y_pos - 1));
-        }
-        if can_go_left {
-            let pressure = world.grid[(own_y_pos) as usize][(own_x_pos - 1) as usize].2;
-            values.push((pressure, own_x_pos - 1, own_y_pos));
-        }
-        if can_go_up && can_go_left {
-            let pressure = world.grid[(own_y_pos + 1) as usize][(own_x_pos - 1) as usize].2;
-            values.push((pressure, own_x_pos - 1, own_y_pos + 1));
-        }
+        if can_go_right && can_go_down { push(own_x_pos + 1, own_y_pos - 1); }
+        if can_go_right { push(own_x_pos + 1, own_y_pos); }
+        if can_go_up && can_go_right { push(own_x_pos + 1, own_y_pos + 1); }
+        if can_go_down { push(own_x_pos, own_y_pos - 1); }
+        if can_go_down && can_go_left { push(own_x_pos - 1, own_y_pos - 1); }
+        if can_go_left { push(own_x_pos - 1, own_y_pos); }
+        if can_go_up && can_go_left { push(own_x_pos - 1, own_y_pos + 1); }
 
         let min_pressure = values.iter().map(|v| v.0).fold(f32::INFINITY, |a, b| a.min(b));
         let min_options: Vec<_> = values.iter().filter(|v| v.0 == min_pressure).collect();
 
-        match min_options.choose(&mut rand::thread_rng()) {
-            Some(&&(pressure, x, y)) => Some((pressure, x, y)),
-            None => None,
-        }
+        min_options.choose(rng).map(|&&v| v)
     }
 
-    pub fn resolve_pressure(&mut self, world: &mut World) {
-        let own_x = self.position[0] as usize;
-        let own_y = self.position[1] as usize;
+    pub fn resolve_pressure(&mut self, world: &mut World, rng: &mut impl Rng) {
+        let (own_x, own_y) = World::cell_of(self.position);
         let own_pressure = world.give_pressure_on_position(own_x, own_y);
 
-        if own_pressure <= self.mass() {
+        if own_pressure <= self.mass(&world.materials) {
             return;
         }
 
-        if let Some((min_pressure, target_x, target_y)) = self.check_way(world) {
-            if min_pressure < own_pressure && target_y <= own_y as i32 {
-                if world.give_occupation_on_position(target_x as usize, target_y as usize).is_none() {
-                    world.clear_occupation_on_position(self.position);
-                    world.clear_mass_on_position(self.position);
-                    self.position[0] = target_x as f32;
-                    self.position[1] = target_y as f32;
-                    world.update_occupation_on_position(self.position, self.particle_ref);
-                    world.update_mass_on_position(self.position, self.mass());
-                }
+        if let Some((min_pressure, target_x, target_y)) = self.check_way(world, rng) {
+            if min_pressure < own_pressure && target_y <= own_y as i32
+                && world.give_occupation_on_position(target_x as usize, target_y as usize).is_none()
+            {
+                world.clear_occupation_on_position(self.position);
+                world.clear_mass_on_position(self.position);
+                self.position[0] = target_x as f32;
+                self.position[1] = target_y as f32;
+                world.update_occupation_on_position(self.position, self.particle_ref);
+                world.update_mass_on_position(self.position, self.mass(&world.materials));
             }
         }
     }
 
-    pub fn fall_down(&mut self, world: &mut World) {
-        let x = self.position[0] as i32;
-        let y = self.position[1] as i32;
+    /// Klebt das Partikel fest? Dafür braucht es links, rechts oder gegen die Schwerkraft eine Fläche (statische
+    /// Zelle oder Objekt), und die Last – eigene Masse plus die lückenlos darauf liegenden freien Partikel, mal
+    /// Schwerkraft – muss unter `adhesion` bleiben. Wächst die Last darüber, fällt es wieder.
+    pub fn adheres(&self, world: &World, gravity: [f32; 2]) -> bool {
+        let adhesion = world.materials.adhesion(self.material);
+        if adhesion <= 0.0 { return false; }
+        let (x, y) = World::cell_of(self.position);
+        if x >= world.width || y >= world.height { return false; }
 
-        if y <= 0 {
-            return;
+        let up: i32 = if gravity[1] > 0.0 { -1 } else { 1 };
+        let cell = |cx: i32, cy: i32| {
+            if cx < 0 || cy < 0 || cx >= world.width as i32 || cy >= world.height as i32 { return None; }
+            world.give_occupation_on_position(cx as usize, cy as usize)
+        };
+        let surface = [(-1, 0), (1, 0), (0, up)].into_iter()
+            .any(|(dx, dy)| matches!(cell(x as i32 + dx, y as i32 + dy), Some(ParticleRef::Static | ParticleRef::InObject(..))));
+        if !surface { return false; }
+
+        let mut load = self.mass(&world.materials);
+        let mut cy = y as i32 + up;
+        while let Some(ParticleRef::Free(..)) = cell(x as i32, cy) {
+            load += world.cell(x, cy as usize).map_or(0.0, |c| c.mass);
+            cy += up;
         }
+        load * gravity[0].hypot(gravity[1]) < adhesion
+    }
 
-        if world.give_occupation_on_position(x as usize, (y - 1) as usize).is_none() {
-            world.clear_occupation_on_position(self.position);
-            world.clear_mass_on_position(self.position);
-            self.position[1] -= 1.0;
-            world.update_occupation_on_position(self.position, self.particle_ref);
-            world.update_mass_on_position(self.position, self.mass());
+    /// Ein Feld nach unten, sonst schräg nach unten; `left_first` legt fest, welche Diagonale zuerst probiert wird.
+    pub fn fall_down(&mut self, world: &mut World, left_first: bool) {
+        let (x, y) = World::cell_of(self.position);
+        let (x, y) = (x as i32, y as i32);
+
+        // Im freien Fall bewegt schon `update_velocity` das Partikel, hier nur Aufsetzen und Abrutschen
+        if y <= 0 || self.velocity[1] != 0.0 {
             return;
         }
 
-        if x > 0 && world.give_occupation_on_position((x - 1) as usize, (y - 1) as usize).is_none() {
+        if world.give_occupation_on_position(x as usize, (y - 1) as usize).is_none() {
             world.clear_occupation_on_position(self.position);
             world.clear_mass_on_position(self.position);
-            self.position[0] -= 1.0;
             self.position[1] -= 1.0;
             world.update_occupation_on_position(self.position, self.particle_ref);
-            world.update_mass_on_position(self.position, self.mass());
+            world.update_mass_on_position(self.position, self.mass(&world.materials));
             return;
         }
 
-        if x < (world.width - 1) as i32 && world.give_occupation_on_position((x + 1) as usize, (y - 1) as usize).is_none() {
+        let sides = if left_first { [-1, 1] } else { [1, -1] };
+        for dx in sides {
+            let nx = x + dx;
+            if nx < 0 || nx >= world.width as i32 || world.give_occupation_on_position(nx as usize, (y - 1) as usize).is_some() {
+                continue;
+            }
             world.clear_occupation_on_position(self.position);
             world.clear_mass_on_position(self.position);
-            self.position[0] += 1.0;
+            self.position[0] += dx as f32;
             self.position[1] -= 1.0;
             world.update_occupation_on_position(self.position, self.particle_ref);
-            world.update_mass_on_position(self.position, self.mass());
+            world.update_mass_on_position(self.position, self.mass(&world.materials));
+            return;
         }
     }
 
-    /// Flüssigkeiten breiten sich seitlich aus wenn sie nicht fallen können
-    pub fn flow_sideways(&mut self, world: &mut World) {
-        // Nur für Flüssigkeiten (Wasser)
+    /// Flüssigkeiten ohne freien Weg nach unten fließen zur nächstgelegenen Kante, an der es tiefer geht
+    /// (bis `LIQUID_LOOKAHEAD` Zellen weit). Gleich weit: niedrigerer Druck der Nachbarzelle, dann die
+    /// Partikel-ID als fester Tie-Break. Ohne Kante und ohne Druckgefälle bleibt das Partikel liegen.
+    pub fn flow_down_gradient(&mut self, world: &mut World) {
         if self.material.is_solid() {
             return;
         }
 
-        let x = self.position[0] as i32;
-        let y = self.position[1] as i32;
-        let w = world.width as i32;
-
-        // Nur fließen wenn unten blockiert ist
-        if y > 0 && world.give_occupation_on_position(x as usize, (y - 1) as usize).is_none() {
-            return; // Kann fallen, also nicht seitlich fließen
-        }
-
-        let can_left = x > 0 && world.give_occupation_on_position((x - 1) as usize, y as usize).is_none();
-        let can_right = x < w - 1 && world.give_occupation_on_position((x + 1) as usize, y as usize).is_none();
-
-        if !can_left && !can_right {
+        let (x, y) = World::cell_of(self.position);
+        let (x, y) = (x as i32, y as i32);
+        let free = |cx: i32, cy: i32| {
+            cx >= 0 && cy >= 0 && cx < world.width as i32 && cy < world.height as i32
+                && world.give_occupation_on_position(cx as usize, cy as usize).is_none()
+        };
+        if y > 0 && free(x, y - 1) {
             return;
         }
 
-        // Bevorzuge Seite mit niedrigerem Druck
-        let pressure_left = if can_left { world.give_pressure_on_position((x - 1) as usize, y as usize) } else { f32::MAX };
-        let pressure_right = if can_right { world.give_pressure_on_position((x + 1) as usize, y as usize) } else { f32::MAX };
+        // (Abstand zur nächsten Kante, Druck der Nachbarzelle) je Richtung; `None`, wenn die Seite zu ist
+        let side = |dir: i32| -> Option<(i32, f32)> {
+            if !free(x + dir, y) {
+                return None;
+            }
+            let drop = (1..=LIQUID_LOOKAHEAD)
+                .take_while(|&k| free(x + dir * k, y))
+                .find(|&k| y > 0 && free(x + dir * k, y - 1))
+                .unwrap_or(i32::MAX);
+            Some((drop, world.give_pressure_on_position((x + dir) as usize, y as usize)))
+        };
 
-        let go_left = if can_left && can_right {
-            if pressure_left < pressure_right {
-                true
-            } else if pressure_right < pressure_left {
-                false
-            } else {
-                rand::random::<bool>() // Zufällig wenn gleich
+        let dir = match (side(-1), side(1)) {
+            (None, None) => return,
+            (Some((drop, _)), None) | (None, Some((drop, _))) if drop == i32::MAX => return,
+            (Some(_), None) => -1,
+            (None, Some(_)) => 1,
+            (Some(left), Some(right)) => {
+                if left.0 != right.0 {
+                    if left.0 < right.0 { -1 } else { 1 }
+                } else if left.1 != right.1 {
+                    if left.1 < right.1 { -1 } else { 1 }
+                } else if left.0 == i32::MAX {
+                    return;
+                } else if self.id % 2 == 0 {
+                    -1
+                } else {
+                    1
+                }
             }
-        } else {
-            can_left
         };
 
         world.clear_occupation_on_position(self.position);
         world.clear_mass_on_position(self.position);
-
-        if go_left {
-            self.position[0] -= 1.0;
-        } else {
-            self.position[0] += 1.0;
-        }
-
+        self.position[0] += dir as f32;
         world.update_occupation_on_position(self.position, self.particle_ref);
-        world.update_mass_on_position(self.position, self.mass());
+        world.update_mass_on_position(self.position, self.mass(&world.materials));
     }
 
     pub fn get_position(&self) -> [f32; 2] {
@@ -270,52 +660,182 @@ impl Particle {
         }
 
         world.update_occupation_on_position(self.position, self.particle_ref);
-        world.update_mass_on_position(self.position, self.mass());
+        world.update_mass_on_position(self.position, self.mass(&world.materials));
     }
 
-    pub fn update_velocity(&mut self, gravity: [f32; 2], world: &World) {
-        let next_y = self.position[1] + self.velocity[1] + gravity[1];
-        let check_y = if next_y < 0.0 { 0.0 } else { next_y };
+    /// Integriert die Schwerkraft und tastet den Weg Zelle für Zelle ab, erst in x entlang der eigenen Zeile,
+    /// dann in y in der Zielspalte: Ein schnelles Partikel legt mehrere Zellen pro Tick zurück, bleibt aber
+    /// direkt vor dem ersten Hindernis bzw. am Gridrand stehen. Bestehende seitliche Geschwindigkeit verliert pro
+    /// Tick `HORIZONTAL_DRAG`, bevor die Schwerkraft in beiden Achsen wirkt. Die Geschwindigkeit wird danach je Achse
+    /// auf `max_speed` begrenzt.
+    pub fn update_velocity(&mut self, gravity: [f32; 2], world: &World, max_speed: f32) {
+        let drag = self.velocity[0].signum() * HORIZONTAL_DRAG.min(self.velocity[0].abs());
+        self.velocity = clamp_speed([self.velocity[0] - drag + gravity[0], self.velocity[1] + gravity[1]], max_speed);
+        let vx = self.velocity[0];
+        let (_, y) = World::cell_of(self.position);
+        let x_target = Self::sweep(self.position[0], vx, world.width, |cx| world.give_occupation_on_position(cx, y).is_some());
+        let (column, _) = World::cell_of([x_target, self.position[1]]);
+        let y_target = Self::sweep(self.position[1], self.velocity[1], world.height, |cy| {
+            world.give_occupation_on_position(column, cy).is_some()
+        });
+        self.velocity = [x_target - self.position[0], y_target - self.position[1]];
+    }
 
-        if world.give_occupation_on_position(self.position[0] as usize, check_y as usize).is_some() {
-            self.velocity[1] = 0.0;
-        } else if next_y < 0.0 {
-            self.velocity[1] = -self.position[1];
-        } else {
-            self.velocity[1] += gravity[1];
+    /// Zielkoordinate auf einer Achse: von `start` um `delta`, höchstens bis vor die erste belegte Zelle
+    /// bzw. an den Rand `0..len`.
+    fn sweep(start: f32, delta: f32, len: usize, occupied: impl Fn(usize) -> bool) -> f32 {
+        let target = (start + delta).clamp(0.0, (len - 1) as f32);
+        let (from, _) = World::cell_of([start, 0.0]);
+        let (to, _) = World::cell_of([target, 0.0]);
+        let path: Vec<usize> = if to < from { (to..from).rev().collect() } else { (from + 1..=to).collect() };
+        match path.into_iter().find(|&c| occupied(c)) {
+            Some(c) if c < from => (c + 1) as f32,
+            Some(c) => (c - 1) as f32,
+            None => target,
+        }
+    }
+
+    /// Strahl aus einem Leck: Flüssigkeit unter Druck (mehr als `JET_MIN_DEPTH` Zellen Flüssigkeit darüber),
+    /// die seitlich eine freie Zelle hat, schießt mit einer zum Druck proportionalen Geschwindigkeit hinaus.
+    /// Sind beide Seiten frei, entscheidet wie bei `flow_down_gradient` die ID.
+    pub fn jet_out(&mut self, world: &World) {
+        if self.material.is_solid() || self.velocity[0] != 0.0 {
+            return;
+        }
+        let (x, y) = World::cell_of(self.position);
+        let depth = world.give_pressure_on_position(x, y) / self.mass(&world.materials) - 1.0;
+        if depth <= JET_MIN_DEPTH {
+            return;
         }
+
+        let free = |cx: i32| cx >= 0 && cx < world.width as i32 && world.give_occupation_on_position(cx as usize, y).is_none();
+        let dir = match (free(x as i32 - 1), free(x as i32 + 1)) {
+            (false, false) => return,
+            (true, false) => -1.0,
+            (false, true) => 1.0,
+            (true, true) => if self.id % 2 == 0 { -1.0 } else { 1.0 },
+        };
+        self.velocity[0] = dir * (depth * JET_SPEED_PER_DEPTH).min(JET_MAX_SPEED).round();
     }
 }
 
 // ============== OBJECT ==============
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+/// Faserrichtung eines Objekts (z.B. Holz). Stöße entlang der Faser werden stärker gedämpft als quer dazu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grain {
+    Isotropic,
+    Along(Axis),
+}
+
+impl Grain {
+    pub fn stress_factor(&self, impact: Axis) -> f32 {
+        match self {
+            Grain::Isotropic => 1.0,
+            Grain::Along(axis) if *axis == impact => 0.5,
+            Grain::Along(_) => 1.5,
+        }
+    }
+
+    fn rotated(self) -> Grain {
+        match self {
+            Grain::Isotropic => Grain::Isotropic,
+            Grain::Along(Axis::Horizontal) => Grain::Along(Axis::Vertical),
+            Grain::Along(Axis::Vertical) => Grain::Along(Axis::Horizontal),
+        }
+    }
+}
+
+/// Wie sich die Aufprallkraft über ein Objekt verteilt, abhängig vom Abstand zur getroffenen Kante.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ForceProfile {
+    /// 1 / (Abstand + 1): die Kraft sammelt sich an der Kante.
+    #[default]
+    Inverse,
+    /// Fällt linear von 1 an der Kante bis zur gegenüberliegenden Seite ab.
+    Linear,
+    /// Überall die volle Kraft.
+    Uniform,
+    /// e^(-k · Abstand).
+    Exponential(f32),
+}
+
+impl ForceProfile {
+    /// Anteil der Kraft in `distance` Zeilen/Spalten Abstand bei `extent` Zeilen/Spalten insgesamt.
+    pub fn factor(&self, distance: usize, extent: usize) -> f32 {
+        match *self {
+            ForceProfile::Inverse => 1.0 / (distance as f32 + 1.0),
+            ForceProfile::Linear => extent.saturating_sub(distance) as f32 / extent.max(1) as f32,
+            ForceProfile::Uniform => 1.0,
+            ForceProfile::Exponential(k) => (-k * distance as f32).exp(),
+        }
+    }
+}
+
+/// Vorlagen für `Object::new_quadrant_with` (unten links, unten rechts, oben links, oben rechts);
+/// die erste ist der Standard-Quadrant.
+pub const QUADRANT_PRESETS: [[MaterialTyp; 4]; 4] = [
+    [MaterialTyp::Holz, MaterialTyp::Stein, MaterialTyp::Metall, MaterialTyp::Sand],
+    [MaterialTyp::Stein, MaterialTyp::Stein, MaterialTyp::Holz, MaterialTyp::Holz],
+    [MaterialTyp::Metall, MaterialTyp::Eis, MaterialTyp::Eis, MaterialTyp::Metall],
+    [MaterialTyp::Beton, MaterialTyp::Sand, MaterialTyp::Sand, MaterialTyp::Beton],
+];
+
+/// Bindung zwischen zwei benachbarten Zellen eines Objekts, als (i, j)-Paar.
+type Bond = ((usize, usize), (usize, usize));
+
+#[derive(Clone)]
 pub struct Object {
     pub object_id: i32,
     pub is_destroyed: bool,
     position: [f32; 2],
     velocity: [f32; 2],
-    total_object_mass: f32,
     object_h: usize,
     object_w: usize,
     object_grid: Vec<Vec<(Particle, f32, f32)>>,
+    crack_front: Vec<Bond>,
+    crack_damage: HashMap<Bond, f32>,
+    crack_force: f32,
+    residual_velocity: f32,
+    struck: Vec<(i32, f32)>,
+    /// Der nächste Positionsschritt setzt das Objekt auf seine Unterlage; danach ruht es.
+    landing: bool,
+    grain: Grain,
+    force_profile: ForceProfile,
 }
 
 impl Object {
-    pub fn new(id: i32, object_idx: usize, position: [f32; 2], velocity: [f32; 2], material: MaterialTyp, h: usize, w: usize) -> Object {
+    /// Fehler bei `h` oder `w` gleich 0.
+    pub fn new(id: i32, position: [f32; 2], velocity: [f32; 2], material: MaterialTyp, h: usize, w: usize) -> Result<Object, String> {
+        Object::new_with(id, position, velocity, h, w, |_, _| material)
+    }
+
+    /// Objekt mit eigenem Material je Zelle: `material_at(i, j)` wird für jede Zelle (Zeile i, Spalte j) gefragt.
+    /// Fehler bei `h` oder `w` gleich 0.
+    pub fn new_with<F: Fn(usize, usize) -> MaterialTyp>(id: i32, position: [f32; 2], velocity: [f32; 2], h: usize, w: usize, material_at: F) -> Result<Object, String> {
+        if h == 0 || w == 0 {
+            return Err(format!("Objekt ohne Zellen: {}x{}", h, w));
+        }
+        Ok(Object::filled(id, position, velocity, h, w, material_at))
+    }
+
+    /// Wie `new_with` für bereits geprüfte Maße (h, w > 0).
+    fn filled<F: Fn(usize, usize) -> MaterialTyp>(id: i32, position: [f32; 2], velocity: [f32; 2], h: usize, w: usize, material_at: F) -> Object {
         let mut object_grid: Vec<Vec<(Particle, f32, f32)>> = Vec::new();
 
         for i in 0..h {
             let mut row: Vec<(Particle, f32, f32)> = Vec::new();
             for j in 0..w {
+                let material = material_at(i, j);
                 let particle_pos = [position[0] + j as f32, position[1] + i as f32];
-                let particle_ref = ParticleRef::InObject(object_idx, i, j);
-                let particle = Particle::new(
-                    id * 100 + (i * w + j) as i32,
-                    particle_pos,
-                    [0.0, 0.0],
-                    material,
-                    particle_ref,
-                );
+                let particle_ref = ParticleRef::InObject(id, i, j);
+                let particle = Particle::new(id * 100 + (i * w + j) as i32, particle_pos, [0.0, 0.0], material, particle_ref);
                 row.push((particle, 0.0, 0.0));
             }
             object_grid.push(row);
@@ -326,19 +846,42 @@ impl Object {
             is_destroyed: false,
             position,
             velocity,
-            total_object_mass: (h * w) as f32 * material.density(),
             object_h: h,
             object_w: w,
             object_grid,
+            crack_front: Vec::new(),
+            crack_damage: HashMap::new(),
+            crack_force: 0.0,
+            residual_velocity: 0.0,
+            landing: false,
+            struck: Vec::new(),
+            grain: Grain::Isotropic,
+            force_profile: ForceProfile::Inverse,
         }
     }
 
-    pub fn new_from_fragment(id: i32, object_idx: usize, fragment_data: &[([f32; 2], MaterialTyp)], velocity: [f32; 2]) -> Object {
-        let min_x = fragment_data.iter().map(|(pos, _)| pos[0] as usize).min().unwrap();
-        let max_x = fragment_data.iter().map(|(pos, _)| pos[0] as usize).max().unwrap();
-        let min_y = fragment_data.iter().map(|(pos, _)| pos[1] as usize).min().unwrap();
-        let max_y = fragment_data.iter().map(|(pos, _)| pos[1] as usize).max().unwrap();
+    /// Objekt aus Zellen (Weltposition, Material) in beliebiger Reihenfolge: Das Hüllrechteck bestimmt Anker
+    /// (linke untere Zelle) und Maße, Lücken werden Luft, Positionen werden auf ihre Zelle gesetzt.
+    /// Kleinstes gültiges Fragment ist eine einzelne Zelle (1x1-Objekt); `Simulation` macht daraus allerdings
+    /// ein freies Partikel. Fehler bei leeren `fragment_data`, nicht endlichen Positionen und zwei Einträgen in
+    /// derselben Zelle.
+    pub fn new_from_fragment(id: i32, fragment_data: &[([f32; 2], MaterialTyp)], velocity: [f32; 2]) -> Result<Object, String> {
+        if let Some((pos, _)) = fragment_data.iter().find(|(pos, _)| !pos[0].is_finite() || !pos[1].is_finite()) {
+            return Err(format!("Fragment mit ungültiger Position {:?}", pos));
+        }
+        let cells: Vec<(usize, usize)> = fragment_data.iter().map(|(pos, _)| World::cell_of(*pos)).collect();
+        let mut seen = HashSet::new();
+        if let Some(cell) = cells.iter().find(|&&cell| !seen.insert(cell)) {
+            return Err(format!("Fragment belegt Zelle {:?} doppelt", cell));
+        }
+        let (Some(min_x), Some(max_x)) = (cells.iter().map(|c| c.0).min(), cells.iter().map(|c| c.0).max()) else {
+            return Err("Fragment ohne Zellen".to_string());
+        };
+        let (Some(min_y), Some(max_y)) = (cells.iter().map(|c| c.1).min(), cells.iter().map(|c| c.1).max()) else {
+            return Err("Fragment ohne Zellen".to_string());
+        };
 
+        // min <= max, da beide aus denselben Zellen stammen
         let h = max_y - min_y + 1;
         let w = max_x - min_x + 1;
         let anchor = [min_x as f32, min_y as f32];
@@ -348,68 +891,102 @@ impl Object {
             let mut row: Vec<(Particle, f32, f32)> = Vec::new();
             for j in 0..w {
                 let particle_pos = [anchor[0] + j as f32, anchor[1] + i as f32];
-                let particle_ref = ParticleRef::InObject(object_idx, i, j);
+                let particle_ref = ParticleRef::InObject(id, i, j);
                 let particle = Particle::new(id * 100 + (i * w + j) as i32, particle_pos, [0.0, 0.0], MaterialTyp::Luft, particle_ref);
                 row.push((particle, 0.0, 0.0));
             }
             object_grid.push(row);
         }
 
-        let mut total_mass = 0.0;
-        for (world_pos, material) in fragment_data {
-            let i = (world_pos[1] as usize) - min_y;
-            let j = (world_pos[0] as usize) - min_x;
-            let particle_ref = ParticleRef::InObject(object_idx, i, j);
-            object_grid[i][j].0 = Particle::new(id * 100 + (i * w + j) as i32, *world_pos, [0.0, 0.0], *material, particle_ref);
-            total_mass += material.density();
+        for (&(x, y), (_, material)) in cells.iter().zip(fragment_data) {
+            let i = y - min_y;
+            let j = x - min_x;
+            let particle_ref = ParticleRef::InObject(id, i, j);
+            object_grid[i][j].0 = Particle::new(id * 100 + (i * w + j) as i32, [x as f32, y as f32], [0.0, 0.0], *material, particle_ref);
         }
 
-        Object {
+        Ok(Object {
             object_id: id,
             is_destroyed: false,
             position: anchor,
             velocity,
-            total_object_mass: total_mass,
             object_h: h,
             object_w: w,
             object_grid,
-        }
+            crack_front: Vec::new(),
+            crack_damage: HashMap::new(),
+            crack_force: 0.0,
+            residual_velocity: 0.0,
+            landing: false,
+            struck: Vec::new(),
+            grain: Grain::Isotropic,
+            force_profile: ForceProfile::Inverse,
+        })
     }
 
-    pub fn new_quadrant(id: i32, object_idx: usize, position: [f32; 2], velocity: [f32; 2]) -> Object {
-        let materials = [
-            [MaterialTyp::Holz, MaterialTyp::Holz, MaterialTyp::Stein, MaterialTyp::Stein],
-            [MaterialTyp::Holz, MaterialTyp::Holz, MaterialTyp::Stein, MaterialTyp::Stein],
-            [MaterialTyp::Metall, MaterialTyp::Metall, MaterialTyp::Sand, MaterialTyp::Sand],
-            [MaterialTyp::Metall, MaterialTyp::Metall, MaterialTyp::Sand, MaterialTyp::Sand],
-        ];
+    pub fn new_quadrant(id: i32, position: [f32; 2], velocity: [f32; 2]) -> Object {
+        Object::new_quadrant_with(id, position, velocity, QUADRANT_PRESETS[0])
+    }
 
-        let mut object_grid: Vec<Vec<(Particle, f32, f32)>> = Vec::new();
-        let mut total_mass = 0.0;
+    /// 4x4-Block aus vier 2x2-Vierteln in der Reihenfolge unten links, unten rechts, oben links, oben rechts.
+    pub fn new_quadrant_with(id: i32, position: [f32; 2], velocity: [f32; 2], materials: [MaterialTyp; 4]) -> Object {
+        Object::filled(id, position, velocity, 4, 4, |i, j| materials[(i / 2) * 2 + j / 2])
+    }
 
-        for i in 0..4 {
-            let mut row: Vec<(Particle, f32, f32)> = Vec::new();
-            for j in 0..4 {
-                let material = materials[i][j];
-                let particle_pos = [position[0] + j as f32, position[1] + i as f32];
-                let particle_ref = ParticleRef::InObject(object_idx, i, j);
-                let particle = Particle::new(id * 100 + (i * 4 + j) as i32, particle_pos, [0.0, 0.0], material, particle_ref);
-                total_mass += material.density();
-                row.push((particle, 0.0, 0.0));
-            }
-            object_grid.push(row);
-        }
+    /// Vergrößerte Kopie: Jede Zelle wird zu einem factor×factor-Block desselben Materials, der Anker bleibt.
+    /// Die Kopie ist noch nicht im Grid eingetragen und übernimmt keine Risse; `factor` 0 zählt wie 1.
+    /// Ohne Prüfung gegen `max_object_cells`, dafür `Simulation::scale_object`.
+    pub fn scale(&self, factor: usize) -> Object {
+        let factor = factor.max(1);
+        let (h, w) = (self.object_h * factor, self.object_w * factor);
+        Object::filled(self.object_id, self.position, self.velocity, h, w, |i, j| {
+            self.object_grid[i / factor][j / factor].0.material
+        }).with_grain(self.grain).with_force_profile(self.force_profile)
+    }
 
-        Object {
-            object_id: id,
-            is_destroyed: false,
-            position,
-            velocity,
-            total_object_mass: total_mass,
-            object_h: 4,
-            object_w: 4,
-            object_grid,
+    pub fn with_force_profile(mut self, force_profile: ForceProfile) -> Object {
+        self.force_profile = force_profile;
+        self
+    }
+
+    pub fn get_force_profile(&self) -> ForceProfile {
+        self.force_profile
+    }
+
+    pub fn with_grain(mut self, grain: Grain) -> Object {
+        self.grain = grain;
+        self
+    }
+
+    /// Vertikale Restgeschwindigkeit eines Aufpralls in diesem Tick, die nach Abzug der Bruchenergie für Fragmente
+    /// übrig bleibt; in jedem anderen Tick 0.
+    pub fn get_residual_velocity(&self) -> f32 {
+        self.residual_velocity
+    }
+
+    pub fn get_grain(&self) -> Grain {
+        self.grain
+    }
+
+    /// Anzahl der Nicht-Luft-Zellen je Material.
+    pub fn composition(&self) -> HashMap<MaterialTyp, usize> {
+        let mut counts = HashMap::new();
+        for p in self.get_object_elements().into_iter().filter(|p| p.material != MaterialTyp::Luft) {
+            *counts.entry(p.material).or_insert(0) += 1;
         }
+        counts
+    }
+
+    /// Häufigstes Material; bei Gleichstand das frühere in `MaterialTyp::ALL`, ein reines Luftobjekt ergibt Luft.
+    pub fn dominant_material(&self) -> MaterialTyp {
+        let counts = self.composition();
+        MaterialTyp::ALL.into_iter()
+            .filter_map(|material| counts.get(&material).map(|&count| (material, count)))
+            .fold(None, |best: Option<(MaterialTyp, usize)>, (material, count)| match best {
+                Some((_, best_count)) if best_count >= count => best,
+                _ => Some((material, count)),
+            })
+            .map_or(MaterialTyp::Luft, |(material, _)| material)
     }
 
     pub fn get_object_elements(&self) -> Vec<&Particle> {
@@ -420,6 +997,10 @@ impl Object {
         self.velocity
     }
 
+    pub fn get_object_position(&self) -> [f32; 2] {
+        self.position
+    }
+
     pub fn get_particle_at(&self, i: usize, j: usize) -> &Particle {
         &self.object_grid[i][j].0
     }
@@ -432,50 +1013,92 @@ impl Object {
         self.object_w
     }
 
-    pub fn calc_impact_force(&self, velocity_before_impact: f32) -> f32 {
-        self.total_object_mass * velocity_before_impact.abs()
+    pub fn calc_impact_force(&self, velocity_before_impact: f32, materials: &MaterialTable) -> f32 {
+        self.mass(materials) * velocity_before_impact.abs()
     }
 
     fn calc_dampening_factor(collisions: &[ParticleRef]) -> f32 {
         if collisions.is_empty() { return 1.0; }
         let sum: f32 = collisions.iter().map(|c| match c {
             ParticleRef::Static => 1.0,
-            ParticleRef::Free(_) => 0.4,
+            ParticleRef::Free(_, _) => 0.4,
             ParticleRef::InObject(_, _, _) => 0.6,
         }).sum();
         sum / collisions.len() as f32
     }
 
-    fn calc_bond_strength(mat_a: MaterialTyp, mat_b: MaterialTyp) -> f32 {
+    fn calc_bond_strength(mat_a: MaterialTyp, mat_b: MaterialTyp, materials: &MaterialTable) -> f32 {
         if mat_a == mat_b {
-            mat_a.binding_strength()
+            materials.binding_strength(mat_a)
         } else {
-            mat_a.binding_strength().min(mat_b.binding_strength()) * 0.5
+            materials.binding_strength(mat_a).min(materials.binding_strength(mat_b)) * 0.5
+        }
+    }
+
+    /// Kraft, die das Brechen der Bindungen aufzehrt: Summe ihrer Bindungsstärken
+    /// (über `binding_strength` bzw. materials.ron einstellbar).
+    pub fn absorbed_force(&self, bonds: &[Bond], materials: &MaterialTable) -> f32 {
+        bonds.iter()
+            .map(|&((ia, ja), (ib, jb))| Self::calc_bond_strength(self.object_grid[ia][ja].0.material, self.object_grid[ib][jb].0.material, materials))
+            .sum()
+    }
+
+    /// Schwächste durchgehende Schnittlinie, an der das Objekt von selbst zerfallen würde (z.B. für gezielten Abriss):
+    /// `(Axis::Horizontal, k)` trennt die Zeilen k-1 und k, `(Axis::Vertical, k)` die Spalten k-1 und k.
+    /// Maß ist die Summe der Bindungsstärken entlang der Linie; bei Gleichstand gewinnt horizontal, dann das kleinere k.
+    /// `None` für ein Objekt aus nur einer Zelle.
+    pub fn weakest_plane(&self, materials: &MaterialTable) -> Option<(Axis, usize)> {
+        let horizontal = (1..self.object_h).map(|k| (Axis::Horizontal, k));
+        let vertical = (1..self.object_w).map(|k| (Axis::Vertical, k));
+        horizontal.chain(vertical)
+            .map(|(axis, k)| (axis, k, self.absorbed_force(&self.plane_bonds(axis, k), materials)))
+            .fold(None, |best: Option<(Axis, usize, f32)>, candidate| match best {
+                Some(best) if best.2 <= candidate.2 => Some(best),
+                _ => Some(candidate),
+            })
+            .map(|(axis, k, _)| (axis, k))
+    }
+
+    /// Alle Bindungen, die eine Schnittlinie aus `weakest_plane` kreuzt.
+    pub fn plane_bonds(&self, axis: Axis, k: usize) -> Vec<Bond> {
+        match axis {
+            Axis::Horizontal => (0..self.object_w).map(|j| ((k - 1, j), (k, j))).collect(),
+            Axis::Vertical => (0..self.object_h).map(|i| ((i, k - 1), (i, k))).collect(),
         }
     }
 
-    pub fn check_fracture(&self, impact_force: f32, dampening_factor: f32) -> Vec<((usize, usize), (usize, usize))> {
+    pub fn check_fracture(&self, impact_force: f32, dampening_factor: f32, materials: &MaterialTable) -> Vec<((usize, usize), (usize, usize))> {
+        self.check_fracture_along(impact_force, dampening_factor, Axis::Vertical, materials)
+    }
+
+    /// Wie `check_fracture`, aber für einen Aufprall entlang `axis`: vertikal von unten (Zeile 0),
+    /// horizontal von links (Spalte 0). Die Faserrichtung des Objekts skaliert die Belastung.
+    pub fn check_fracture_along(&self, impact_force: f32, dampening_factor: f32, axis: Axis, materials: &MaterialTable) -> Vec<((usize, usize), (usize, usize))> {
         let mut broken_bonds = Vec::new();
-        let base_force = impact_force * dampening_factor;
+        let base_force = impact_force * dampening_factor * self.grain.stress_factor(axis);
 
         for i in 0..self.object_h {
             for j in 0..self.object_w {
                 let mat_a = self.object_grid[i][j].0.material;
                 if mat_a == MaterialTyp::Luft { continue; }
 
-                let row_factor = 1.0 / (i as f32 + 1.0);
+                let (distance, extent) = match axis {
+                    Axis::Vertical => (i, self.object_h),
+                    Axis::Horizontal => (j, self.object_w),
+                };
+                let row_factor = self.force_profile.factor(distance, extent);
                 let force_at_row = base_force * row_factor;
 
                 if j + 1 < self.object_w {
                     let mat_b = self.object_grid[i][j + 1].0.material;
-                    if mat_b != MaterialTyp::Luft && force_at_row > Self::calc_bond_strength(mat_a, mat_b) {
+                    if mat_b != MaterialTyp::Luft && force_at_row > Self::calc_bond_strength(mat_a, mat_b, materials) {
                         broken_bonds.push(((i, j), (i, j + 1)));
                     }
                 }
 
                 if i + 1 < self.object_h {
                     let mat_b = self.object_grid[i + 1][j].0.material;
-                    if mat_b != MaterialTyp::Luft && force_at_row > Self::calc_bond_strength(mat_a, mat_b) {
+                    if mat_b != MaterialTyp::Luft && force_at_row > Self::calc_bond_strength(mat_a, mat_b, materials) {
                         broken_bonds.push(((i, j), (i + 1, j)));
                     }
                 }
@@ -487,17 +1110,18 @@ impl Object {
     fn calc_pressure_per_column(&self, world: &World) -> Vec<f32> {
         let mut pressure_per_col = vec![0.0; self.object_w];
 
-        for j in 0..self.object_w {
+        for (j, column_pressure) in pressure_per_col.iter_mut().enumerate() {
             let top_row = self.object_h - 1;
-            let world_x = self.position[0] as usize + j;
-            let world_y = self.position[1] as usize + top_row;
+            let (anchor_x, anchor_y) = World::cell_of(self.position);
+            let world_x = anchor_x + j;
+            let world_y = anchor_y + top_row;
 
             if world_x >= world.width || world_y >= world.height { continue; }
 
             for y in (world_y + 1)..world.height {
-                let mass_at_pos = world.grid[y][world_x].1;
+                let mass_at_pos = world.cell(world_x, y).map_or(0.0, |c| c.mass);
                 if mass_at_pos > 0.0 {
-                    pressure_per_col[j] += mass_at_pos;
+                    *column_pressure += mass_at_pos;
                 }
             }
         }
@@ -508,8 +1132,8 @@ impl Object {
         let mut broken_bonds = Vec::new();
         let external_pressure = self.calc_pressure_per_column(world);
 
-        for j in 0..self.object_w {
-            let mut accumulated_pressure = external_pressure[j];
+        for (j, &column_pressure) in external_pressure.iter().enumerate() {
+            let mut accumulated_pressure = column_pressure;
 
             for i in (0..self.object_h).rev() {
                 let particle = &self.object_grid[i][j].0;
@@ -518,7 +1142,7 @@ impl Object {
                 if i > 0 {
                     let particle_below = &self.object_grid[i - 1][j].0;
                     if particle_below.material != MaterialTyp::Luft {
-                        let bond_strength = Self::calc_bond_strength(particle.material, particle_below.material);
+                        let bond_strength = Self::calc_bond_strength(particle.material, particle_below.material, &world.materials);
                         if accumulated_pressure > bond_strength {
                             broken_bonds.push(((i - 1, j), (i, j)));
                         }
@@ -528,23 +1152,46 @@ impl Object {
                 if j + 1 < self.object_w {
                     let particle_right = &self.object_grid[i][j + 1].0;
                     if particle_right.material != MaterialTyp::Luft {
-                        let bond_strength = Self::calc_bond_strength(particle.material, particle_right.material);
+                        let bond_strength = Self::calc_bond_strength(particle.material, particle_right.material, &world.materials);
                         if accumulated_pressure > bond_strength {
                             broken_bonds.push(((i, j), (i, j + 1)));
                         }
                     }
                 }
 
-                accumulated_pressure += particle.mass();
+                accumulated_pressure += particle.mass(&world.materials);
             }
         }
         broken_bonds
     }
 
-    pub fn find_fragments(&self, broken_bonds: &[((usize, usize), (usize, usize))]) -> Vec<Vec<(usize, usize)>> {
-        let mut parent: Vec<usize> = (0..self.object_h * self.object_w).collect();
-
-        let to_index = |i: usize, j: usize| i * self.object_w + j;
+    /// Bruchbild eines Aufpralls mit dieser Kraft, ohne das Objekt zu verändern: alle überlasteten Bindungen
+    /// auf einmal gebrochen. Ein echter Aufprall lässt stattdessen einen Riss laufen, der schon beim ersten
+    /// Durchreißen teilt; die Vorschau zeigt also die feinste mögliche Zerlegung.
+    pub fn predict_fragments(&self, impact_force: f32, dampening: f32, materials: &MaterialTable) -> Vec<Vec<(usize, usize)>> {
+        self.find_fragments(&self.check_fracture(impact_force, dampening, materials))
+    }
+
+    /// Fragmente eines senkrechten Schnitts durch die Mitte (linke Hälfte abgerundet); wie `find_fragments`.
+    pub fn split_vertical(&self) -> Vec<Vec<(usize, usize)>> {
+        let mid = self.object_w / 2;
+        if mid == 0 {
+            return self.find_fragments(&[]);
+        }
+        let cut: Vec<Bond> = (0..self.object_h).map(|i| ((i, mid - 1), (i, mid))).collect();
+        self.find_fragments(&cut)
+    }
+
+    /// Zerlegt das Objekt in zusammenhängende Teile.
+    ///
+    /// Adjazenzmodell: zwei Nicht-Luft-Zellen hängen nur über ihre vier direkten Nachbarn zusammen
+    /// (rechts/links, oben/unten), nie diagonal. Eine Bindung gilt als gebrochen, egal in welcher
+    /// Reihenfolge ihre beiden Zellen in `broken_bonds` stehen. Luft-Zellen gehören zu keinem Fragment.
+    /// Die Fragmente sind nach ihrer ersten Zelle sortiert, die Zellen darin zeilenweise.
+    pub fn find_fragments(&self, broken_bonds: &[Bond]) -> Vec<Vec<(usize, usize)>> {
+        let mut parent: Vec<usize> = (0..self.object_h * self.object_w).collect();
+
+        let to_index = |i: usize, j: usize| i * self.object_w + j;
 
         fn find(parent: &mut [usize], i: usize) -> usize {
             if parent[i] != i {
@@ -573,14 +1220,14 @@ impl Object {
         }
 
         for bond in &all_bonds {
-            if !broken_bonds.contains(bond) {
+            let reversed = (bond.1, bond.0);
+            if !broken_bonds.contains(bond) && !broken_bonds.contains(&reversed) {
                 let idx_a = to_index(bond.0.0, bond.0.1);
                 let idx_b = to_index(bond.1.0, bond.1.1);
                 union(&mut parent, idx_a, idx_b);
             }
         }
 
-        use std::collections::HashMap;
         let mut fragments_map: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
         for i in 0..self.object_h {
             for j in 0..self.object_w {
@@ -589,43 +1236,220 @@ impl Object {
                 fragments_map.entry(root).or_default().push((i, j));
             }
         }
-        fragments_map.into_values().collect()
+        let mut fragments: Vec<Vec<(usize, usize)>> = fragments_map.into_values().collect();
+        fragments.sort();
+        fragments
+    }
+
+    /// Startet (oder verstärkt) einen Riss an einer Bindung. Liefert Fragmente, falls schon diese eine Bindung das Objekt teilt.
+    pub fn seed_crack(&mut self, seed: Bond, force: f32) -> Option<Vec<Vec<(usize, usize)>>> {
+        self.crack_force = self.crack_force.max(force);
+        if !self.crack_front.contains(&seed) {
+            self.crack_front.push(seed);
+        }
+        self.crack_damage.remove(&seed);
+        self.split_along_crack()
+    }
+
+    /// Zwei Bindungen liegen nebeneinander, wenn ihre Mittelpunkte höchstens eine Zelle auseinander liegen.
+    fn bonds_adjacent(a: Bond, b: Bond) -> bool {
+        let mid_a = (a.0.0 + a.1.0, a.0.1 + a.1.1);
+        let mid_b = (b.0.0 + b.1.0, b.0.1 + b.1.1);
+        a != b && mid_a.0.abs_diff(mid_b.0) <= 2 && mid_a.1.abs_diff(mid_b.1) <= 2
+    }
+
+    /// Ein Tick Rissfortschritt: überlastete Nachbarbindungen der Rissfront sammeln Schaden
+    /// (je nach Sprödigkeit) und brechen bei 1.0. Liefert Fragmente, sobald das Objekt durchgerissen ist.
+    /// Sammelt in einem Tick keine Bindung Schaden (z.B. bei zähem Material), kommt der Riss zum Stillstand
+    /// und Front, Schaden und Kraft werden verworfen.
+    pub fn propagate_crack(&mut self, materials: &MaterialTable) -> Option<Vec<Vec<(usize, usize)>>> {
+        if self.crack_front.is_empty() {
+            return None;
+        }
+
+        let stressed = self.check_fracture(self.crack_force, 1.0, materials);
+        let mut newly_broken = Vec::new();
+        let mut growing = false;
+
+        for bond in stressed {
+            if self.crack_front.contains(&bond) { continue; }
+            if !self.crack_front.iter().any(|&c| Self::bonds_adjacent(c, bond)) { continue; }
+
+            let mat_a = self.object_grid[bond.0.0][bond.0.1].0.material;
+            let mat_b = self.object_grid[bond.1.0][bond.1.1].0.material;
+            let rate = (materials.brittleness(mat_a) + materials.brittleness(mat_b)) / 2.0;
+            if rate <= 0.0 { continue; }
+
+            growing = true;
+            let damage = self.crack_damage.entry(bond).or_insert(0.0);
+            *damage += rate;
+            if *damage >= 1.0 {
+                newly_broken.push(bond);
+            }
+        }
+
+        for bond in newly_broken {
+            self.crack_damage.remove(&bond);
+            self.crack_front.push(bond);
+        }
+        let fragments = self.split_along_crack();
+        if fragments.is_none() && !growing {
+            self.clear_crack();
+        }
+        fragments
     }
 
-    pub fn update_object_velocity(&mut self, gravity: [f32; 2], world: &World) -> Option<Vec<Vec<(usize, usize)>>> {
+    fn split_along_crack(&mut self) -> Option<Vec<Vec<(usize, usize)>>> {
+        let fragments = self.find_fragments(&self.crack_front);
+        if fragments.len() > 1 {
+            self.clear_crack();
+            return Some(fragments);
+        }
+        None
+    }
+
+    fn clear_crack(&mut self) {
+        self.crack_front.clear();
+        self.crack_damage.clear();
+        self.crack_force = 0.0;
+    }
+
+    /// Schwerkraft, Aufprall und Bruch für einen Schritt; die Geschwindigkeit bleibt je Achse unter `max_speed`.
+    pub fn update_object_velocity(&mut self, gravity: [f32; 2], world: &World, max_speed: f32) -> Option<Vec<Vec<(usize, usize)>>> {
+        // Die Restgeschwindigkeit gilt nur für Brüche im Tick des Aufpralls, nicht für später durchlaufende Risse
+        self.residual_velocity = 0.0;
+        self.velocity = clamp_speed(self.velocity, max_speed);
         let next_y = self.position[1] + self.velocity[1] + gravity[1];
-        let check_y = if next_y < 0.0 { 0.0 } else { next_y };
+
+        // Alle Zeilen zwischen Unterkante und Ziel prüfen, damit schnelle Objekte nichts durchschlagen.
+        // Zeilen der Unterkante selbst gehören zum Objekt und zählen nicht.
+        let (_, bottom) = World::cell_of(self.position);
+        let (_, target) = World::cell_of([self.position[0], next_y]);
 
         let mut collisions: Vec<ParticleRef> = Vec::new();
-        for j in 0..self.object_w {
-            let check_x = (self.position[0] + j as f32) as usize;
-            if let Some(particle_ref) = world.give_occupation_on_position(check_x, check_y as usize) {
-                collisions.push(particle_ref);
+        let mut rest_y = bottom;
+        for y in (target..bottom).rev() {
+            for j in 0..self.object_w {
+                let (check_x, _) = World::cell_of([self.position[0] + j as f32, 0.0]);
+                if let Some(particle_ref) = world.give_occupation_on_position(check_x, y) {
+                    collisions.push(particle_ref);
+                }
+            }
+            if !collisions.is_empty() {
+                rest_y = y + 1;
+                break;
             }
         }
 
         if !collisions.is_empty() {
+            // Direkt auf das Hindernis absenken, z.B. auf y = 1 über dem statischen Boden
             let velocity_before = self.velocity[1];
-            self.velocity[1] = 0.0;
+            self.velocity[1] = rest_y as f32 - self.position[1];
+            self.landing = true;
 
             if velocity_before != 0.0 {
-                let impact_force = self.calc_impact_force(velocity_before);
+                let impact_force = self.calc_impact_force(velocity_before, &world.materials);
                 let dampening = Self::calc_dampening_factor(&collisions);
-                let broken_bonds = self.check_fracture(impact_force, dampening);
+                let broken_bonds = self.check_fracture(impact_force, dampening, &world.materials);
 
-                if !broken_bonds.is_empty() {
-                    return Some(self.find_fragments(&broken_bonds));
+                // Getroffene Objekte teilen sich die Aufprallkraft
+                let mut struck: Vec<i32> = collisions.iter()
+                    .filter_map(|c| match c {
+                        ParticleRef::InObject(id, _, _) if *id != self.object_id => Some(*id),
+                        _ => None,
+                    })
+                    .collect();
+                struck.sort_unstable();
+                struck.dedup();
+                let share = impact_force * dampening / struck.len().max(1) as f32;
+                self.struck = struck.into_iter().map(|id| (id, share)).collect();
+
+                // Was die Bindungen nicht aufzehren, bleibt den Fragmenten als Geschwindigkeit
+                let residual_force = (impact_force * dampening - self.absorbed_force(&broken_bonds, &world.materials)).max(0.0);
+                self.residual_velocity = if broken_bonds.is_empty() { 0.0 } else { velocity_before.signum() * residual_force / self.mass(&world.materials) };
+
+                if let Some(&seed) = broken_bonds.first() {
+                    return self.seed_crack(seed, impact_force * dampening);
                 }
             }
         } else if next_y < 0.0 {
+            // Ohne Boden ist die unterste Gridzeile die Unterlage; der Anker wird nie negativ
             self.velocity[1] = -self.position[1];
+            self.landing = true;
         } else {
             self.velocity[1] += gravity[1];
+            self.velocity = clamp_speed(self.velocity, max_speed);
         }
         None
     }
 
+    /// Beim letzten Aufprall getroffene Objekte als (object_id, Kraft); leert die Liste.
+    pub fn take_struck_objects(&mut self) -> Vec<(i32, f32)> {
+        std::mem::take(&mut self.struck)
+    }
+
+    /// Stoß von oben durch ein anderes Objekt: Der Block wird gegen seine Unterlage gedrückt,
+    /// daher wird wie bei einem Aufprall von unten geprüft. Überlastete Bindungen starten einen Riss.
+    pub fn receive_impact(&mut self, force: f32, materials: &MaterialTable) -> Option<Vec<Vec<(usize, usize)>>> {
+        let broken_bonds = self.check_fracture(force, 1.0, materials);
+        let &seed = broken_bonds.first()?;
+        self.seed_crack(seed, force)
+    }
+
+    /// Macht die Zielzellen des nächsten Positionsschritts frei: Freie Partikel darin werden in ihrer Spalte
+    /// nach oben aus dem Weg geschoben. Blockieren Statik, ein anderes Objekt oder ein Partikel ohne Ausweichplatz,
+    /// entfällt erst der seitliche Anteil der Bewegung und, wenn das nicht reicht, die ganze.
+    pub fn make_room(&mut self, world: &mut World, particles: &mut [Particle]) {
+        for velocity in [self.velocity, [0.0, self.velocity[1]]] {
+            if velocity == [0.0, 0.0] { break; }
+            let Some(moves) = self.plan_room(world, particles, velocity) else { continue; };
+
+            self.velocity = velocity;
+            for (idx, (x, y)) in moves {
+                let p = &mut particles[idx];
+                world.clear_occupation_on_position(p.position);
+                world.clear_mass_on_position(p.position);
+                p.position = [x as f32, y as f32];
+                p.velocity = [0.0, 0.0];
+                world.update_occupation_on_position(p.position, p.particle_ref);
+                world.update_mass_on_position(p.position, p.mass(&world.materials));
+            }
+            return;
+        }
+        self.velocity = [0.0, 0.0];
+    }
+
+    /// Nötige Partikelverschiebungen (Index, neue Zelle) für einen Schritt um `velocity`; `None`, wenn er blockiert ist.
+    fn plan_room(&self, world: &World, particles: &[Particle], velocity: [f32; 2]) -> Option<Vec<(usize, (usize, usize))>> {
+        let mut target = Vec::new();
+        for row in &self.object_grid {
+            for (p, _, _) in row.iter().filter(|(p, _, _)| p.material != MaterialTyp::Luft) {
+                let (x, y) = World::cell_of([p.position[0] + velocity[0], p.position[1] + velocity[1]]);
+                if x >= world.width || y >= world.height { return None; }
+                target.push((x, y));
+            }
+        }
+
+        let mut taken: HashSet<(usize, usize)> = target.iter().copied().collect();
+        let mut moves = Vec::new();
+        for &(x, y) in &target {
+            match world.give_occupation_on_position(x, y) {
+                None => {}
+                Some(ParticleRef::InObject(id, _, _)) if id == self.object_id => {}
+                Some(particle_ref @ ParticleRef::Free(idx, _)) if particles.get(idx).is_some_and(|p| p.particle_ref == particle_ref) => {
+                    let free_y = (y + 1..world.height)
+                        .find(|&yy| !taken.contains(&(x, yy)) && world.give_occupation_on_position(x, yy).is_none())?;
+                    taken.insert((x, free_y));
+                    moves.push((idx, (x, free_y)));
+                }
+                _ => return None,
+            }
+        }
+        Some(moves)
+    }
+
     pub fn update_object_position(&mut self, world: &mut World) {
+        let landing = std::mem::take(&mut self.landing);
         if self.velocity[0] == 0.0 && self.velocity[1] == 0.0 {
             return;
         }
@@ -648,8 +1472,325 @@ impl Object {
                 if self.object_grid[i][j].0.material != MaterialTyp::Luft {
                     let p = &self.object_grid[i][j].0;
                     world.update_occupation_on_position(p.position, p.particle_ref);
-                    world.update_mass_on_position(p.position, p.mass());
+                    world.update_mass_on_position(p.position, p.mass(&world.materials));
+                }
+            }
+        }
+
+        if landing {
+            self.velocity[1] = 0.0;
+        }
+    }
+
+    /// Weltzellen direkt unter der jeweils untersten Materialzelle jeder Spalte.
+    pub fn cells_below(&self) -> Vec<(usize, usize)> {
+        let mut cells = Vec::new();
+        for j in 0..self.object_w {
+            for i in 0..self.object_h {
+                let particle = &self.object_grid[i][j].0;
+                if particle.material == MaterialTyp::Luft { continue; }
+                let (x, y) = World::cell_of(particle.position);
+                if y > 0 {
+                    cells.push((x, y - 1));
+                }
+                break;
+            }
+        }
+        cells
+    }
+
+    /// Liegt das Objekt auf? Wahr, sobald unter der untersten Materialzelle einer Spalte der Gridboden oder eine
+    /// belegte Zelle (statisch, anderes Objekt, freies Partikel) liegt. Anders als Geschwindigkeit 0 gilt das
+    /// nicht für den Scheitelpunkt eines Wurfs.
+    pub fn is_supported(&self, world: &World) -> bool {
+        (0..self.object_w).any(|j| {
+            let Some(particle) = (0..self.object_h).map(|i| &self.object_grid[i][j].0).find(|p| p.material != MaterialTyp::Luft) else {
+                return false;
+            };
+            let (x, y) = World::cell_of(particle.position);
+            if y == 0 {
+                return true;
+            }
+            if x >= world.width || y > world.height {
+                return false;
+            }
+            match world.give_occupation_on_position(x, y - 1) {
+                None => false,
+                Some(ParticleRef::InObject(id, _, _)) => id != self.object_id,
+                Some(_) => true,
+            }
+        })
+    }
+
+    /// Bremst die horizontale Bewegung eines aufliegenden Objekts um `friction * |g|` pro Tick.
+    pub fn apply_friction(&mut self, friction: f32, gravity: [f32; 2]) {
+        let decel = friction * gravity[1].abs();
+        if self.velocity[0].abs() <= decel {
+            self.velocity[0] = 0.0;
+        } else {
+            self.velocity[0] -= decel * self.velocity[0].signum();
+        }
+    }
+
+    /// Vergibt eine neue `object_id` und trägt sie in alle Zellreferenzen ein, ohne das Grid anzufassen.
+    fn renumber(&mut self, object_id: i32) {
+        self.object_id = object_id;
+        for (i, row) in self.object_grid.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                cell.0.particle_ref = ParticleRef::InObject(object_id, i, j);
+            }
+        }
+    }
+
+    /// Setzt den Anker neu und zieht die Zellpositionen nach, ohne das Grid anzufassen.
+    fn move_anchor(&mut self, anchor: [f32; 2]) {
+        self.position = anchor;
+        for i in 0..self.object_h {
+            for j in 0..self.object_w {
+                self.object_grid[i][j].0.position = [anchor[0] + j as f32, anchor[1] + i as f32];
+            }
+        }
+    }
+
+    /// Versetzt das Objekt an einen neuen Anker und hält es dort an. Liegt das Ziel außerhalb oder ist
+    /// belegt, bleibt das Objekt unverändert und es wird `false` geliefert.
+    pub fn relocate(&mut self, new_anchor: [f32; 2], world: &mut World) -> bool {
+        let moved = self.set_position(world, new_anchor).is_ok();
+        if moved {
+            self.velocity = [0.0, 0.0];
+        }
+        moved
+    }
+
+    /// Setzt den Anker und trägt das Objekt im Grid um, die Geschwindigkeit bleibt. Ragt es am Ziel
+    /// aus dem Grid oder überdeckt fremde Zellen, bleiben Objekt und Grid unverändert.
+    pub fn set_position(&mut self, world: &mut World, new_anchor: [f32; 2]) -> Result<(), String> {
+        self.clear_from_world(world);
+        let old_anchor = self.position;
+        self.move_anchor(new_anchor);
+
+        let fits = self.fits(world);
+        if !fits {
+            self.move_anchor(old_anchor);
+        }
+        self.register_in_world(world);
+        if fits { Ok(()) } else { Err(format!("Objekt passt nicht an {:?}: außerhalb des Grids oder belegt", new_anchor)) }
+    }
+
+    /// Gitterkoordinaten (i, j) der Objektzelle auf Weltzelle (x, y); `None` bei Luft, außerhalb oder zerstört.
+    /// Rechnet nur mit dem Anker und ist damit unabhängig von der Belegung im Grid.
+    pub fn contains_world_cell(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        if self.is_destroyed || self.position[0] < 0.0 || self.position[1] < 0.0 {
+            return None;
+        }
+        let (anchor_x, anchor_y) = World::cell_of(self.position);
+        let j = x.checked_sub(anchor_x)?;
+        let i = y.checked_sub(anchor_y)?;
+        if i >= self.object_h || j >= self.object_w {
+            return None;
+        }
+
+        let particle = &self.object_grid[i][j].0;
+        (particle.material != MaterialTyp::Luft && World::cell_of(particle.position) == (x, y)).then_some((i, j))
+    }
+
+    /// Höhlt das Objekt aus: Zellen, deren acht Nachbarn alle Material dieses Objekts sind, werden zu Luft.
+    /// Übrig bleibt eine Wand von einer Zelle, in der sich z.B. Wasser sammeln kann.
+    pub fn hollow(&mut self, world: &mut World) {
+        let solid = |i: usize, j: usize| self.object_grid[i][j].0.material != MaterialTyp::Luft;
+        let mut interior = Vec::new();
+        for i in 1..self.object_h.saturating_sub(1) {
+            for j in 1..self.object_w.saturating_sub(1) {
+                let surrounded = (i - 1..=i + 1).all(|ni| (j - 1..=j + 1).all(|nj| solid(ni, nj)));
+                if surrounded {
+                    interior.push((i, j));
+                }
+            }
+        }
+
+        for (i, j) in interior {
+            self.set_material_at(i, j, MaterialTyp::Luft, world);
+        }
+    }
+
+    /// Schlägt die Zelle (i, j) heraus (Spitzhacke): Sie wird Luft, Masse und Grid werden nachgeführt. Liefert
+    /// Position und Material für ein freies Partikel an ihrer Stelle, `None` bei Luft oder außerhalb des Objekts.
+    /// Ob das Objekt dabei zerfällt, prüft `Simulation::chip_object`.
+    pub fn chip(&mut self, i: usize, j: usize, world: &mut World) -> Option<([f32; 2], MaterialTyp)> {
+        let particle = &self.object_grid.get(i)?.get(j)?.0;
+        let (position, material) = (particle.position, particle.material);
+        if material == MaterialTyp::Luft || !self.set_material_at(i, j, MaterialTyp::Luft, world) {
+            return None;
+        }
+        Some((position, material))
+    }
+
+    /// Kopie mit denselben Zellmaterialien samt Luftlöchern unter `new_id` am Anker `new_anchor`: ruhend, ohne Riss,
+    /// mit neuen Partikel-IDs und Referenzen und gleich ins Grid eingetragen. `new_id` darf noch kein Objekt im Grid
+    /// tragen. Fehler, wenn die Kopie aus dem Grid ragt oder etwas überdeckt; das Grid bleibt dann unverändert.
+    pub fn clone_at(&self, new_id: i32, new_anchor: [f32; 2], world: &mut World) -> Result<Object, String> {
+        let mut clone = self.clone();
+        clone.renumber(new_id);
+        for (k, cell) in clone.object_grid.iter_mut().flatten().enumerate() {
+            cell.0.id = new_id * 100 + k as i32;
+            cell.0.velocity = [0.0, 0.0];
+        }
+        clone.move_anchor(new_anchor);
+        clone.velocity = [0.0, 0.0];
+        clone.crack_front.clear();
+        clone.crack_damage.clear();
+        clone.crack_force = 0.0;
+        clone.residual_velocity = 0.0;
+        clone.struck.clear();
+        clone.landing = false;
+
+        if !clone.fits(world) {
+            return Err(format!("Kopie bei {:?} ragt aus dem Grid oder überdeckt etwas", new_anchor));
+        }
+        clone.register_in_world(world);
+        Ok(clone)
+    }
+
+    /// Schneidet Randzeilen und -spalten aus reiner Luft ab (z.B. nach `set_material_at`), verschiebt den Anker
+    /// entsprechend und trägt die neuen (i, j)-Referenzen ins Grid ein; die Materialzellen bleiben, wo sie sind.
+    /// Laufende Risse werden verworfen. Liefert `false`, wenn es nichts abzuschneiden gab oder nur Luft übrig ist.
+    pub fn trim(&mut self, world: &mut World) -> bool {
+        let solid = |i: usize, j: usize| self.object_grid[i][j].0.material != MaterialTyp::Luft;
+        let rows: Vec<usize> = (0..self.object_h).filter(|&i| (0..self.object_w).any(|j| solid(i, j))).collect();
+        let cols: Vec<usize> = (0..self.object_w).filter(|&j| (0..self.object_h).any(|i| solid(i, j))).collect();
+        let (Some(&i0), Some(&i1), Some(&j0), Some(&j1)) = (rows.first(), rows.last(), cols.first(), cols.last()) else {
+            return false;
+        };
+        if (i0, i1, j0, j1) == (0, self.object_h - 1, 0, self.object_w - 1) {
+            return false;
+        }
+
+        self.clear_from_world(world);
+        self.object_grid = self.object_grid[i0..=i1].iter().map(|row| row[j0..=j1].to_vec()).collect();
+        self.object_h = i1 - i0 + 1;
+        self.object_w = j1 - j0 + 1;
+        self.position = [self.position[0] + j0 as f32, self.position[1] + i0 as f32];
+        self.renumber(self.object_id);
+        self.crack_front.clear();
+        self.crack_damage.clear();
+        self.crack_force = 0.0;
+        self.register_in_world(world);
+        true
+    }
+
+    /// Färbt die Zelle (i, j) in ein anderes Material um und hält Masse und Grid aktuell. Wird aus Luft
+    /// Material, muss die Weltzelle frei sein; wird Material zu Luft, verlässt die Zelle das Grid und
+    /// laufende Risse an ihr werden verworfen. Liefert `false`, wenn nichts geändert wurde.
+    pub fn set_material_at(&mut self, i: usize, j: usize, material: MaterialTyp, world: &mut World) -> bool {
+        if i >= self.object_h || j >= self.object_w {
+            return false;
+        }
+        let particle = &self.object_grid[i][j].0;
+        if particle.material == material {
+            return false;
+        }
+        let (x, y) = World::cell_of(particle.position);
+        let in_bounds = particle.position[0] >= 0.0 && particle.position[1] >= 0.0 && x < world.width && y < world.height;
+
+        // Luftzellen stehen nicht im Grid; dort kann ein fremdes Partikel liegen
+        let filling = particle.material == MaterialTyp::Luft;
+        if filling && (!in_bounds || world.give_occupation_on_position(x, y).is_some()) {
+            return false;
+        }
+
+        self.object_grid[i][j].0.material = material;
+        let particle = &self.object_grid[i][j].0;
+        if material == MaterialTyp::Luft {
+            world.clear_occupation_on_position(particle.position);
+            world.clear_mass_on_position(particle.position);
+            self.crack_front.retain(|&(a, b)| a != (i, j) && b != (i, j));
+            self.crack_damage.retain(|&(a, b), _| a != (i, j) && b != (i, j));
+        } else {
+            world.update_occupation_on_position(particle.position, particle.particle_ref);
+            world.update_mass_on_position(particle.position, particle.mass(&world.materials));
+        }
+
+        true
+    }
+
+    /// Gesamtmasse aller Nicht-Luft-Zellen.
+    pub fn mass(&self, materials: &MaterialTable) -> f32 {
+        self.get_object_elements().iter()
+            .filter(|p| p.material != MaterialTyp::Luft)
+            .map(|p| p.mass(materials))
+            .sum()
+    }
+
+    /// Massenschwerpunkt aller Nicht-Luft-Zellen in Weltkoordinaten.
+    pub fn center_of_mass(&self, materials: &MaterialTable) -> [f32; 2] {
+        let mut sum = [0.0, 0.0];
+        let mut total = 0.0;
+        for p in self.get_object_elements() {
+            if p.material == MaterialTyp::Luft { continue; }
+            sum[0] += p.position[0] * p.mass(materials);
+            sum[1] += p.position[1] * p.mass(materials);
+            total += p.mass(materials);
+        }
+        if total == 0.0 {
+            return self.position;
+        }
+        [sum[0] / total, sum[1] / total]
+    }
+
+    /// Neue Gitterkoordinaten einer Zelle (i, j) nach einer Vierteldrehung eines h×w-Gitters.
+    pub fn rotated_cell(h: usize, w: usize, i: usize, j: usize, clockwise: bool) -> (usize, usize) {
+        if clockwise { (w - 1 - j, i) } else { (j, h - 1 - i) }
+    }
+
+    /// Dreht das Objektgitter um 90° um den Anker, ohne das Grid anzufassen. Ein laufender Riss wird verworfen.
+    pub fn rotate_quarter(&mut self, clockwise: bool) {
+        let (h, w) = (self.object_h, self.object_w);
+        let object_id = self.object_id;
+
+        self.object_grid = (0..w).map(|ni| {
+            (0..h).map(|nj| {
+                let (i, j) = if clockwise { (nj, w - 1 - ni) } else { (h - 1 - nj, ni) };
+                let mut cell = self.object_grid[i][j].clone();
+                cell.0.particle_ref = ParticleRef::InObject(object_id, ni, nj);
+                cell
+            }).collect()
+        }).collect();
+
+        self.object_h = w;
+        self.object_w = h;
+        self.crack_front.clear();
+        self.crack_damage.clear();
+        self.crack_force = 0.0;
+        self.residual_velocity = 0.0;
+        self.grain = self.grain.rotated();
+        self.move_anchor(self.position);
+    }
+
+    /// Passen alle Materialzellen ins Grid, ohne etwas anderes zu überdecken?
+    pub fn fits(&self, world: &World) -> bool {
+        self.get_object_elements().into_iter()
+            .filter(|p| p.material != MaterialTyp::Luft)
+            .all(|p| {
+                if p.position[0] < -CELL_EPSILON || p.position[1] < -CELL_EPSILON {
+                    return false;
+                }
+                let (x, y) = World::cell_of(p.position);
+                if x >= world.width || y >= world.height {
+                    return false;
                 }
+                match world.give_occupation_on_position(x, y) {
+                    None => true,
+                    Some(ParticleRef::InObject(id, _, _)) => id == self.object_id,
+                    Some(_) => false,
+                }
+            })
+    }
+
+    pub fn register_in_world(&self, world: &mut World) {
+        for p in self.get_object_elements() {
+            if p.material != MaterialTyp::Luft {
+                world.update_occupation_on_position(p.position, p.particle_ref);
+                world.update_mass_on_position(p.position, p.mass(&world.materials));
             }
         }
     }
@@ -675,10 +1816,25 @@ impl Object {
 
 // ============== WORLD ==============
 
+/// Toleranz für Rundungsfehler bei der Umrechnung von Positionen in Zellen, siehe `World::cell_of`.
+pub const CELL_EPSILON: f32 = 1e-3;
+
+/// Zustand einer Gridzelle, siehe `World::cell`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellView {
+    pub occupant: Option<ParticleRef>,
+    pub mass: f32,
+    /// Druck aus dem letzten `calc_pressure_along`.
+    pub pressure: f32,
+}
+
+#[derive(Clone)]
 pub struct World {
     pub height: usize,
     pub width: usize,
     pub grid: Vec<Vec<(Option<ParticleRef>, f32, f32)>>,
+    /// Materialwerte dieser Welt, siehe `Simulation::set_materials`.
+    pub materials: MaterialTable,
 }
 
 impl World {
@@ -687,6 +1843,7 @@ impl World {
             height: h,
             width: w,
             grid: vec![vec![(None, 0.0, 0.0); w]; h],
+            materials: MaterialTable::default(),
         }
     }
 
@@ -694,49 +1851,4235 @@ impl World {
         self.grid[y][x].2
     }
 
+    /// Gesamter Zustand der Zelle (x, y); `None` außerhalb des Grids.
+    pub fn cell(&self, x: usize, y: usize) -> Option<CellView> {
+        let &(occupant, mass, pressure) = self.grid.get(y)?.get(x)?;
+        Some(CellView { occupant, mass, pressure })
+    }
+
     pub fn give_occupation_on_position(&self, x: usize, y: usize) -> Option<ParticleRef> {
         self.grid[y][x].0
     }
 
+    /// Einzige Umrechnung Position → Zelle: abgerundet, wobei Werte bis `CELL_EPSILON` unter einer ganzen
+    /// Zahl schon zu deren Zelle zählen (3.9999 → 4). Negative Koordinaten landen in Zelle 0.
+    pub fn cell_of(pos: [f32; 2]) -> (usize, usize) {
+        let cell = |v: f32| (v + CELL_EPSILON).floor().max(0.0) as usize;
+        (cell(pos[0]), cell(pos[1]))
+    }
+
     pub fn update_mass_on_position(&mut self, pos: [f32; 2], mass: f32) {
-        let x = pos[0] as usize;
-        let y = pos[1] as usize;
+        let (x, y) = World::cell_of(pos);
         if x < self.width && y < self.height {
             self.grid[y][x].1 = mass;
         }
     }
 
     pub fn update_occupation_on_position(&mut self, pos: [f32; 2], particle_ref: ParticleRef) {
-        let x = pos[0] as usize;
-        let y = pos[1] as usize;
+        let (x, y) = World::cell_of(pos);
         if x < self.width && y < self.height {
             self.grid[y][x].0 = Some(particle_ref);
         }
     }
 
     pub fn clear_occupation_on_position(&mut self, pos: [f32; 2]) {
-        let x = pos[0] as usize;
-        let y = pos[1] as usize;
+        let (x, y) = World::cell_of(pos);
         if x < self.width && y < self.height {
             self.grid[y][x].0 = None;
         }
     }
 
     pub fn clear_mass_on_position(&mut self, pos: [f32; 2]) {
-        let x = pos[0] as usize;
-        let y = pos[1] as usize;
+        let (x, y) = World::cell_of(pos);
         if x < self.width && y < self.height {
             self.grid[y][x].1 = 0.0;
         }
     }
 
+    /// Höchste belegte Zelle der Spalte x (von oben gesucht), `None` bei leerer oder ungültiger Spalte.
+    pub fn column_surface(&self, x: usize) -> Option<usize> {
+        if x >= self.width {
+            return None;
+        }
+        (0..self.height).rev().find(|&y| self.grid[y][x].0.is_some())
+    }
+
+    /// Höhe der Spalte x: Zeile über der obersten belegten Zelle, 0 für leere oder ungültige Spalten.
+    pub fn column_height(&self, x: usize) -> usize {
+        self.column_surface(x).map_or(0, |y| y + 1)
+    }
+
+    /// Höhenkarte mit einem Grauwert je Spalte: `column_height` auf 0–255 skaliert (255 = volle Welthöhe).
+    pub fn to_heightmap(&self) -> Vec<u8> {
+        (0..self.width)
+            .map(|x| (self.column_height(x) as f32 / self.height.max(1) as f32 * 255.0).round() as u8)
+            .collect()
+    }
+
+    /// Alle Zellen, die von `start` aus über die vier direkten Nachbarn erreichbar sind,
+    /// solange `passable` für die Belegung der Zelle `true` liefert. Iterativ (BFS), also ohne Rekursionstiefe.
+    pub fn flood_fill(&self, start: (usize, usize), passable: impl Fn(Option<ParticleRef>) -> bool) -> Vec<(usize, usize)> {
+        let (sx, sy) = start;
+        if sx >= self.width || sy >= self.height || !passable(self.grid[sy][sx].0) {
+            return Vec::new();
+        }
+
+        let mut visited = vec![vec![false; self.width]; self.height];
+        let mut queue = VecDeque::new();
+        let mut region = Vec::new();
+        visited[sy][sx] = true;
+        queue.push_back((sx, sy));
+
+        while let Some((x, y)) = queue.pop_front() {
+            region.push((x, y));
+            let neighbors = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for (nx, ny) in neighbors {
+                if nx >= self.width || ny >= self.height || visited[ny][nx] { continue; }
+                if !passable(self.grid[ny][nx].0) { continue; }
+                visited[ny][nx] = true;
+                queue.push_back((nx, ny));
+            }
+        }
+        region
+    }
+
+    /// Massengewichteter Schwerpunkt aller belegten, nicht statischen Zellen; `None` ohne Masse.
+    pub fn mass_center(&self) -> Option<[f32; 2]> {
+        let mut sum = [0.0, 0.0];
+        let mut total = 0.0;
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                match cell.0 {
+                    None | Some(ParticleRef::Static) => continue,
+                    Some(_) => {
+                        sum[0] += x as f32 * cell.1;
+                        sum[1] += y as f32 * cell.1;
+                        total += cell.1;
+                    }
+                }
+            }
+        }
+        if total <= 0.0 {
+            return None;
+        }
+        Some([sum[0] / total, sum[1] / total])
+    }
+
     pub fn calc_pressure_on_all_position(&mut self) {
-        for j in 0..self.width {
-            let mut sum_pressure: f32 = 0.0;
-            for i in (0..self.height).rev() {
-                sum_pressure += self.grid[i][j].1;
-                self.grid[i][j].2 = sum_pressure;
+        self.calc_pressure_along([0.0, -1.0]);
+    }
+
+    /// Druck als Masse, die entgegen `gravity_dir` auf einer Zelle lastet (inklusive der eigenen).
+    /// Die Richtung wird auf einen der acht Nachbarn gerundet; ohne Schwerkraft zählt nur die eigene Masse.
+    pub fn calc_pressure_along(&mut self, gravity_dir: [f32; 2]) {
+        let scale = gravity_dir[0].abs().max(gravity_dir[1].abs());
+        let (dx, dy) = if scale > 0.0 {
+            ((gravity_dir[0] / scale).round() as i32, (gravity_dir[1] / scale).round() as i32)
+        } else {
+            (0, 0)
+        };
+
+        // Gegen die Schwerkraft beginnen, damit die Zelle darüber immer schon fertig ist
+        let xs: Vec<usize> = if dx < 0 { (0..self.width).rev().collect() } else { (0..self.width).collect() };
+        let ys: Vec<usize> = if dy < 0 { (0..self.height).rev().collect() } else { (0..self.height).collect() };
+        for &y in &ys {
+            for &x in &xs {
+                let (ux, uy) = (x as i32 - dx, y as i32 - dy);
+                let inside = (dx, dy) != (0, 0) && ux >= 0 && uy >= 0 && ux < self.width as i32 && uy < self.height as i32;
+                let above = if inside { self.grid[uy as usize][ux as usize].2 } else { 0.0 };
+                self.grid[y][x].2 = above + self.grid[y][x].1;
+            }
+        }
+    }
+
+    /// Vorhersage für Debug-Pfeile: die Zellen, die `particle` in den nächsten `steps` Ticks erreicht (eine je Tick).
+    /// Rechnet auf einer Kopie des Grids mit Fallen und Abrutschen wie `Simulation::step` (Seite wie bei
+    /// `SlideOrder::Alternating`); alles andere gilt als stillstehend, Druck und Fließen bleiben außen vor.
+    /// `Simulation::predicted_path` setzt Schwerkraft und `max_speed` der Simulation ein.
+    pub fn predicted_path(&self, particle: &Particle, gravity: [f32; 2], max_speed: f32, steps: usize) -> Vec<(usize, usize)> {
+        let mut world = self.clone();
+        let mut p = particle.clone();
+        (0..steps).map(|_| {
+            p.update_velocity(gravity, &world, max_speed);
+            p.update_position(&mut world);
+            p.fall_down(&mut world, p.id % 2 == 0);
+            World::cell_of(p.position)
+        }).collect()
+    }
+
+    /// Lageenergie aller belegten, nicht statischen Zellen: `Masse * |gravity_y| * Höhe`, Höhe 0 ist die unterste Zeile.
+    pub fn potential_energy(&self, gravity: [f32; 2]) -> f32 {
+        let g = gravity[1].abs();
+        self.grid.iter().enumerate()
+            .flat_map(|(y, row)| row.iter().map(move |cell| (y, cell)))
+            .filter(|(_, (occupation, _, _))| occupation.is_some_and(|r| r != ParticleRef::Static))
+            .map(|(y, (_, mass, _))| mass * g * y as f32)
+            .sum()
+    }
+
+    /// Inhalt der 8 Nachbarzellen von (x, y) in der Reihenfolge von `NEIGHBOR_OFFSETS`, z.B. als Sinne
+    /// für externe Agenten. `Neighbor::Empty` für leere Zellen, außerhalb des Grids und veraltete Referenzen.
+    pub fn material_neighbors(&self, x: usize, y: usize, particles: &[Particle], objects: &[Object]) -> [Neighbor; 8] {
+        NEIGHBOR_OFFSETS.map(|(dx, dy)| {
+            let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+            if nx < 0 || ny < 0 || nx >= self.width as i32 || ny >= self.height as i32 { return Neighbor::Empty; }
+            let material = match self.give_occupation_on_position(nx as usize, ny as usize) {
+                None => None,
+                Some(particle_ref @ ParticleRef::Free(idx, _)) => particles.get(idx).filter(|p| p.particle_ref == particle_ref).map(|p| p.material),
+                Some(ParticleRef::InObject(object_id, i, j)) => index_of_object(objects, object_id)
+                    .map(|object_idx| &objects[object_idx])
+                    .filter(|obj| !obj.is_destroyed)
+                    .map(|obj| obj.get_particle_at(i, j).material),
+                Some(ParticleRef::Static) => return Neighbor::Static,
+            };
+            material.map_or(Neighbor::Empty, Neighbor::Material)
+        })
+    }
+}
+
+// ============== GRAVITY ==============
+
+/// Ein Beitrag zum Schwerefeld; `Simulation::net_gravity` summiert alle zur Grundschwerkraft.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GravitySource {
+    /// Wirkt überall gleich.
+    Uniform([f32; 2]),
+    /// Zieht zum Zentrum hin; volle Stärke in der Mitte, am Radius null.
+    Well { center: [f32; 2], strength: f32, radius: f32 },
+    /// Wirkt nur innerhalb des Rechtecks [min, max).
+    Zone { min: [f32; 2], max: [f32; 2], gravity: [f32; 2] },
+}
+
+impl GravitySource {
+    pub fn at(&self, pos: [f32; 2]) -> [f32; 2] {
+        match *self {
+            GravitySource::Uniform(gravity) => gravity,
+            GravitySource::Well { center, strength, radius } => {
+                let d = [center[0] - pos[0], center[1] - pos[1]];
+                let dist = (d[0] * d[0] + d[1] * d[1]).sqrt();
+                if dist == 0.0 || dist >= radius {
+                    return [0.0, 0.0];
+                }
+                let pull = strength * (1.0 - dist / radius) / dist;
+                [d[0] * pull, d[1] * pull]
             }
+            GravitySource::Zone { min, max, gravity } => {
+                let inside = pos[0] >= min[0] && pos[0] < max[0] && pos[1] >= min[1] && pos[1] < max[1];
+                if inside { gravity } else { [0.0, 0.0] }
+            }
+        }
+    }
+}
+
+/// Begrenzt jede Achse auf `-max_speed..=max_speed`.
+fn clamp_speed(velocity: [f32; 2], max_speed: f32) -> [f32; 2] {
+    velocity.map(|v| v.clamp(-max_speed, max_speed))
+}
+
+fn sum_gravity(base: [f32; 2], sources: &[GravitySource], pos: [f32; 2]) -> [f32; 2] {
+    sources.iter().fold(base, |acc, source| {
+        let g = source.at(pos);
+        [acc[0] + g[0], acc[1] + g[1]]
+    })
+}
+
+/// `Simulation::push_object` vergibt streng steigende IDs, daher genügt eine binäre Suche.
+fn index_of_object(objects: &[Object], object_id: i32) -> Option<usize> {
+    objects.binary_search_by_key(&object_id, |obj| obj.object_id).ok()
+}
+
+// ============== SIMULATION ==============
+
+/// Gelenk: hält die Zelle `anchor_cell` (i, j) des Objekts `object_id` am Weltpunkt `world_point` fest.
+#[derive(Debug, Clone, Copy)]
+pub struct Joint {
+    pub object_id: i32,
+    pub anchor_cell: (usize, usize),
+    pub world_point: [f32; 2],
+}
+
+/// Reibung des statischen Bodens, der selbst kein Material trägt.
+const STATIC_FRICTION: f32 = 0.5;
+
+/// Standard-Obergrenze für Zellen pro gespawntem Objekt.
+const DEFAULT_MAX_OBJECT_CELLS: usize = 400;
+
+/// Standard für `Simulation::max_speed`: weit über allem, was Schwerkraft und Strahlen im normalen Spiel erreichen.
+pub const DEFAULT_MAX_SPEED: f32 = 64.0;
+
+/// Standard-Obergrenze für freie Partikel bei `try_spawn_particle`.
+const DEFAULT_MAX_PARTICLES: usize = 10_000;
+
+/// So lange (in Ticks) muss Lava ruhen, bis sie zu Stein erstarrt.
+pub const LAVA_COOL_TICKS: u32 = 120;
+
+/// So lange (in Ticks) bleibt frischer Beton beweglich wie nasser Sand, danach ist er fest.
+pub const BETON_CURE_TICKS: u32 = 60;
+
+/// Reihenfolge der Nachbarn in `World::material_neighbors`: zeilenweise von oben links nach unten rechts (y zeigt nach oben).
+pub const NEIGHBOR_OFFSETS: [(i32, i32); 8] = [(-1, 1), (0, 1), (1, 1), (-1, 0), (1, 0), (-1, -1), (0, -1), (1, -1)];
+
+/// Eine Nachbarzelle aus `World::material_neighbors`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbor {
+    Empty,
+    /// Statische Zelle: Boden, Wand oder Eingefrorenes.
+    Static,
+    /// Freies Partikel oder Objektzelle aus diesem Material.
+    Material(MaterialTyp),
+}
+
+/// So viel seitliche Geschwindigkeit (Zellen pro Tick) verliert ein freies Partikel pro Tick.
+const HORIZONTAL_DRAG: f32 = 1.0;
+
+/// Ab dieser Flüssigkeitstiefe über einer Zelle spritzt sie aus einem seitlichen Leck (siehe `Particle::jet_out`).
+const JET_MIN_DEPTH: f32 = 2.0;
+
+/// Strahlgeschwindigkeit (Zellen pro Tick) je Zelle Tiefe, gedeckelt durch `JET_MAX_SPEED`.
+const JET_SPEED_PER_DEPTH: f32 = 0.5;
+const JET_MAX_SPEED: f32 = 6.0;
+
+/// So weit (in Zellen) sucht eine Flüssigkeit seitlich nach einer Kante, an der es tiefer geht.
+const LIQUID_LOOKAHEAD: i32 = 8;
+
+/// Wasser muss schneller als diese Geschwindigkeit (Zellen pro Tick) sein, um zu erodieren.
+const EROSION_MIN_SPEED: f32 = 1.0;
+
+/// Langsamer als diese Geschwindigkeit lädt Wasser sein Sediment wieder als Sand ab.
+const SEDIMENT_DEPOSIT_SPEED: f32 = 0.5;
+
+/// Spawn-Spalte um `center` mit symmetrischem Versatz in `-spread..=spread`, auf `0..width` begrenzt.
+pub fn jitter_column(center: usize, spread: usize, width: usize, rng: &mut impl Rng) -> usize {
+    let offset = rng.gen_range(-(spread as i64)..=spread as i64);
+    (center as i64 + offset).clamp(0, width.saturating_sub(1) as i64) as usize
+}
+
+/// Anker (linke untere Zelle) für einen `h`×`w`-Block, der um die Zelle `center` (x, y) zentriert liegt; bei
+/// geraden Maßen liegt `center` rechts oben von der Mitte. Am Rand wird der Block ins Grid geschoben.
+/// `None`, wenn er nicht ins `width`×`height`-Grid passt.
+pub fn centered_anchor(center: (i32, i32), h: usize, w: usize, width: usize, height: usize) -> Option<(usize, usize)> {
+    if h == 0 || w == 0 || h > height || w > width {
+        return None;
+    }
+    let x = (center.0 as i64 - (w / 2) as i64).clamp(0, (width - w) as i64);
+    let y = (center.1 as i64 - (h / 2) as i64).clamp(0, (height - h) as i64);
+    Some((x as usize, y as usize))
+}
+
+/// Was aus einem Bruchstück geworden ist: ein freies Partikel oder ein neues Objekt (jeweils Index).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Piece {
+    Particle(usize),
+    Object(usize),
+}
+
+/// Ein zerbrochenes Objekt samt den daraus entstandenen Teilen.
+#[derive(Debug, Clone)]
+pub struct Split {
+    pub object_idx: usize,
+    pub pieces: Vec<Piece>,
+}
+
+/// Welche Diagonale ein fallendes Partikel zuerst probiert, wenn es nicht gerade nach unten kann.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SlideOrder {
+    /// Immer erst links: Haufen lehnen sich nach links.
+    LeftFirst,
+    /// Nach ID im Wechsel (gerade links, ungerade rechts): symmetrische Haufen, trotzdem deterministisch.
+    #[default]
+    Alternating,
+    /// Zufällig aus dem Simulations-RNG.
+    Random,
+}
+
+/// Laufrichtung eines Förderbands, siehe `Simulation::place_conveyor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conveyor {
+    Left,
+    Right,
+}
+
+impl Conveyor {
+    /// Schritt in x pro Tick.
+    pub fn dx(self) -> i32 {
+        match self {
+            Conveyor::Left => -1,
+            Conveyor::Right => 1,
         }
     }
-}
\ No newline at end of file
+
+    pub fn reversed(self) -> Conveyor {
+        match self {
+            Conveyor::Left => Conveyor::Right,
+            Conveyor::Right => Conveyor::Left,
+        }
+    }
+}
+
+/// Was `Simulation::try_spawn_particle` tut, wenn `max_particles` erreicht ist.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CapPolicy {
+    /// Neue Partikel ablehnen.
+    #[default]
+    Reject,
+    /// Das älteste ruhende Partikel (kleinste ID) entfernen und Platz machen.
+    RecycleOldest,
+    /// Das ruhende Partikel mit den meisten freien Nachbarn in einen davon verschmelzen: Der Nachbar übernimmt
+    /// seine Masse (`Particle::merged_mass`), die Gesamtmasse bleibt erhalten.
+    Merge,
+}
+
+/// Welche Durchgänge `Simulation::step` ausführt; standardmäßig alle. Abgeschaltete Durchgänge lassen ihren
+/// Zustand unverändert, z.B. bleibt ohne `do_pressure` der Druck des letzten Schritts im Grid stehen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepConfig {
+    /// Druck im Grid neu berechnen.
+    pub do_pressure: bool,
+    /// Freie Partikel bewegen: Fallen, Druckausgleich, Fließen.
+    pub do_particles: bool,
+    /// Erosion, Mischen, Abkippen hoher Säulen und das Binden von Partikelhaufen.
+    pub do_reactions: bool,
+    /// Objekte bewegen samt Aufprall, Einsinken und Gelenken.
+    pub do_objects: bool,
+    /// Druckbruch ruhender Objekte.
+    pub do_fracture: bool,
+}
+
+impl Default for StepConfig {
+    fn default() -> Self {
+        StepConfig { do_pressure: true, do_particles: true, do_reactions: true, do_objects: true, do_fracture: true }
+    }
+}
+
+/// Herkunft eines Körpers aus `Simulation::bodies`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyId {
+    /// Lebendes Objekt mit diesem Index.
+    Object(usize),
+    /// Haufen ruhender freier Partikel, benannt nach seinem kleinsten Partikelindex.
+    Cluster(usize),
+}
+
+/// Zusammenfassung eines Körpers: Zellzahl, Materialanteile, Hüllrechteck und Schwerpunkt.
+#[derive(Debug, Clone)]
+pub struct BodyInfo {
+    pub id: BodyId,
+    pub cells: usize,
+    pub composition: HashMap<MaterialTyp, usize>,
+    /// Hüllrechteck in Weltkoordinaten, beide Ecken eingeschlossen.
+    pub min: [f32; 2],
+    pub max: [f32; 2],
+    pub center_of_mass: [f32; 2],
+}
+
+impl BodyInfo {
+    fn from_particles<'a>(id: BodyId, particles: impl IntoIterator<Item = &'a Particle>, materials: &MaterialTable) -> BodyInfo {
+        let mut info = BodyInfo {
+            id,
+            cells: 0,
+            composition: HashMap::new(),
+            min: [f32::INFINITY; 2],
+            max: [f32::NEG_INFINITY; 2],
+            center_of_mass: [0.0, 0.0],
+        };
+        let mut total = 0.0;
+        for p in particles {
+            info.cells += 1;
+            *info.composition.entry(p.material).or_insert(0) += 1;
+            for k in 0..2 {
+                info.min[k] = info.min[k].min(p.position[k]);
+                info.max[k] = info.max[k].max(p.position[k]);
+                info.center_of_mass[k] += p.position[k] * p.mass(materials);
+            }
+            total += p.mass(materials);
+        }
+        if total > 0.0 {
+            info.center_of_mass = info.center_of_mass.map(|sum| sum / total);
+        }
+        info
+    }
+}
+
+/// Ereignisse eines Simulationsschritts, damit ein Frontend seine Darstellung nachziehen kann.
+#[derive(Debug, Clone, Default)]
+pub struct StepEvents {
+    pub splits: Vec<Split>,
+    /// Gelenk-Drehungen als (object_idx, clockwise).
+    pub rotations: Vec<(usize, bool)>,
+    /// Aus ruhenden Partikelhaufen gebildete Objekte (siehe `cluster_min_size`). Die Partikelindizes haben sich dabei verschoben.
+    pub bonded: Vec<usize>,
+    /// Von `emitters` erzeugte Partikelindizes.
+    pub emitted: Vec<usize>,
+    /// Ein Emitter hat an der Partikelgrenze ein Partikel ersetzt; die Indizes haben sich verschoben.
+    pub recycled: bool,
+}
+
+/// Feste Partikelquelle, siehe `Simulation::emitters`: Alle `rate` Ticks entsteht an `pos` ein Partikel mit
+/// `velocity`, etwa ein waagrechter Wasserhahn oder ein Geysir. Ist die Zelle belegt, fällt der Ausstoß aus.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Emitter {
+    pub pos: (usize, usize),
+    pub material: MaterialTyp,
+    /// Abstand der Ausstöße in Ticks; 0 schaltet den Emitter ab.
+    pub rate: u64,
+    pub velocity: [f32; 2],
+}
+
+/// Abstand der Schritte, in denen `step` nach Partikelhaufen für `cluster_min_size` sucht.
+pub const CLUSTER_PASS_TICKS: u64 = 20;
+
+/// Standard für `Simulation::dt`, passend zum festen Schritt des Fensters (50 ms).
+pub const DEFAULT_DT: f32 = 0.05;
+
+/// Simulationszeit: gezählte Schritte und die dabei vergangenen simulierten Sekunden. Zeitgeber wie das
+/// Aushärten von Beton rechnen in diesen Ticks statt in Frames.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SimClock {
+    ticks: u64,
+    seconds: f64,
+}
+
+impl SimClock {
+    /// Einen Schritt der Länge `dt` weiterzählen.
+    pub fn advance(&mut self, dt: f32) {
+        self.ticks += 1;
+        self.seconds += dt as f64;
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn seconds(&self) -> f64 {
+        self.seconds
+    }
+}
+
+/// Relative Abweichung der Gesamtmasse, ab der `stability_check` einen Massenverlust meldet.
+pub const MASS_DRIFT_TOLERANCE: f32 = 1e-3;
+
+/// Einzelner Befund von `Simulation::stability_check`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StabilityIssue {
+    /// Position oder Geschwindigkeit ist NaN oder unendlich.
+    NonFinite(Piece),
+    OutOfBounds(Piece),
+    /// Grid-Zelle (x, y) verweist auf ein entferntes Partikel oder ein zerstörtes Objekt.
+    DanglingRef(usize, usize),
+    MassDrift { baseline: f32, current: f32 },
+    /// Die Masse im Grid (ohne statische Zellen) weicht von der Masse der Teile ab, z.B. weil ein Objekt ein
+    /// freies Partikel überdeckt hat.
+    GridMassMismatch { grid: f32, expected: f32 },
+}
+
+/// Ergebnis von `Simulation::stability_check`; ohne Befunde ist die Simulation stabil.
+#[derive(Debug, Clone, Default)]
+pub struct StabilityReport {
+    pub issues: Vec<StabilityIssue>,
+}
+
+impl StabilityReport {
+    pub fn is_stable(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Benutzerlogik pro Tick, siehe `Simulation::step_hooks`. `Send + Sync`, damit die Simulation eine Bevy-Ressource bleibt.
+pub type StepHook = Box<dyn FnMut(&mut World, &mut [Particle], &mut [Object]) + Send + Sync>;
+
+pub struct Simulation {
+    pub world: World,
+    pub particles: Vec<Particle>,
+    pub objects: Vec<Object>,
+    /// Grundschwerkraft; `gravity_sources` kommen ortsabhängig hinzu.
+    pub gravity: [f32; 2],
+    pub gravity_sources: Vec<GravitySource>,
+    pub rng: StdRng,
+    pub joints: Vec<Joint>,
+    /// Freie Partikel dieser Materialien bewegen sich nicht, belegen aber weiter ihre Zelle.
+    pub frozen_materials: HashSet<MaterialTyp>,
+    /// Höchstzahl an Zellen für `spawn_object`/`spawn_quadrant`. Fragmente und der Builder sind ausgenommen.
+    pub max_object_cells: usize,
+    /// Höchstzahl freier Partikel für `try_spawn_particle`; Fragmente, Auftauen und der Builder sind ausgenommen.
+    pub max_particles: usize,
+    /// Verhalten an der Partikelgrenze, siehe `CapPolicy`.
+    pub cap_policy: CapPolicy,
+    /// Höchstgeschwindigkeit je Achse in Zellen pro Tick für Partikel und Objekte, gegen Ausreißer und Tunneln.
+    pub max_speed: f32,
+    /// Simulierte Sekunden pro `step`, für `elapsed_time`.
+    pub dt: f32,
+    /// Freie Partikel von unten nach oben abarbeiten statt in Vektorreihenfolge; umkämpfte Zellen gehen an das tiefere Partikel.
+    pub settle_bottom_up: bool,
+    /// Kraftverteilung bei Aufprall und Riss, gilt zu Beginn jedes `step` für alle Objekte.
+    pub force_profile: ForceProfile,
+    /// Alle `CLUSTER_PASS_TICKS` Schritte werden ruhende Partikelhaufen ab dieser Größe zu Objekten (0 = aus).
+    pub cluster_min_size: usize,
+    /// Verbindungsradius für `find_clusters`, siehe dort.
+    pub cluster_radius: usize,
+    /// Welche Durchgänge `step` ausführt.
+    pub step_config: StepConfig,
+    /// Rutschrichtung beim Fallen, siehe `SlideOrder`.
+    pub slide_order: SlideOrder,
+    /// Kontaktreaktionen (a, b) → (neues a, neues b), siehe `react`. Standardmäßig leer.
+    pub reactions: HashMap<(MaterialTyp, MaterialTyp), (MaterialTyp, MaterialTyp)>,
+    /// Mischregeln (a, b) → Ergebnis unter Druck, siehe `mix`. Startet mit `MIXING_RECIPES`; leeren schaltet Mischen ab.
+    pub mixing_recipes: HashMap<(MaterialTyp, MaterialTyp), MaterialTyp>,
+    /// Laufen am Ende jedes `step` nach Physik, Gelenken und Brüchen, vor dem Rendering – in Registrierungsreihenfolge.
+    /// Ihre Bewegungen zählen zur `activity` des Schritts.
+    pub step_hooks: Vec<StepHook>,
+    /// Per `freeze_region` zu statischen Zellen gemachte Teile mit ihrem ursprünglichen Material.
+    frozen_cells: HashMap<(usize, usize), MaterialTyp>,
+    /// Förderbänder: statische Zellen, die aufliegende Partikel seitwärts schieben.
+    conveyors: HashMap<(usize, usize), Conveyor>,
+    /// Partikelquellen, die am Ende jedes `step` ausstoßen. Sie halten `max_particles` ein.
+    pub emitters: Vec<Emitter>,
+    /// Aushärtender Beton: Partikel-ID → Tick der `clock`, ab dem er fest ist. Freier Beton ohne Eintrag ist fest.
+    curing: HashMap<i32, u64>,
+    mass_baseline: Option<f32>,
+    activity: usize,
+    clock: SimClock,
+    next_generation: u32,
+    particle_counter: i32,
+    object_counter: i32,
+}
+
+impl Simulation {
+    pub fn new(h: usize, w: usize, gravity: [f32; 2]) -> Simulation {
+        Simulation {
+            world: World::new(h, w),
+            particles: Vec::new(),
+            objects: Vec::new(),
+            gravity,
+            gravity_sources: Vec::new(),
+            rng: StdRng::from_entropy(),
+            joints: Vec::new(),
+            frozen_materials: HashSet::new(),
+            max_object_cells: DEFAULT_MAX_OBJECT_CELLS,
+            max_particles: DEFAULT_MAX_PARTICLES,
+            cap_policy: CapPolicy::Reject,
+            max_speed: DEFAULT_MAX_SPEED,
+            dt: DEFAULT_DT,
+            settle_bottom_up: false,
+            force_profile: ForceProfile::Inverse,
+            cluster_min_size: 0,
+            cluster_radius: 1,
+            step_config: StepConfig::default(),
+            slide_order: SlideOrder::Alternating,
+            reactions: HashMap::new(),
+            mixing_recipes: MIXING_RECIPES.iter().map(|&(a, b, result)| ((a, b), result)).collect(),
+            step_hooks: Vec::new(),
+            frozen_cells: HashMap::new(),
+            conveyors: HashMap::new(),
+            emitters: Vec::new(),
+            curing: HashMap::new(),
+            mass_baseline: None,
+            activity: 0,
+            clock: SimClock::default(),
+            next_generation: 0,
+            particle_counter: 0,
+            object_counter: 0,
+        }
+    }
+
+    /// Ein kompletter Simulationsschritt ohne Rendering: Partikel, Objekte, Risse, Gelenke und Druckbrüche.
+    /// Zerbrochene Objekte werden am Ende des Schritts in ihre Teile zerlegt.
+    pub fn step(&mut self) -> StepEvents {
+        let particles_before: Vec<[f32; 2]> = self.particles.iter().map(|p| p.position).collect();
+        let objects_before: Vec<[f32; 2]> = self.objects.iter().map(|o| o.position).collect();
+        let config = self.step_config;
+        if config.do_pressure {
+            self.world.calc_pressure_along(self.gravity);
+        }
+
+        if config.do_particles {
+            let Simulation { world, particles, rng, frozen_materials, settle_bottom_up, curing, gravity, gravity_sources, slide_order, max_speed, .. } = self;
+            let mut moving: Vec<&mut Particle> = particles.iter_mut()
+                .filter(|p| !frozen_materials.contains(&p.material))
+                .filter(|p| p.material != MaterialTyp::Beton || curing.contains_key(&p.id))
+                .filter_map(|p| {
+                    // Klebende Partikel bleiben stehen und verlieren ihren Schwung
+                    if !p.adheres(world, sum_gravity(*gravity, gravity_sources, p.position)) { return Some(p); }
+                    p.velocity = [0.0, 0.0];
+                    None
+                })
+                .collect();
+            if *settle_bottom_up {
+                // Stabil sortiert: gleiche Höhe behält die Vektorreihenfolge, der Schritt bleibt deterministisch
+                moving.sort_by(|a, b| a.position[1].total_cmp(&b.position[1]));
+            }
+
+            // Lecks vor der Bewegung erkennen, sonst rutscht das Partikel darüber schräg hinein und verstopft sie
+            for p in moving.iter_mut() {
+                p.jet_out(world);
+                p.update_velocity(sum_gravity(*gravity, gravity_sources, p.position), world, *max_speed);
+                p.update_position(world);
+            }
+
+            for p in moving.iter_mut() {
+                p.resolve_pressure(world, rng);
+            }
+
+            for p in moving.iter_mut() {
+                let left_first = match slide_order {
+                    SlideOrder::LeftFirst => true,
+                    SlideOrder::Alternating => p.id % 2 == 0,
+                    SlideOrder::Random => rng.gen::<bool>(),
+                };
+                p.fall_down(world, left_first);
+            }
+
+            // Flüssigkeiten fließen zur nächsten tieferen Stelle
+            for p in moving.iter_mut() {
+                p.flow_down_gradient(world);
+            }
+            self.pass_through();
+            self.settle_by_density();
+            self.convey();
+        }
+
+        if config.do_reactions {
+            self.erode();
+            self.mix_materials();
+            self.react_on_contact();
+            self.topple_columns();
+            self.age_particles(&particles_before);
+        }
+
+        self.clock.advance(self.dt);
+        let now = self.clock.ticks();
+        self.curing.retain(|_, cured_at| *cured_at > now);
+        let bonded = if config.do_reactions && self.cluster_min_size > 0 && self.clock.ticks().is_multiple_of(CLUSTER_PASS_TICKS) {
+            self.bond_clusters(self.cluster_radius, self.cluster_min_size)
+        } else {
+            Vec::new()
+        };
+
+        let frictions: Vec<Option<f32>> = (0..self.objects.len()).map(|idx| self.support_friction(idx)).collect();
+        let pinned: Vec<bool> = (0..self.objects.len()).map(|idx| self.is_pinned(idx)).collect();
+
+        // Objekte mit eingereihtem Bruch sind bis zum Zerlegen am Ende des Schritts gesperrt
+        let mut fractures = Vec::new();
+        let mut pending = vec![false; self.objects.len()];
+        let mut impacts = Vec::new();
+        let Simulation { world, objects, particles, gravity, gravity_sources, force_profile, max_speed, .. } = self;
+        for (obj_idx, obj) in objects.iter_mut().enumerate() {
+            obj.force_profile = *force_profile;
+            if !config.do_objects || obj.is_destroyed || pinned[obj_idx] { continue; }
+
+            let gravity = sum_gravity(*gravity, gravity_sources, obj.center_of_mass(&world.materials));
+            let fragments = obj.update_object_velocity(gravity, world, *max_speed);
+            impacts.extend(obj.take_struck_objects());
+            if let Some(fragments) = fragments {
+                fractures.push((obj_idx, fragments));
+                pending[obj_idx] = true;
+                continue;
+            }
+
+            if let Some(friction) = frictions[obj_idx] {
+                obj.apply_friction(friction, gravity);
+            }
+
+            if !obj.is_destroyed {
+                obj.make_room(world, particles);
+                obj.update_object_position(world);
+            }
+
+            // Risse wandern Tick für Tick weiter
+            if let Some(fragments) = obj.propagate_crack(&world.materials) {
+                fractures.push((obj_idx, fragments));
+                pending[obj_idx] = true;
+            }
+        }
+
+        // Aufprall auf andere Objekte: der getroffene Block bekommt seinen Anteil ab
+        for (struck_id, force) in impacts {
+            let Some(struck_idx) = self.object_index(struck_id) else { continue; };
+            if pending[struck_idx] { continue; }
+            let Some(struck) = self.objects.get_mut(struck_idx).filter(|obj| !obj.is_destroyed) else { continue; };
+            if let Some(fragments) = struck.receive_impact(force, &self.world.materials) {
+                fractures.push((struck_idx, fragments));
+                pending[struck_idx] = true;
+            }
+        }
+
+        // Gedrehte Objekte würden die Zellkoordinaten der eingereihten Fragmente ungültig machen
+        let rotations = if config.do_objects {
+            self.sink_into_sand(&pending);
+            self.resolve_joints_except(&pending)
+        } else {
+            Vec::new()
+        };
+
+        let Simulation { world, objects, .. } = self;
+        for (obj_idx, obj) in objects.iter_mut().enumerate() {
+            if !config.do_fracture || obj.is_destroyed || pending[obj_idx] { continue; }
+
+            if !obj.is_supported(world) { continue; }
+
+            let broken_bonds = obj.check_pressure_fracture(world);
+            if !broken_bonds.is_empty() {
+                let fragments = obj.find_fragments(&broken_bonds);
+                if fragments.len() > 1 {
+                    fractures.push((obj_idx, fragments));
+                }
+            }
+        }
+
+        let mut splits = Vec::new();
+        for (object_idx, fragments) in fractures {
+            if self.live_object(object_idx).is_none() { continue; }
+            let pieces = self.split_object(object_idx, &fragments);
+            splits.push(Split { object_idx, pieces });
+        }
+
+        let (emitted, recycled) = self.run_emitters();
+
+        let Simulation { world, particles, objects, step_hooks, .. } = self;
+        for hook in step_hooks.iter_mut() {
+            hook(world, particles, objects);
+        }
+
+        let moved_particles = self.particles.iter().enumerate()
+            .filter(|(idx, p)| particles_before.get(*idx) != Some(&p.position))
+            .count();
+        let moved_objects = self.objects.iter().enumerate()
+            .filter(|(idx, o)| !o.is_destroyed && objects_before.get(*idx) != Some(&o.position))
+            .count();
+        self.activity = moved_particles + moved_objects + rotations.len() + splits.len();
+
+        StepEvents { splits, rotations, bonded, emitted, recycled }
+    }
+
+    /// Lässt jeden fälligen Emitter ein Partikel per `try_spawn_particle` ausstoßen. Liefert die neuen
+    /// Partikelindizes und ob dabei an der Partikelgrenze ersetzt wurde.
+    fn run_emitters(&mut self) -> (Vec<usize>, bool) {
+        let mut emitted = Vec::new();
+        let mut recycled = false;
+        let ticks = self.clock.ticks();
+        for emitter in self.emitters.clone() {
+            if emitter.rate == 0 || !ticks.is_multiple_of(emitter.rate) { continue; }
+            let (x, y) = emitter.pos;
+            if x >= self.world.width || y >= self.world.height || self.world.give_occupation_on_position(x, y).is_some() { continue; }
+
+            let at_cap = self.at_particle_cap();
+            let Ok(idx) = self.try_spawn_particle([x as f32, y as f32], emitter.velocity, emitter.material) else { continue; };
+            recycled |= at_cap;
+            emitted.push(idx);
+        }
+        (emitted, recycled)
+    }
+
+    /// Hängt einen Hook an `step_hooks` an.
+    pub fn add_step_hook(&mut self, hook: impl FnMut(&mut World, &mut [Particle], &mut [Object]) + Send + Sync + 'static) {
+        self.step_hooks.push(Box::new(hook));
+    }
+
+    /// Bewegungen im letzten Schritt: verschobene Partikel und Objekte, Drehungen und Brüche. 0 heißt: alles liegt.
+    pub fn activity(&self) -> usize {
+        self.activity
+    }
+
+    /// Bisher ausgeführte `step`-Aufrufe.
+    pub fn elapsed_ticks(&self) -> u64 {
+        self.clock.ticks()
+    }
+
+    /// Bisher simulierte Sekunden, die Summe von `dt` über alle Schritte.
+    pub fn elapsed_time(&self) -> f64 {
+        self.clock.seconds()
+    }
+
+    pub fn clock(&self) -> SimClock {
+        self.clock
+    }
+
+    /// Ergebnis der Mischregel für `a` neben `b` aus `mixing_recipes`, in beliebiger Reihenfolge; `None` ohne Regel.
+    pub fn mix(&self, a: MaterialTyp, b: MaterialTyp) -> Option<MaterialTyp> {
+        self.mixing_recipes.get(&(a, b)).or_else(|| self.mixing_recipes.get(&(b, a))).copied()
+    }
+
+    /// Wendet `mixing_recipes` an: Ein freies Partikel, auf dem etwas lastet, mischt sich mit einem angrenzenden
+    /// freien Partner. Entstehender Beton härtet `BETON_CURE_TICKS` aus.
+    pub fn mix_materials(&mut self) {
+        if self.mixing_recipes.is_empty() { return; }
+
+        for idx in 0..self.particles.len() {
+            let particle = &self.particles[idx];
+            if self.frozen_materials.contains(&particle.material) { continue; }
+            let (x, y) = World::cell_of(particle.position);
+            if x >= self.world.width || y >= self.world.height { continue; }
+            if particle.sediment.is_some() || self.world.give_pressure_on_position(x, y) <= particle.mass(&self.world.materials) { continue; }
+
+            for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+                if nx < 0 || ny < 0 || nx >= self.world.width as i32 || ny >= self.world.height as i32 { continue; }
+
+                let Some(neighbor_ref @ ParticleRef::Free(n, _)) = self.world.give_occupation_on_position(nx as usize, ny as usize) else { continue; };
+                let Some(neighbor) = self.resolve_particle(neighbor_ref) else { continue; };
+                if neighbor.sediment.is_some() || self.frozen_materials.contains(&neighbor.material) { continue; }
+                let Some(result) = self.mix(self.particles[idx].material, neighbor.material) else { continue; };
+
+                for k in [idx, n] {
+                    let p = &mut self.particles[k];
+                    p.material = result;
+                    if result == MaterialTyp::Beton {
+                        self.curing.insert(p.id, self.clock.ticks() + BETON_CURE_TICKS as u64);
+                    }
+                    self.world.update_mass_on_position(p.position, p.mass(&self.world.materials));
+                }
+                break;
+            }
+        }
+    }
+
+    /// Kollisionsebenen: Ein freies Partikel, das mit dem freien Partikel in Schwerkraftrichtung unter ihm
+    /// nicht kollidiert (siehe `MaterialTyp::collides`), tauscht mit ihm den Platz. Jedes Partikel tauscht
+    /// höchstens einmal pro Aufruf; statische Zellen und Objekte halten immer auf.
+    pub fn pass_through(&mut self) {
+        let all_collide = MaterialTyp::ALL.iter().all(|&a| MaterialTyp::ALL.iter().all(|&b| self.world.materials.collides(a, b)));
+        if all_collide { return; }
+
+        let dy = if self.gravity[1] > 0.0 { 1 } else { -1 };
+        let mut swapped = vec![false; self.particles.len()];
+        for idx in 0..self.particles.len() {
+            let particle = &self.particles[idx];
+            if swapped[idx] || self.frozen_materials.contains(&particle.material) { continue; }
+            let (x, y) = World::cell_of(particle.position);
+            let ny = y as i32 + dy;
+            if x >= self.world.width || ny < 0 || ny >= self.world.height as i32 { continue; }
+
+            let Some(other_ref @ ParticleRef::Free(other, _)) = self.world.give_occupation_on_position(x, ny as usize) else { continue; };
+            let Some(occupant) = self.resolve_particle(other_ref) else { continue; };
+            if swapped[other] || self.frozen_materials.contains(&occupant.material) || self.world.materials.collides(particle.material, occupant.material) { continue; }
+
+            self.swap_free_particles(idx, other);
+            swapped[idx] = true;
+            swapped[other] = true;
+        }
+    }
+
+    /// Sedimentation: Ein freies Partikel sinkt durch eine leichtere Flüssigkeit in Schwerkraftrichtung unter
+    /// ihm, die Flüssigkeit steigt dafür auf. So setzt sich Sand unter Wasser ab, statt Wasser einzuschließen.
+    /// Jedes Partikel tauscht höchstens einmal pro Aufruf.
+    pub fn settle_by_density(&mut self) {
+        let dy = if self.gravity[1] > 0.0 { 1 } else { -1 };
+        let mut swapped = vec![false; self.particles.len()];
+        for idx in 0..self.particles.len() {
+            let particle = &self.particles[idx];
+            if swapped[idx] || self.frozen_materials.contains(&particle.material) { continue; }
+            let (x, y) = World::cell_of(particle.position);
+            let ny = y as i32 + dy;
+            if x >= self.world.width || ny < 0 || ny >= self.world.height as i32 { continue; }
+
+            let Some(other_ref @ ParticleRef::Free(other, _)) = self.world.give_occupation_on_position(x, ny as usize) else { continue; };
+            let Some(fluid) = self.resolve_particle(other_ref) else { continue; };
+            if swapped[other] || fluid.material.is_solid() || self.frozen_materials.contains(&fluid.material) { continue; }
+            if particle.mass(&self.world.materials) <= fluid.mass(&self.world.materials) { continue; }
+
+            self.swap_free_particles(idx, other);
+            swapped[idx] = true;
+            swapped[other] = true;
+        }
+    }
+
+    fn swap_free_particles(&mut self, a: usize, b: usize) {
+        let (pos_a, pos_b) = (self.particles[a].position, self.particles[b].position);
+        for (k, position) in [(a, pos_b), (b, pos_a)] {
+            let p = &mut self.particles[k];
+            p.position = position;
+            self.world.update_occupation_on_position(position, p.particle_ref);
+            self.world.update_mass_on_position(position, p.mass(&self.world.materials));
+        }
+    }
+
+    /// Registriert eine Kontaktreaktion: Berühren sich freie Partikel aus `a` und `b`, werden sie zu
+    /// `result.0` bzw. `result.1`. Gilt in beiden Reihenfolgen und ersetzt eine frühere Regel für das Paar.
+    pub fn add_reaction(&mut self, a: MaterialTyp, b: MaterialTyp, result: (MaterialTyp, MaterialTyp)) {
+        self.reactions.remove(&(b, a));
+        self.reactions.insert((a, b), result);
+    }
+
+    /// Ergebnis der Kontaktreaktion von `a` mit `b` als (neues a, neues b); `None` ohne Regel.
+    pub fn react(&self, a: MaterialTyp, b: MaterialTyp) -> Option<(MaterialTyp, MaterialTyp)> {
+        if let Some(&result) = self.reactions.get(&(a, b)) {
+            return Some(result);
+        }
+        self.reactions.get(&(b, a)).map(|&(new_b, new_a)| (new_a, new_b))
+    }
+
+    /// Wendet `reactions` auf direkt benachbarte freie Partikel verschiedener Materialien an. Jedes Partikel
+    /// reagiert höchstens einmal pro Aufruf; das Alter beginnt von vorn, entstehender Beton härtet aus.
+    pub fn react_on_contact(&mut self) {
+        if self.reactions.is_empty() { return; }
+
+        let mut reacted = vec![false; self.particles.len()];
+        for idx in 0..self.particles.len() {
+            let particle = &self.particles[idx];
+            if reacted[idx] || self.frozen_materials.contains(&particle.material) { continue; }
+            let (x, y) = World::cell_of(particle.position);
+            if x >= self.world.width || y >= self.world.height { continue; }
+
+            for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+                if nx < 0 || ny < 0 || nx >= self.world.width as i32 || ny >= self.world.height as i32 { continue; }
+
+                let Some(neighbor_ref @ ParticleRef::Free(n, _)) = self.world.give_occupation_on_position(nx as usize, ny as usize) else { continue; };
+                let Some(neighbor) = self.resolve_particle(neighbor_ref) else { continue; };
+                if reacted[n] || neighbor.material == self.particles[idx].material || self.frozen_materials.contains(&neighbor.material) { continue; }
+                let Some((result, neighbor_result)) = self.react(self.particles[idx].material, neighbor.material) else { continue; };
+
+                for (k, material) in [(idx, result), (n, neighbor_result)] {
+                    let p = &mut self.particles[k];
+                    p.material = material;
+                    p.age = 0;
+                    if material == MaterialTyp::Beton {
+                        self.curing.insert(p.id, self.clock.ticks() + BETON_CURE_TICKS as u64);
+                    }
+                    self.world.update_mass_on_position(p.position, p.mass(&self.world.materials));
+                    reacted[k] = true;
+                }
+                break;
+            }
+        }
+    }
+
+    /// Lässt ruhende freie Partikel einen Tick altern. Wer sich seit `previous_positions` bewegt hat oder noch
+    /// Geschwindigkeit hat, altert nicht; eingefrorene Materialien auch nicht. Erreicht das Alter die Schwelle
+    /// aus `ages_into`, wechselt das Material und das Alter beginnt von vorn.
+    pub fn age_particles(&mut self, previous_positions: &[[f32; 2]]) {
+        for (idx, p) in self.particles.iter_mut().enumerate() {
+            if self.frozen_materials.contains(&p.material) { continue; }
+            if p.velocity != [0.0, 0.0] || previous_positions.get(idx).is_some_and(|&before| before != p.position) { continue; }
+
+            p.age += 1;
+            let Some((target, ticks)) = p.material.ages_into() else { continue; };
+            if p.age >= ticks {
+                p.material = target;
+                p.age = 0;
+                self.world.update_mass_on_position(p.position, p.mass(&self.world.materials));
+            }
+        }
+    }
+
+    /// Böschung: Ein Durchgang über alle benachbarten Spaltenpaare. Ist eine Spalte um mehr als `max_slope` ihres
+    /// obersten Materials höher als die Nachbarspalte, rutscht das oberste freie Partikel auf deren Oberfläche.
+    /// Höhe ist die oberste belegte Zelle, Schwerkraft zeigt nach unten. Stein, Metall und fester Beton rutschen
+    /// nicht. Liefert die Zahl der Rutschungen; mehrfach aufrufen, bis sie 0 ist.
+    pub fn relax_terrain(&mut self) -> usize {
+        let (width, height) = (self.world.width, self.world.height);
+        let top = |world: &World, x: usize| (0..height).rev().find(|&y| world.give_occupation_on_position(x, y).is_some());
+        let mut surface: Vec<Option<usize>> = (0..width).map(|x| top(&self.world, x)).collect();
+        let level = |surface: &[Option<usize>], x: usize| surface[x].map_or(0, |y| y + 1);
+
+        let mut slides = 0;
+        for x in 0..width.saturating_sub(1) {
+            let (from, to) = if level(&surface, x) > level(&surface, x + 1) { (x, x + 1) } else { (x + 1, x) };
+            let Some(y) = surface[from] else { continue; };
+            let target_y = level(&surface, to);
+            if target_y >= height { continue; }
+
+            let Some(particle_ref @ ParticleRef::Free(idx, _)) = self.world.give_occupation_on_position(from, y) else { continue; };
+            let Some(particle) = self.resolve_particle(particle_ref) else { continue; };
+            let slope = self.world.materials.max_slope(particle.material);
+            if slope == 0 || y + 1 - target_y <= slope || self.frozen_materials.contains(&particle.material) { continue; }
+            if particle.material == MaterialTyp::Beton && !self.curing.contains_key(&particle.id) { continue; }
+
+            let p = &mut self.particles[idx];
+            self.world.clear_occupation_on_position(p.position);
+            self.world.clear_mass_on_position(p.position);
+            p.position = [to as f32, target_y as f32];
+            p.velocity = [0.0, 0.0];
+            self.world.update_occupation_on_position(p.position, p.particle_ref);
+            self.world.update_mass_on_position(p.position, p.mass(&self.world.materials));
+            surface[from] = top(&self.world, from);
+            surface[to] = Some(target_y);
+            slides += 1;
+        }
+        slides
+    }
+
+    /// Standhöhe: Ragt eine Säule loser Partikel ohne seitlichen Halt höher als `stack_limit` ihres obersten
+    /// Materials, kippt das oberste Partikel schräg nach unten zur Seite – unabhängig vom Druck.
+    /// Der Gridrand zählt als Halt.
+    pub fn topple_columns(&mut self) {
+        let width = self.world.width;
+        for idx in 0..self.particles.len() {
+            let particle = &self.particles[idx];
+            let limit = self.world.materials.stack_limit(particle.material);
+            if limit == 0 || self.frozen_materials.contains(&particle.material) { continue; }
+            if particle.material == MaterialTyp::Beton && !self.curing.contains_key(&particle.id) { continue; }
+
+            let (x, y) = World::cell_of(particle.position);
+            if x == 0 || x + 1 >= width || y >= self.world.height { continue; }
+            if y + 1 < self.world.height && self.world.give_occupation_on_position(x, y + 1).is_some() { continue; }
+
+            let unsupported = |yy: usize| {
+                self.world.give_occupation_on_position(x - 1, yy).is_none()
+                    && self.world.give_occupation_on_position(x + 1, yy).is_none()
+                    && self.world.give_occupation_on_position(x, yy)
+                        .is_some_and(|r| matches!(r, ParticleRef::Free(..)) && self.resolve_particle(r).is_some())
+            };
+            let height = (0..=y).rev().take_while(|&yy| unsupported(yy)).count();
+            if height <= limit { continue; }
+
+            // Die Zelle schräg darunter ist frei, sonst wäre die Säule dort gestützt
+            let target_x = if self.rng.gen::<bool>() { x - 1 } else { x + 1 };
+            let p = &mut self.particles[idx];
+            self.world.clear_occupation_on_position(p.position);
+            self.world.clear_mass_on_position(p.position);
+            p.position = [target_x as f32, (y - 1) as f32];
+            self.world.update_occupation_on_position(p.position, p.particle_ref);
+            self.world.update_mass_on_position(p.position, p.mass(&self.world.materials));
+        }
+    }
+
+    /// Treibsand: Ruht ein Objekt mit jeder Spalte auf losen Partikeln, deren `bearing_capacity` unter seiner
+    /// Last pro Spalte liegt, sinkt es mit der Chance `1 - Tragkraft / Last` eine Zelle ein. Die verdrängten
+    /// Partikel rücken in die frei gewordene oberste Zelle ihrer Spalte. Objekte mit `skip` bleiben liegen.
+    fn sink_into_sand(&mut self, skip: &[bool]) {
+        'objects: for obj_idx in 0..self.objects.len() {
+            if skip.get(obj_idx).copied().unwrap_or(false) || self.is_pinned(obj_idx) { continue; }
+            let Some(obj) = self.live_object(obj_idx) else { continue; };
+            if obj.velocity[1] != 0.0 { continue; }
+            let (anchor_x, anchor_y) = World::cell_of(obj.position);
+            if anchor_y == 0 { continue; }
+
+            // Je belegter Spalte: (Partikel darunter, oberste Zelle); trägt eine Spalte fest, sinkt nichts
+            let mut columns = Vec::new();
+            let mut capacity: f32 = 0.0;
+            for j in 0..obj.object_w {
+                let solid = |i: &usize| obj.object_grid[*i][j].0.material != MaterialTyp::Luft;
+                let Some(bottom) = (0..obj.object_h).find(solid) else { continue; };
+                let top = (0..obj.object_h).rev().find(solid).unwrap_or(bottom);
+                if anchor_x + j >= self.world.width { continue 'objects; }
+                let support = self.world.give_occupation_on_position(anchor_x + j, anchor_y + bottom - 1)
+                    .and_then(|r| self.resolve_particle(r))
+                    .filter(|p| !self.frozen_materials.contains(&p.material));
+                let Some(support) = support else { continue 'objects; };
+                capacity = capacity.max(self.world.materials.bearing_capacity(support.material));
+                columns.push((support.particle_ref, (anchor_x + j, anchor_y + top)));
+            }
+            if columns.is_empty() { continue; }
+
+            let load = obj.mass(&self.world.materials) / columns.len() as f32;
+            if load <= capacity || self.rng.gen::<f32>() >= 1.0 - capacity / load { continue; }
+
+            let obj = &mut self.objects[obj_idx];
+            obj.clear_from_world(&mut self.world);
+            obj.move_anchor([obj.position[0], obj.position[1] - 1.0]);
+            for (particle_ref, (x, y)) in columns {
+                let ParticleRef::Free(idx, _) = particle_ref else { continue; };
+                let p = &mut self.particles[idx];
+                self.world.clear_occupation_on_position(p.position);
+                self.world.clear_mass_on_position(p.position);
+                p.position = [x as f32, y as f32];
+                p.velocity = [0.0, 0.0];
+                self.world.update_occupation_on_position(p.position, p.particle_ref);
+                self.world.update_mass_on_position(p.position, p.mass(&self.world.materials));
+            }
+            obj.register_in_world(&mut self.world);
+        }
+    }
+
+    /// Alle Körper: jedes lebende Objekt und jeder Haufen ruhender freier Partikel aus `find_clusters(1)`,
+    /// auch einzelne Partikel. Z.B. um zu zählen, in wie viele Teile eine Brücke zerfallen ist.
+    pub fn bodies(&self) -> Vec<BodyInfo> {
+        let objects = self.objects.iter().enumerate()
+            .filter(|(_, obj)| !obj.is_destroyed)
+            .map(|(idx, obj)| BodyInfo::from_particles(
+                BodyId::Object(idx),
+                obj.get_object_elements().into_iter().filter(|p| p.material != MaterialTyp::Luft),
+                &self.world.materials,
+            ));
+        let clusters = self.find_clusters(1).into_iter()
+            .map(|cluster| BodyInfo::from_particles(BodyId::Cluster(cluster[0]), cluster.iter().map(|&idx| &self.particles[idx]), &self.world.materials));
+        objects.chain(clusters).collect()
+    }
+
+    /// Zusammenhängende Haufen ruhender, fester, nicht eingefrorener freier Partikel gleichen Materials.
+    /// Zwei Partikel hängen zusammen, wenn ihre Zellen in x und y höchstens `radius` auseinanderliegen:
+    /// Bei 1 zählen nur berührende Zellen (auch diagonal), bei 2 wird schon eine leere Zelle überbrückt.
+    /// Liefert Partikelindizes je Haufen, aufsteigend.
+    pub fn find_clusters(&self, radius: usize) -> Vec<Vec<usize>> {
+        let eligible = |p: &Particle| {
+            p.material.is_solid() && p.velocity == [0.0, 0.0] && !self.frozen_materials.contains(&p.material)
+        };
+        let r = radius as i32;
+        let mut visited = vec![false; self.particles.len()];
+        let mut clusters = Vec::new();
+
+        for start in 0..self.particles.len() {
+            if visited[start] || !eligible(&self.particles[start]) { continue; }
+            visited[start] = true;
+            let material = self.particles[start].material;
+            let mut cluster = vec![start];
+            let mut queue = VecDeque::from([start]);
+
+            while let Some(idx) = queue.pop_front() {
+                let (x, y) = World::cell_of(self.particles[idx].position);
+                for dy in -r..=r {
+                    for dx in -r..=r {
+                        let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+                        if nx < 0 || ny < 0 || nx >= self.world.width as i32 || ny >= self.world.height as i32 { continue; }
+                        let Some(neighbor_ref @ ParticleRef::Free(n, _)) = self.world.give_occupation_on_position(nx as usize, ny as usize) else { continue; };
+                        let Some(neighbor) = self.resolve_particle(neighbor_ref) else { continue; };
+                        if visited[n] || neighbor.material != material || !eligible(neighbor) { continue; }
+                        visited[n] = true;
+                        cluster.push(n);
+                        queue.push_back(n);
+                    }
+                }
+            }
+            cluster.sort_unstable();
+            clusters.push(cluster);
+        }
+        clusters
+    }
+
+    /// Macht jeden Haufen aus `find_clusters(radius)` mit mindestens `min_size` Partikeln zu einem ruhenden Objekt.
+    /// Überbrückte Lücken bleiben darin Luft. Die Partikel werden entfernt, ihre Indizes verschieben sich.
+    /// Liefert die neuen Objektindizes.
+    pub fn bond_clusters(&mut self, radius: usize, min_size: usize) -> Vec<usize> {
+        let clusters: Vec<Vec<usize>> = self.find_clusters(radius).into_iter()
+            .filter(|cluster| cluster.len() >= min_size.max(2))
+            .collect();
+        if clusters.is_empty() {
+            return Vec::new();
+        }
+
+        let fragment_data: Vec<Vec<([f32; 2], MaterialTyp)>> = clusters.iter()
+            .map(|cluster| cluster.iter().map(|&idx| (self.particles[idx].position, self.particles[idx].material)).collect())
+            .collect();
+
+        // Erst alle Partikel entfernen (von hinten), dann die Objekte eintragen, damit nichts überschrieben wird
+        let mut removed: Vec<usize> = clusters.into_iter().flatten().collect();
+        removed.sort_unstable_by(|a, b| b.cmp(a));
+        for idx in removed {
+            self.remove_particle(idx);
+        }
+
+        fragment_data.iter().filter_map(|data| {
+            let object = Object::new_from_fragment(self.object_counter + 1, data, [0.0, 0.0]).ok()?;
+            Some(self.push_object(object))
+        }).collect()
+    }
+
+    /// Frischer Beton, der noch nicht fest ist.
+    pub fn is_curing(&self, particle_id: i32) -> bool {
+        self.curing.contains_key(&particle_id)
+    }
+
+    /// Erosion: Schnelles Wasser nimmt ein angrenzendes freies, erodierbares Partikel als Sediment auf –
+    /// das Korn wird zu Wasser, das mit der Strömung fließt. Die Chance wächst mit Erodierbarkeit und Tempo.
+    /// Wird sedimentführendes Wasser langsamer als `SEDIMENT_DEPOSIT_SPEED`, lagert es das Korn an Ort und Stelle ab.
+    pub fn erode(&mut self) {
+        for w in 0..self.particles.len() {
+            let water = &self.particles[w];
+            if water.material != MaterialTyp::Wasser || self.frozen_materials.contains(&water.material) { continue; }
+            let speed = (water.velocity[0].powi(2) + water.velocity[1].powi(2)).sqrt();
+
+            if let Some(grain) = water.sediment {
+                if speed < SEDIMENT_DEPOSIT_SPEED {
+                    let water = &mut self.particles[w];
+                    water.material = grain;
+                    water.sediment = None;
+                }
+                continue;
+            }
+            if speed <= EROSION_MIN_SPEED { continue; }
+
+            let (x, y) = World::cell_of(water.position);
+            let (x, y) = (x as i32, y as i32);
+            for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= self.world.width as i32 || ny >= self.world.height as i32 { continue; }
+
+                let Some(neighbor_ref @ ParticleRef::Free(n, _)) = self.world.give_occupation_on_position(nx as usize, ny as usize) else { continue; };
+                let Some(neighbor) = self.resolve_particle(neighbor_ref) else { continue; };
+                if self.frozen_materials.contains(&neighbor.material) { continue; }
+                let chance = self.world.materials.erodibility(neighbor.material) * (speed - EROSION_MIN_SPEED);
+                if chance <= 0.0 || self.rng.gen::<f32>() >= chance { continue; }
+
+                let velocity = self.particles[w].velocity;
+                let grain = &mut self.particles[n];
+                grain.sediment = Some(grain.material);
+                grain.material = MaterialTyp::Wasser;
+                grain.velocity = velocity;
+                break;
+            }
+        }
+    }
+
+    /// Zerlegt ein Objekt in Bruchstücke: Einzelzellen werden freie Partikel, größere Stücke neue Objekte
+    /// mit Geschwindigkeit und Maserung des alten. Vertikal erhalten die Fragmente die Restgeschwindigkeit
+    /// des Aufpralls, also was die Bruchenergie übrig lässt. Das alte Objekt wird zerstört.
+    pub fn split_object(&mut self, object_idx: usize, fragments: &[Vec<(usize, usize)>]) -> Vec<Piece> {
+        let Some(obj) = self.live_object(object_idx) else { return Vec::new(); };
+        let old_velocity = obj.get_object_velocity();
+        let old_grain = obj.get_grain();
+        let residual_velocity = obj.get_residual_velocity();
+        let fragment_data: Vec<Vec<([f32; 2], MaterialTyp)>> = fragments.iter()
+            .map(|frag| obj.extract_fragment_data(frag))
+            .collect();
+
+        self.objects[object_idx].clear_from_world(&mut self.world);
+        self.objects[object_idx].is_destroyed = true;
+
+        fragment_data.into_iter().filter_map(|frag_data| {
+            if frag_data.len() == 1 {
+                let (pos, material) = frag_data[0];
+                Some(Piece::Particle(self.spawn_particle(pos, [0.0, residual_velocity], material)))
+            } else {
+                // Leere Fragmente ergeben kein Teil
+                let velocity = [old_velocity[0], residual_velocity];
+                let object = Object::new_from_fragment(self.object_counter + 1, &frag_data, velocity).ok()?;
+                Some(Piece::Object(self.push_object(object.with_grain(old_grain))))
+            }
+        }).collect()
+    }
+
+    /// Legt ein freies Partikel an, trägt es ins Grid ein und liefert seinen Index. IDs beginnen bei 1.
+    pub fn spawn_particle(&mut self, position: [f32; 2], velocity: [f32; 2], material: MaterialTyp) -> usize {
+        let idx = self.particles.len();
+        let particle_ref = ParticleRef::Free(idx, self.next_generation);
+        self.next_generation += 1;
+        self.particle_counter += 1;
+
+        let particle = Particle::new(self.particle_counter, position, velocity, material, particle_ref);
+        if material == MaterialTyp::Beton {
+            self.curing.insert(particle.id, self.clock.ticks() + BETON_CURE_TICKS as u64);
+        }
+        self.world.update_occupation_on_position(particle.position, particle.particle_ref);
+        self.world.update_mass_on_position(particle.position, particle.mass(&self.world.materials));
+        self.particles.push(particle);
+        idx
+    }
+
+    /// Wie `spawn_particle`, hält aber `max_particles` ein. An der Grenze entscheidet `cap_policy`: Bei `Reject`
+    /// oder ohne passendes ruhendes Partikel gibt es einen Fehler, sonst wird vorher eines per `remove_particle`
+    /// entfernt – Partikelindizes verschieben sich dabei.
+    pub fn try_spawn_particle(&mut self, position: [f32; 2], velocity: [f32; 2], material: MaterialTyp) -> Result<usize, String> {
+        if self.at_particle_cap() {
+            let victim = match self.cap_policy {
+                CapPolicy::Reject => None,
+                CapPolicy::RecycleOldest => self.resting_particles().min_by_key(|&idx| self.particles[idx].id),
+                CapPolicy::Merge => self.resting_particles()
+                    .filter_map(|idx| {
+                        let neighbors = self.free_neighbors(idx);
+                        // Bevorzugt ein Nachbar aus demselben Material nimmt die Masse auf
+                        let target = neighbors.iter().copied()
+                            .find(|&n| self.particles[n].material == self.particles[idx].material)
+                            .or(neighbors.first().copied())?;
+                        Some((idx, target, neighbors.len()))
+                    })
+                    .max_by_key(|&(_, _, count)| count)
+                    .map(|(idx, target, _)| {
+                        let mass = self.particles[idx].mass(&self.world.materials);
+                        let receiver = &mut self.particles[target];
+                        receiver.merged_mass += mass;
+                        self.world.update_mass_on_position(receiver.position, receiver.mass(&self.world.materials));
+                        idx
+                    }),
+            };
+            let Some(victim) = victim else {
+                return Err(format!("Partikelgrenze erreicht: {} ({:?})", self.max_particles, self.cap_policy));
+            };
+            self.remove_particle(victim);
+        }
+        Ok(self.spawn_particle(position, velocity, material))
+    }
+
+    /// Indizes der freien Partikel in den 8 Nachbarzellen des Partikels `idx`.
+    fn free_neighbors(&self, idx: usize) -> Vec<usize> {
+        let (x, y) = World::cell_of(self.particles[idx].position);
+        NEIGHBOR_OFFSETS.iter().filter_map(|&(dx, dy)| {
+            let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+            if nx < 0 || ny < 0 || nx >= self.world.width as i32 || ny >= self.world.height as i32 { return None; }
+            let particle_ref @ ParticleRef::Free(n, _) = self.world.give_occupation_on_position(nx as usize, ny as usize)? else { return None; };
+            self.resolve_particle(particle_ref).map(|_| n)
+        }).collect()
+    }
+
+    /// Ob `try_spawn_particle` nur noch nach `cap_policy` Platz schafft.
+    pub fn at_particle_cap(&self) -> bool {
+        self.particles.len() >= self.max_particles
+    }
+
+    /// Indizes ruhender, nicht eingefrorener freier Partikel.
+    fn resting_particles(&self) -> impl Iterator<Item = usize> + '_ {
+        self.particles.iter().enumerate()
+            .filter(|(_, p)| p.velocity == [0.0, 0.0] && !self.frozen_materials.contains(&p.material))
+            .map(|(idx, _)| idx)
+    }
+
+    /// Legt ein rechteckiges Objekt aus einem Material an, trägt es ins Grid ein und liefert seinen Index.
+    /// IDs beginnen bei 1. Objekte über `max_object_cells` werden abgelehnt, das Grid bleibt unverändert.
+    pub fn spawn_object(&mut self, anchor: [f32; 2], material: MaterialTyp, h: usize, w: usize) -> Result<usize, String> {
+        self.check_object_size(h, w)?;
+        self.insert_object(anchor, material, h, w)
+    }
+
+    /// Wie `spawn_object`, aber als 4x4-Quadrant aus Holz, Stein, Metall und Sand.
+    pub fn spawn_quadrant(&mut self, anchor: [f32; 2]) -> Result<usize, String> {
+        self.spawn_quadrant_with(anchor, QUADRANT_PRESETS[0])
+    }
+
+    /// Wie `spawn_quadrant` mit eigenen Vierteln, siehe `Object::new_quadrant_with`.
+    pub fn spawn_quadrant_with(&mut self, anchor: [f32; 2], materials: [MaterialTyp; 4]) -> Result<usize, String> {
+        self.check_object_size(4, 4)?;
+        let object = Object::new_quadrant_with(self.object_counter + 1, anchor, [0.0, 0.0], materials);
+        Ok(self.push_object(object))
+    }
+
+    /// Stresstest: Raster aus `cols`×`rows` Quadranten-Blöcken (4×4), linke untere Ecke bei `origin` (x, y),
+    /// mit `gap` Zellen Abstand. Plätze, die nicht ins Grid passen oder belegt sind, werden übersprungen.
+    /// `launch` > 0 schießt alle Blöcke mit dieser Geschwindigkeit nach unten. Liefert die neuen Indizes.
+    pub fn spawn_object_grid(&mut self, origin: (usize, usize), cols: usize, rows: usize, gap: usize, launch: f32) -> Vec<usize> {
+        let mut spawned = Vec::new();
+        for row in 0..rows {
+            for col in 0..cols {
+                let x = origin.0 + col * (4 + gap);
+                let y = origin.1 + row * (4 + gap);
+                if x + 4 > self.world.width || y + 4 > self.world.height { continue; }
+                if !self.occupants_in_rect((x, y), (x + 3, y + 3)).is_empty() { continue; }
+
+                let Ok(obj_idx) = self.spawn_quadrant([x as f32, y as f32]) else { continue; };
+                self.objects[obj_idx].velocity = [0.0, -launch.max(0.0)];
+                spawned.push(obj_idx);
+            }
+        }
+        spawned
+    }
+
+    fn check_object_size(&self, h: usize, w: usize) -> Result<(), String> {
+        let cells = h.saturating_mul(w);
+        if cells == 0 {
+            return Err(format!("Objekt ohne Zellen: {}x{}", h, w));
+        }
+        if cells > self.max_object_cells {
+            return Err(format!("Objekt zu groß: {} Zellen, erlaubt sind {}", cells, self.max_object_cells));
+        }
+        Ok(())
+    }
+
+    fn insert_object(&mut self, anchor: [f32; 2], material: MaterialTyp, h: usize, w: usize) -> Result<usize, String> {
+        let object = Object::new(self.object_counter + 1, anchor, [0.0, 0.0], material, h, w)?;
+        Ok(self.push_object(object))
+    }
+
+    /// Trägt ein fertiges Objekt unter dem nächsten Index ein, sofern es ins Grid passt und nichts überdeckt.
+    fn place_object(&mut self, mut object: Object) -> Result<(), String> {
+        // Vor `fits` umnummerieren, sonst zählten die Zellen eines Objekts mit derselben ID als eigene
+        if object.object_id <= self.object_counter {
+            object.renumber(self.object_counter + 1);
+        }
+        if !object.fits(&self.world) {
+            return Err(format!("Objekt bei {:?} ragt aus dem Grid oder überdeckt etwas", object.position));
+        }
+        self.push_object(object);
+        Ok(())
+    }
+
+    /// Hängt ein Objekt an. IDs werden nie wiederverwendet: Eine ID bis `object_counter` wird durch die nächste
+    /// freie ersetzt. Damit steigen die IDs mit dem Index, worauf `object_index` baut.
+    fn push_object(&mut self, mut object: Object) -> usize {
+        if object.object_id <= self.object_counter {
+            object.renumber(self.object_counter + 1);
+        }
+        self.object_counter = object.object_id;
+        object.register_in_world(&mut self.world);
+        self.objects.push(object);
+        self.objects.len() - 1
+    }
+
+    /// Schlägt die Zelle (i, j) aus dem Objekt an `object_idx` (siehe `Object::chip`) und legt an ihrer Stelle ein
+    /// freies Partikel an. Zerfällt das Objekt dadurch, wird es wie bei einem Bruch per `split_object` zerlegt;
+    /// war es die letzte Zelle, ist das Objekt danach zerstört. Liefert den Partikelindex und ggf. die Zerlegung.
+    pub fn chip_object(&mut self, object_idx: usize, i: usize, j: usize) -> Option<(usize, Option<Split>)> {
+        let object = self.objects.get_mut(object_idx).filter(|obj| !obj.is_destroyed)?;
+        let (position, material) = object.chip(i, j, &mut self.world)?;
+        let fragments = object.find_fragments(&[]);
+        if fragments.is_empty() {
+            object.is_destroyed = true;
+        }
+        let idx = self.spawn_particle(position, [0.0, 0.0], material);
+
+        let split = (fragments.len() > 1).then(|| Split { object_idx, pieces: self.split_object(object_idx, &fragments) });
+        Some((idx, split))
+    }
+
+    /// Stempelt eine Kopie des Objekts an `object_idx` an den Anker `anchor` (siehe `Object::clone_at`) und
+    /// liefert ihren Index.
+    pub fn clone_object(&mut self, object_idx: usize, anchor: [f32; 2]) -> Result<usize, String> {
+        let Some(object) = self.objects.get(object_idx).filter(|obj| !obj.is_destroyed) else {
+            return Err(format!("Kein lebendes Objekt mit Index {}", object_idx));
+        };
+        let clone = object.clone_at(self.object_counter + 1, anchor, &mut self.world)?;
+        Ok(self.push_object(clone))
+    }
+
+    /// Vergrößert das Objekt an `object_idx` an Ort und Stelle (siehe `Object::scale`); Index, ID und Anker bleiben.
+    /// Fehler, wenn das Ergebnis `max_object_cells` überschreitet, aus dem Grid ragt oder etwas überdeckt;
+    /// Objekt und Grid bleiben dann unverändert.
+    pub fn scale_object(&mut self, object_idx: usize, factor: usize) -> Result<(), String> {
+        let Some(object) = self.objects.get(object_idx).filter(|obj| !obj.is_destroyed) else {
+            return Err(format!("Kein lebendes Objekt mit Index {}", object_idx));
+        };
+        let factor = factor.max(1);
+        self.check_object_size(object.object_h.saturating_mul(factor), object.object_w.saturating_mul(factor))?;
+
+        let scaled = object.scale(factor);
+        object.clear_from_world(&mut self.world);
+        if !scaled.fits(&self.world) {
+            self.objects[object_idx].register_in_world(&mut self.world);
+            return Err(format!("Vergrößertes Objekt bei {:?} ragt aus dem Grid oder überdeckt etwas", scaled.position));
+        }
+        scaled.register_in_world(&mut self.world);
+        self.objects[object_idx] = scaled;
+        Ok(())
+    }
+
+    /// Füllt alle freien Zellen im Rechteck `min..=max` (x, y) mit freien Partikeln.
+    /// Belegte Zellen und Zellen außerhalb des Grids werden übersprungen.
+    pub fn spawn_particles_rect(&mut self, min: (usize, usize), max: (usize, usize), material: MaterialTyp) -> Vec<usize> {
+        let mut spawned = Vec::new();
+        let max_x = max.0.min(self.world.width.saturating_sub(1));
+        let max_y = max.1.min(self.world.height.saturating_sub(1));
+
+        for y in min.1..=max_y {
+            for x in min.0..=max_x {
+                if self.world.give_occupation_on_position(x, y).is_some() { continue; }
+                spawned.push(self.spawn_particle([x as f32, y as f32], [0.0, 0.0], material));
+            }
+        }
+        spawned
+    }
+
+    /// Entfernt ein Partikel per `swap_remove`. Das nachgerückte Partikel behält seine Generation,
+    /// bekommt aber den neuen Index – alte Referenzen auf beide Plätze werden dadurch als veraltet erkannt.
+    pub fn remove_particle(&mut self, idx: usize) -> Option<Particle> {
+        if idx >= self.particles.len() {
+            return None;
+        }
+
+        let removed = self.particles.swap_remove(idx);
+        self.world.clear_occupation_on_position(removed.position);
+        self.world.clear_mass_on_position(removed.position);
+
+        if let Some(moved) = self.particles.get_mut(idx) {
+            if let ParticleRef::Free(_, generation) = moved.particle_ref {
+                moved.particle_ref = ParticleRef::Free(idx, generation);
+            }
+            self.world.update_occupation_on_position(moved.position, moved.particle_ref);
+        }
+        Some(removed)
+    }
+
+    /// Wie `World::predicted_path` mit der Grundschwerkraft und `max_speed` dieser Simulation.
+    pub fn predicted_path(&self, particle: &Particle, steps: usize) -> Vec<(usize, usize)> {
+        self.world.predicted_path(particle, self.gravity, self.max_speed, steps)
+    }
+
+    /// Schwerkraft an `pos`: Grundschwerkraft plus alle `gravity_sources`.
+    pub fn net_gravity(&self, pos: [f32; 2]) -> [f32; 2] {
+        sum_gravity(self.gravity, &self.gravity_sources, pos)
+    }
+
+    pub fn add_gravity_source(&mut self, source: GravitySource) {
+        self.gravity_sources.push(source);
+    }
+
+    /// Nimmt ein Objekt aus dem Grid und markiert es als zerstört. Der Platz im Vektor bleibt bis `gc_objects`.
+    pub fn remove_object(&mut self, object_idx: usize) -> bool {
+        if self.live_object(object_idx).is_none() {
+            return false;
+        }
+        self.objects[object_idx].clear_from_world(&mut self.world);
+        self.objects[object_idx].is_destroyed = true;
+        true
+    }
+
+    /// Entfernt zerstörte Objekte aus `objects`. Die `object_id`s und damit alle `InObject`-Referenzen bleiben
+    /// gültig. Liefert die Zuordnung alter → neuer Index (`None` für entfernte), damit ein Frontend seine Indizes
+    /// nachziehen kann.
+    pub fn gc_objects(&mut self) -> Vec<Option<usize>> {
+        let mut next = 0;
+        let remap: Vec<Option<usize>> = self.objects.iter().map(|obj| {
+            if obj.is_destroyed {
+                None
+            } else {
+                next += 1;
+                Some(next - 1)
+            }
+        }).collect();
+
+        self.objects.retain(|obj| !obj.is_destroyed);
+        remap
+    }
+
+    /// Index des Objekts mit dieser ID, auch wenn es zerstört ist; `None` nach `gc_objects` oder für unbekannte IDs.
+    pub fn object_index(&self, object_id: i32) -> Option<usize> {
+        index_of_object(&self.objects, object_id)
+    }
+
+    /// Lebendes Objekt mit dieser ID, etwa aus einer `InObject`-Referenz.
+    pub fn live_object_by_id(&self, object_id: i32) -> Option<&Object> {
+        self.object_index(object_id).and_then(|object_idx| self.live_object(object_idx))
+    }
+
+    /// Löst eine Grid-Referenz auf ein freies Partikel auf; `None`, wenn sie veraltet ist.
+    pub fn resolve_particle(&self, particle_ref: ParticleRef) -> Option<&Particle> {
+        match particle_ref {
+            ParticleRef::Free(idx, _) => self.particles.get(idx).filter(|p| p.particle_ref == particle_ref),
+            _ => None,
+        }
+    }
+
+    /// Objekt am Index, sofern es existiert und nicht zerstört ist.
+    pub fn live_object(&self, object_idx: usize) -> Option<&Object> {
+        self.objects.get(object_idx).filter(|obj| !obj.is_destroyed)
+    }
+
+    /// Ersetzt die Materialwerte dieser Simulation (z.B. aus materials.ron) und trägt die neuen Massen ins Grid ein.
+    pub fn set_materials(&mut self, materials: MaterialTable) {
+        self.world.materials = materials;
+        for p in &self.particles {
+            self.world.update_mass_on_position(p.position, p.mass(&self.world.materials));
+        }
+        for obj in self.objects.iter().filter(|obj| !obj.is_destroyed) {
+            obj.register_in_world(&mut self.world);
+        }
+    }
+
+    pub fn is_pinned(&self, object_idx: usize) -> bool {
+        self.objects.get(object_idx)
+            .is_some_and(|obj| self.joints.iter().any(|joint| joint.object_id == obj.object_id))
+    }
+
+    /// Löst alle Gelenke auf: Liegt der Schwerpunkt nicht unter dem Drehpunkt, schwingt das Objekt
+    /// pro Aufruf um eine Vierteldrehung in Richtung des Schwerkraft-Drehmoments. Danach wird die
+    /// Ankerzelle auf den Drehpunkt gesetzt. Ist die neue Lage blockiert, bleibt das Objekt wie es war.
+    /// Liefert die tatsächlich ausgeführten Drehungen als (object_idx, clockwise).
+    pub fn resolve_joints(&mut self) -> Vec<(usize, bool)> {
+        self.resolve_joints_except(&[])
+    }
+
+    /// Wie `resolve_joints`, lässt aber Objekte mit `skip[object_idx] == true` unangetastet.
+    fn resolve_joints_except(&mut self, skip: &[bool]) -> Vec<(usize, bool)> {
+        let mut rotations = Vec::new();
+        for k in 0..self.joints.len() {
+            let joint = self.joints[k];
+            let Some(obj_idx) = self.objects.iter().position(|o| o.object_id == joint.object_id && !o.is_destroyed) else { continue; };
+            if skip.get(obj_idx).copied().unwrap_or(false) { continue; }
+
+            let obj = &mut self.objects[obj_idx];
+            obj.clear_from_world(&mut self.world);
+            let backup = obj.clone();
+            let mut anchor_cell = joint.anchor_cell;
+            let mut rotation = None;
+
+            let com = obj.center_of_mass(&self.world.materials);
+            let gravity = sum_gravity(self.gravity, &self.gravity_sources, com);
+            let r = [com[0] - joint.world_point[0], com[1] - joint.world_point[1]];
+            let along = r[0] * gravity[0] + r[1] * gravity[1];
+            let torque = r[0] * gravity[1] - r[1] * gravity[0];
+            if along < torque.abs() {
+                let clockwise = torque < 0.0;
+                anchor_cell = Object::rotated_cell(obj.object_h, obj.object_w, anchor_cell.0, anchor_cell.1, clockwise);
+                obj.rotate_quarter(clockwise);
+                rotation = Some((obj_idx, clockwise));
+            }
+
+            obj.move_anchor([joint.world_point[0] - anchor_cell.1 as f32, joint.world_point[1] - anchor_cell.0 as f32]);
+            obj.velocity = [0.0, 0.0];
+            if obj.fits(&self.world) {
+                self.joints[k].anchor_cell = anchor_cell;
+                rotations.extend(rotation);
+            } else {
+                *obj = backup;
+            }
+            obj.register_in_world(&mut self.world);
+        }
+        rotations
+    }
+
+    /// Material der Zelle (x, y); `None` für leere, statische oder ungültige Zellen.
+    pub fn material_at(&self, x: usize, y: usize) -> Option<MaterialTyp> {
+        if x >= self.world.width || y >= self.world.height {
+            return None;
+        }
+        match self.world.give_occupation_on_position(x, y) {
+            Some(particle_ref @ ParticleRef::Free(_, _)) => self.resolve_particle(particle_ref).map(|p| p.material),
+            Some(ParticleRef::InObject(object_id, i, j)) => self.live_object_by_id(object_id).map(|obj| obj.get_particle_at(i, j).material),
+            _ => None,
+        }
+    }
+
+    /// Mittlere Reibung der Unterlage eines Objekts; `None`, wenn es nirgends aufliegt.
+    pub fn support_friction(&self, object_idx: usize) -> Option<f32> {
+        let obj = self.live_object(object_idx)?;
+        let frictions: Vec<f32> = obj.cells_below().into_iter()
+            .filter(|&(x, y)| x < self.world.width && y < self.world.height)
+            .filter_map(|(x, y)| match self.world.give_occupation_on_position(x, y) {
+                Some(ParticleRef::Static) => Some(STATIC_FRICTION),
+                Some(ParticleRef::InObject(id, _, _)) if id == obj.object_id => None,
+                Some(_) => self.material_at(x, y).map(|m| self.world.materials.friction(m)),
+                None => None,
+            })
+            .collect();
+
+        if frictions.is_empty() {
+            return None;
+        }
+        Some(frictions.iter().sum::<f32>() / frictions.len() as f32)
+    }
+
+    /// Worauf ein von ganz oben in Spalte x fallengelassenes Teil landen würde (z.B. für eine Spawn-Vorschau).
+    pub fn trace_drop(&self, x: usize) -> Option<ParticleRef> {
+        let y = self.world.column_surface(x)?;
+        self.world.give_occupation_on_position(x, y)
+    }
+
+    /// Lebendes Objekt, das die Weltzelle (x, y) belegt, samt Gitterkoordinaten (i, j) im Objekt.
+    pub fn object_at(&self, x: usize, y: usize) -> Option<(&Object, usize, usize)> {
+        if x >= self.world.width || y >= self.world.height {
+            return None;
+        }
+        match self.world.give_occupation_on_position(x, y) {
+            Some(ParticleRef::InObject(object_id, i, j)) => self.live_object_by_id(object_id).map(|obj| (obj, i, j)),
+            _ => None,
+        }
+    }
+
+    /// Hüllrechteck aller Inhalte als (min_x, min_y, max_x, max_y), beide Ecken eingeschlossen: freie Partikel,
+    /// Zellen lebender Objekte und per `freeze_region` eingefrorene Zellen. Übrige statische Zellen (Boden,
+    /// Wände) zählen nicht. Geht über die Teile statt über das Grid; `None`, wenn nichts da ist.
+    pub fn content_bounds(&self) -> Option<(usize, usize, usize, usize)> {
+        let particles = self.particles.iter().map(|p| World::cell_of(p.position));
+        let objects = self.objects.iter()
+            .filter(|obj| !obj.is_destroyed)
+            .flat_map(|obj| obj.get_object_elements())
+            .filter(|p| p.material != MaterialTyp::Luft)
+            .map(|p| World::cell_of(p.position));
+        let frozen = self.frozen_cells.keys().copied();
+
+        particles.chain(objects).chain(frozen).fold(None, |bounds, (x, y)| match bounds {
+            None => Some((x, y, x, y)),
+            Some((x0, y0, x1, y1)) => Some((x0.min(x), y0.min(y), x1.max(x), y1.max(y))),
+        })
+    }
+
+    /// Belegte Zellen im Rechteck zwischen den Ecken `a` und `b` (jeweils (x, y), inklusive, in beliebiger
+    /// Reihenfolge) als (x, y, Referenz), zeilenweise von unten. Teile außerhalb der Welt werden abgeschnitten.
+    pub fn occupants_in_rect(&self, a: (usize, usize), b: (usize, usize)) -> Vec<(usize, usize, ParticleRef)> {
+        let mut occupants = Vec::new();
+        if self.world.width == 0 || self.world.height == 0 {
+            return occupants;
+        }
+        let (x0, x1) = (a.0.min(b.0), a.0.max(b.0).min(self.world.width - 1));
+        let (y0, y1) = (a.1.min(b.1), a.1.max(b.1).min(self.world.height - 1));
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                if let Some(particle_ref) = self.world.give_occupation_on_position(x, y) {
+                    occupants.push((x, y, particle_ref));
+                }
+            }
+        }
+        occupants
+    }
+
+    /// Friert das Rechteck ein: Freie Partikel darin und jedes Objekt, das hineinragt (als Ganzes), werden zu
+    /// statischen Zellen. Das Material wird für `unfreeze_region` gemerkt. Liefert die neuen statischen Zellen.
+    /// Achtung: Partikel werden entfernt, die Indizes der übrigen können sich dadurch ändern.
+    pub fn freeze_region(&mut self, a: (usize, usize), b: (usize, usize)) -> Vec<(usize, usize, MaterialTyp)> {
+        let mut particle_idxs = Vec::new();
+        let mut object_idxs = Vec::new();
+        for (_, _, particle_ref) in self.occupants_in_rect(a, b) {
+            match particle_ref {
+                ParticleRef::Free(idx, _) if self.resolve_particle(particle_ref).is_some() => particle_idxs.push(idx),
+                ParticleRef::InObject(id, _, _) => object_idxs.extend(self.object_index(id).filter(|&idx| self.live_object(idx).is_some())),
+                _ => {}
+            }
+        }
+        object_idxs.sort_unstable();
+        object_idxs.dedup();
+
+        let mut frozen = Vec::new();
+        for obj_idx in object_idxs {
+            let cells: Vec<([f32; 2], MaterialTyp)> = self.objects[obj_idx].get_object_elements().into_iter()
+                .filter(|p| p.material != MaterialTyp::Luft)
+                .map(|p| (p.position, p.material))
+                .collect();
+            self.objects[obj_idx].clear_from_world(&mut self.world);
+            self.objects[obj_idx].is_destroyed = true;
+            frozen.extend(cells.into_iter().filter_map(|(pos, material)| self.freeze_cell(pos, material)));
+        }
+
+        // Von hinten entfernen, damit `swap_remove` keine noch ausstehenden Indizes verschiebt
+        particle_idxs.sort_unstable_by(|a, b| b.cmp(a));
+        for idx in particle_idxs {
+            if let Some(particle) = self.remove_particle(idx) {
+                frozen.extend(self.freeze_cell(particle.position, particle.material));
+            }
+        }
+        frozen
+    }
+
+    /// Taut alle per `freeze_region` eingefrorenen Zellen im Rechteck wieder zu freien Partikeln ihres
+    /// ursprünglichen Materials auf. Andere statische Zellen (z.B. der Boden) bleiben. Liefert die neuen Indizes.
+    pub fn unfreeze_region(&mut self, a: (usize, usize), b: (usize, usize)) -> Vec<usize> {
+        let cells: Vec<(usize, usize, MaterialTyp)> = self.occupants_in_rect(a, b).into_iter()
+            .filter(|&(_, _, particle_ref)| particle_ref == ParticleRef::Static)
+            .filter_map(|(x, y, _)| self.frozen_cells.get(&(x, y)).map(|&material| (x, y, material)))
+            .collect();
+
+        cells.into_iter().map(|(x, y, material)| {
+            let pos = [x as f32, y as f32];
+            self.frozen_cells.remove(&(x, y));
+            self.world.clear_occupation_on_position(pos);
+            self.world.clear_mass_on_position(pos);
+            self.spawn_particle(pos, [0.0, 0.0], material)
+        }).collect()
+    }
+
+    /// Legt bei (x, y) ein Förderband als statische Zelle an oder dreht ein bestehendes um. Fehler außerhalb
+    /// des Grids und auf belegten Zellen, die kein Förderband sind.
+    pub fn place_conveyor(&mut self, x: usize, y: usize, direction: Conveyor) -> Result<(), String> {
+        if x >= self.world.width || y >= self.world.height {
+            return Err(format!("Förderband außerhalb des Grids: ({}, {})", x, y));
+        }
+        if !self.conveyors.contains_key(&(x, y)) && self.world.give_occupation_on_position(x, y).is_some() {
+            return Err(format!("Zelle ({}, {}) ist belegt", x, y));
+        }
+        let pos = [x as f32, y as f32];
+        self.world.update_occupation_on_position(pos, ParticleRef::Static);
+        self.world.update_mass_on_position(pos, SCENE_STATIC_MASS);
+        self.conveyors.insert((x, y), direction);
+        Ok(())
+    }
+
+    /// Entfernt das Förderband bei (x, y); die Zelle wird frei.
+    pub fn remove_conveyor(&mut self, x: usize, y: usize) -> bool {
+        if self.conveyors.remove(&(x, y)).is_none() {
+            return false;
+        }
+        let pos = [x as f32, y as f32];
+        self.world.clear_occupation_on_position(pos);
+        self.world.clear_mass_on_position(pos);
+        true
+    }
+
+    pub fn conveyor_at(&self, x: usize, y: usize) -> Option<Conveyor> {
+        self.conveyors.get(&(x, y)).copied()
+    }
+
+    /// Freie Partikel, die in Schwerkraftrichtung direkt auf einem Förderband liegen, rücken eine Zelle in
+    /// dessen Richtung, sofern die Zelle frei ist. Eingefrorene Materialien bleiben liegen.
+    pub fn convey(&mut self) {
+        if self.conveyors.is_empty() { return; }
+
+        let down = if self.gravity[1] > 0.0 { 1 } else { -1 };
+        for idx in 0..self.particles.len() {
+            let p = &self.particles[idx];
+            if self.frozen_materials.contains(&p.material) { continue; }
+            let (x, y) = World::cell_of(p.position);
+            let below = y as i32 + down;
+            if below < 0 { continue; }
+            let Some(direction) = self.conveyor_at(x, below as usize) else { continue; };
+
+            let nx = x as i32 + direction.dx();
+            if nx < 0 || nx >= self.world.width as i32 || self.world.give_occupation_on_position(nx as usize, y).is_some() { continue; }
+            let p = &mut self.particles[idx];
+            self.world.clear_occupation_on_position(p.position);
+            self.world.clear_mass_on_position(p.position);
+            p.position[0] = nx as f32;
+            self.world.update_occupation_on_position(p.position, p.particle_ref);
+            self.world.update_mass_on_position(p.position, p.mass(&self.world.materials));
+        }
+    }
+
+    fn freeze_cell(&mut self, pos: [f32; 2], material: MaterialTyp) -> Option<(usize, usize, MaterialTyp)> {
+        let (x, y) = World::cell_of(pos);
+        if x >= self.world.width || y >= self.world.height {
+            return None;
+        }
+        self.world.update_occupation_on_position(pos, ParticleRef::Static);
+        self.world.update_mass_on_position(pos, self.world.materials.density(material));
+        self.frozen_cells.insert((x, y), material);
+        Some((x, y, material))
+    }
+
+    /// Anzahl belegter Zellen je Material, freie Partikel und Objektzellen zusammen.
+    pub fn count_by_material(&self) -> HashMap<MaterialTyp, usize> {
+        let mut counts = HashMap::new();
+        for y in 0..self.world.height {
+            for x in 0..self.world.width {
+                if let Some(material) = self.material_at(x, y) {
+                    *counts.entry(material).or_insert(0) += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// Gesamtmasse aller freien Partikel und lebenden Objektzellen (ohne Luft).
+    pub fn total_mass(&self) -> f32 {
+        let particles: f32 = self.particles.iter().map(|p| p.mass(&self.world.materials)).sum();
+        let objects: f32 = self.objects.iter()
+            .filter(|obj| !obj.is_destroyed)
+            .flat_map(|obj| obj.get_object_elements())
+            .filter(|p| p.material != MaterialTyp::Luft)
+            .map(|p| p.mass(&self.world.materials))
+            .sum();
+        particles + objects
+    }
+
+    /// Summe von ½·m·v² über freie Partikel und lebende Objekte.
+    pub fn kinetic_energy(&self) -> f32 {
+        let speed_sq = |v: [f32; 2]| v[0] * v[0] + v[1] * v[1];
+        let particles: f32 = self.particles.iter().map(|p| 0.5 * p.mass(&self.world.materials) * speed_sq(p.velocity)).sum();
+        let objects: f32 = self.objects.iter()
+            .filter(|obj| !obj.is_destroyed)
+            .map(|obj| 0.5 * obj.mass(&self.world.materials) * speed_sq(obj.velocity))
+            .sum();
+        particles + objects
+    }
+
+    /// Höchster Druck im Grid, Stand der letzten Druckberechnung.
+    pub fn max_pressure(&self) -> f32 {
+        self.world.grid.iter().flatten().map(|cell| cell.2).fold(0.0, f32::max)
+    }
+
+    /// Merkt sich die aktuelle Gesamtmasse als Referenz für `stability_check`.
+    pub fn record_mass_baseline(&mut self) {
+        self.mass_baseline = Some(self.total_mass());
+    }
+
+    /// Prüft auf NaN/unendliche Werte, Teile außerhalb der Welt, verwaiste Grid-Referenzen, Grid-Masse gegen Teile-Masse und –
+    /// falls eine Referenz gesetzt ist – auf Massenverlust gegenüber `record_mass_baseline`.
+    pub fn stability_check(&self) -> StabilityReport {
+        let mut issues = Vec::new();
+        let (w, h) = (self.world.width as f32, self.world.height as f32);
+        let in_bounds = |pos: [f32; 2]| pos[0] >= 0.0 && pos[1] >= 0.0 && pos[0] < w && pos[1] < h;
+
+        for (idx, p) in self.particles.iter().enumerate() {
+            if !p.position.iter().chain(&p.velocity).all(|v| v.is_finite()) {
+                issues.push(StabilityIssue::NonFinite(Piece::Particle(idx)));
+            }
+            if !in_bounds(p.position) {
+                issues.push(StabilityIssue::OutOfBounds(Piece::Particle(idx)));
+            }
+        }
+
+        for (idx, obj) in self.objects.iter().enumerate().filter(|(_, obj)| !obj.is_destroyed) {
+            if !obj.position.iter().chain(&obj.velocity).all(|v| v.is_finite()) {
+                issues.push(StabilityIssue::NonFinite(Piece::Object(idx)));
+            }
+            let far_corner = [obj.position[0] + obj.object_w as f32 - 1.0, obj.position[1] + obj.object_h as f32 - 1.0];
+            if !in_bounds(obj.position) || !in_bounds(far_corner) {
+                issues.push(StabilityIssue::OutOfBounds(Piece::Object(idx)));
+            }
+        }
+
+        for y in 0..self.world.height {
+            for x in 0..self.world.width {
+                let dangling = match self.world.give_occupation_on_position(x, y) {
+                    Some(particle_ref @ ParticleRef::Free(_, _)) => self.resolve_particle(particle_ref).is_none(),
+                    Some(ParticleRef::InObject(object_id, i, j)) => self.live_object_by_id(object_id)
+                        .is_none_or(|obj| i >= obj.object_h || j >= obj.object_w),
+                    _ => false,
+                };
+                if dangling {
+                    issues.push(StabilityIssue::DanglingRef(x, y));
+                }
+            }
+        }
+
+        let expected = self.total_mass();
+        let grid: f32 = self.world.grid.iter().flatten()
+            .filter(|cell| cell.0 != Some(ParticleRef::Static))
+            .map(|cell| cell.1)
+            .sum();
+        if (grid - expected).abs() > MASS_DRIFT_TOLERANCE * expected.max(1.0) {
+            issues.push(StabilityIssue::GridMassMismatch { grid, expected });
+        }
+
+        if let Some(baseline) = self.mass_baseline {
+            let current = expected;
+            if (current - baseline).abs() > MASS_DRIFT_TOLERANCE * baseline.max(1.0) || !current.is_finite() {
+                issues.push(StabilityIssue::MassDrift { baseline, current });
+            }
+        }
+
+        StabilityReport { issues }
+    }
+
+    /// Textbild der Welt, oberste Zeile zuerst: `#` statisch, Leerzeichen leer, sonst `MaterialTyp::symbol`.
+    pub fn render_ascii(&self) -> String {
+        let mut out = String::with_capacity((self.world.width + 1) * self.world.height);
+        for y in (0..self.world.height).rev() {
+            for x in 0..self.world.width {
+                out.push(match self.world.give_occupation_on_position(x, y) {
+                    Some(ParticleRef::Static) => '#',
+                    None => ' ',
+                    Some(_) => self.material_at(x, y).map_or('?', |m| m.symbol()),
+                });
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Baut eine Simulation Schritt für Schritt auf und trägt dabei alles korrekt ins Grid ein.
+pub struct SimulationBuilder {
+    height: usize,
+    width: usize,
+    gravity: [f32; 2],
+    seed: Option<u64>,
+    particles: Vec<([f32; 2], [f32; 2], MaterialTyp)>,
+    objects: Vec<([f32; 2], MaterialTyp, usize, usize)>,
+    statics: Vec<(usize, usize, f32)>,
+    settle_bottom_up: bool,
+    force_profile: ForceProfile,
+    step_config: StepConfig,
+    slide_order: SlideOrder,
+    dt: f32,
+    emitters: Vec<Emitter>,
+}
+
+impl Default for SimulationBuilder {
+    fn default() -> Self {
+        SimulationBuilder {
+            height: 100,
+            width: 120,
+            gravity: [0.0, -1.0],
+            seed: None,
+            particles: Vec::new(),
+            objects: Vec::new(),
+            statics: Vec::new(),
+            settle_bottom_up: false,
+            force_profile: ForceProfile::Inverse,
+            step_config: StepConfig::default(),
+            slide_order: SlideOrder::Alternating,
+            dt: DEFAULT_DT,
+            emitters: Vec::new(),
+        }
+    }
+}
+
+impl SimulationBuilder {
+    pub fn new() -> SimulationBuilder {
+        SimulationBuilder::default()
+    }
+
+    pub fn world(mut self, h: usize, w: usize) -> Self {
+        self.height = h;
+        self.width = w;
+        self
+    }
+
+    pub fn gravity(mut self, gravity: [f32; 2]) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn add_particle(mut self, position: [f32; 2], velocity: [f32; 2], material: MaterialTyp) -> Self {
+        self.particles.push((position, velocity, material));
+        self
+    }
+
+    pub fn add_object(mut self, anchor: [f32; 2], material: MaterialTyp, h: usize, w: usize) -> Self {
+        self.objects.push((anchor, material, h, w));
+        self
+    }
+
+    pub fn add_static(mut self, x: usize, y: usize, mass: f32) -> Self {
+        self.statics.push((x, y, mass));
+        self
+    }
+
+    pub fn settle_bottom_up(mut self, enabled: bool) -> Self {
+        self.settle_bottom_up = enabled;
+        self
+    }
+
+    pub fn force_profile(mut self, force_profile: ForceProfile) -> Self {
+        self.force_profile = force_profile;
+        self
+    }
+
+    pub fn step_config(mut self, step_config: StepConfig) -> Self {
+        self.step_config = step_config;
+        self
+    }
+
+    pub fn slide_order(mut self, slide_order: SlideOrder) -> Self {
+        self.slide_order = slide_order;
+        self
+    }
+
+    pub fn dt(mut self, dt: f32) -> Self {
+        self.dt = dt;
+        self
+    }
+
+    pub fn add_emitter(mut self, emitter: Emitter) -> Self {
+        self.emitters.push(emitter);
+        self
+    }
+
+    /// Reihenfolge der Registrierung: statische Zellen, Objekte, freie Partikel. IDs beginnen bei 1.
+    /// Fehler bei Objekten ohne Zellen (h oder w gleich 0).
+    pub fn build(self) -> Result<Simulation, String> {
+        let mut sim = Simulation::new(self.height, self.width, self.gravity);
+        if let Some(seed) = self.seed {
+            sim.rng = StdRng::seed_from_u64(seed);
+        }
+        sim.settle_bottom_up = self.settle_bottom_up;
+        sim.force_profile = self.force_profile;
+        sim.step_config = self.step_config;
+        sim.slide_order = self.slide_order;
+        sim.dt = self.dt;
+        sim.emitters = self.emitters;
+
+        for (x, y, mass) in self.statics {
+            let pos = [x as f32, y as f32];
+            sim.world.update_occupation_on_position(pos, ParticleRef::Static);
+            sim.world.update_mass_on_position(pos, mass);
+        }
+
+        for (anchor, material, h, w) in self.objects {
+            sim.insert_object(anchor, material, h, w)?;
+        }
+
+        for (position, velocity, material) in self.particles {
+            sim.spawn_particle(position, velocity, material);
+        }
+
+        Ok(sim)
+    }
+}
+
+/// Masse der statischen Zellen von `SceneBuilder`, wie beim Boden im Fenster.
+const SCENE_STATIC_MASS: f32 = 1000.0;
+
+/// Testszenen in wenigen Zeilen: Boden, Wände, Blöcke, Becken und fertige Objekte. Jede Platzierung wird
+/// auf Gridgrenzen und Überlappung geprüft; der erste Fehler bleibt stehen, spätere Aufrufe ändern nichts mehr.
+pub struct SceneBuilder {
+    sim: Simulation,
+    error: Option<String>,
+}
+
+impl SceneBuilder {
+    pub fn new(h: usize, w: usize) -> SceneBuilder {
+        SceneBuilder { sim: Simulation::new(h, w, [0.0, -1.0]), error: None }
+    }
+
+    /// Statische Zeile y = 0.
+    pub fn floor(self) -> Self {
+        let max_x = self.sim.world.width.saturating_sub(1);
+        self.statics((0, 0), (max_x, 0))
+    }
+
+    /// Statische Spalte bei `x` über die ganze Höhe; trifft sie auf den Boden, teilen sich beide die Zelle.
+    pub fn wall(self, x: usize) -> Self {
+        let max_y = self.sim.world.height.saturating_sub(1);
+        self.statics((x, 0), (x, max_y))
+    }
+
+    /// Rechteckiges Objekt aus einem Material, Anker wie bei `Simulation::spawn_object`.
+    pub fn block(self, material: MaterialTyp, anchor: [f32; 2], h: usize, w: usize) -> Self {
+        self.apply(|sim| {
+            let object = Object::new(sim.object_counter + 1, anchor, [0.0, 0.0], material, h, w)?;
+            sim.place_object(object)
+        })
+    }
+
+    /// Füllt das Rechteck `min..=max` (x, y) lückenlos mit freien Partikeln.
+    pub fn pool(self, material: MaterialTyp, min: (usize, usize), max: (usize, usize)) -> Self {
+        self.apply(|sim| {
+            Self::check_rect(sim, min, max, |cell| cell.is_none())?;
+            sim.spawn_particles_rect(min, max, material);
+            Ok(())
+        })
+    }
+
+    /// Übernimmt ein fertiges Objekt an seiner Position; der Objektindex wird angepasst.
+    pub fn drop(self, object: Object) -> Self {
+        self.apply(|sim| sim.place_object(object))
+    }
+
+    pub fn build(self) -> Result<(World, Vec<Particle>, Vec<Object>), String> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok((self.sim.world, self.sim.particles, self.sim.objects)),
+        }
+    }
+
+    fn apply(mut self, place: impl FnOnce(&mut Simulation) -> Result<(), String>) -> Self {
+        if self.error.is_none() {
+            self.error = place(&mut self.sim).err();
+        }
+        self
+    }
+
+    fn statics(self, min: (usize, usize), max: (usize, usize)) -> Self {
+        self.apply(|sim| {
+            Self::check_rect(sim, min, max, |cell| matches!(cell, None | Some(ParticleRef::Static)))?;
+            for y in min.1..=max.1 {
+                for x in min.0..=max.0 {
+                    let pos = [x as f32, y as f32];
+                    sim.world.update_occupation_on_position(pos, ParticleRef::Static);
+                    sim.world.update_mass_on_position(pos, SCENE_STATIC_MASS);
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn check_rect(sim: &Simulation, min: (usize, usize), max: (usize, usize), allowed: impl Fn(Option<ParticleRef>) -> bool) -> Result<(), String> {
+        if max.0 >= sim.world.width || max.1 >= sim.world.height || min.0 > max.0 || min.1 > max.1 {
+            return Err(format!("Rechteck {:?}..={:?} liegt nicht im Grid", min, max));
+        }
+        for y in min.1..=max.1 {
+            for x in min.0..=max.0 {
+                if !allowed(sim.world.give_occupation_on_position(x, y)) {
+                    return Err(format!("Zelle ({}, {}) ist schon belegt", x, y));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ============== STATS ==============
+
+/// Schreibt pro Tick eine CSV-Zeile mit Kennzahlen der Simulation, z.B. für die Auswertung eines Laufs.
+pub struct StatsRecorder {
+    writer: BufWriter<File>,
+    tick: u64,
+}
+
+impl StatsRecorder {
+    pub const HEADER: &'static str = "tick,particles,objects,total_mass,kinetic_energy,max_pressure,fractures";
+
+    /// Legt die Datei an (überschreibt sie) und schreibt die Kopfzeile.
+    pub fn new(path: impl AsRef<Path>) -> Result<StatsRecorder, String> {
+        let file = File::create(path).map_err(|e| e.to_string())?;
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "{}", Self::HEADER).map_err(|e| e.to_string())?;
+        Ok(StatsRecorder { writer, tick: 0 })
+    }
+
+    /// Eine Zeile für den gerade ausgeführten Schritt; `events` ist dessen Rückgabe.
+    pub fn record(&mut self, sim: &Simulation, events: &StepEvents) -> Result<(), String> {
+        self.tick += 1;
+        let objects = sim.objects.iter().filter(|obj| !obj.is_destroyed).count();
+        writeln!(
+            self.writer,
+            "{},{},{},{},{},{},{}",
+            self.tick,
+            sim.particles.len(),
+            objects,
+            sim.total_mass(),
+            sim.kinetic_energy(),
+            sim.max_pressure(),
+            events.splits.len(),
+        ).map_err(|e| e.to_string())
+    }
+
+    pub fn flush(&mut self) -> Result<(), String> {
+        self.writer.flush().map_err(|e| e.to_string())
+    }
+}
+
+// ============== IMAGE ==============
+
+/// Größte Farbabweichung (euklidisch im RGB-Raum), mit der ein Pixel noch einer Palettenfarbe zugeordnet wird.
+#[cfg(feature = "image")]
+const PALETTE_TOLERANCE: f32 = 48.0;
+
+#[cfg(feature = "image")]
+impl World {
+    /// Startszene aus einem Bild (Feature `image`): Jedes deckende Pixel wird zu einem Partikel des Materials
+    /// mit der nächstgelegenen Palettenfarbe, durchsichtige Pixel (Alpha unter 128) und Luft bleiben leer.
+    /// Die oberste Bildzeile ist die oberste Gridzeile. Pixel ohne passende Farbe innerhalb von
+    /// `PALETTE_TOLERANCE` sind ein Fehler.
+    pub fn from_image(path: impl AsRef<Path>, palette: &HashMap<[u8; 3], MaterialTyp>) -> Result<(World, Vec<Particle>), String> {
+        let path = path.as_ref();
+        let image = image::open(path).map_err(|e| format!("{}: {}", path.display(), e))?.to_rgba8();
+        let (w, h) = (image.width() as usize, image.height() as usize);
+        let mut sim = Simulation::new(h, w, [0.0, -1.0]);
+
+        for (px, py, pixel) in image.enumerate_pixels() {
+            let [r, g, b, a] = pixel.0;
+            if a < 128 { continue; }
+            let material = palette.iter()
+                .map(|(color, &material)| (Self::color_distance(*color, [r, g, b]), material))
+                .filter(|&(distance, _)| distance <= PALETTE_TOLERANCE)
+                .min_by(|a, b| a.0.total_cmp(&b.0))
+                .map(|(_, material)| material)
+                .ok_or_else(|| format!("{}: Farbe {:?} bei ({}, {}) nicht in der Palette", path.display(), [r, g, b], px, py))?;
+            if material == MaterialTyp::Luft { continue; }
+
+            let (x, y) = (px as usize, h - 1 - py as usize);
+            sim.spawn_particle([x as f32, y as f32], [0.0, 0.0], material);
+        }
+        Ok((sim.world, sim.particles))
+    }
+
+    fn color_distance(a: [u8; 3], b: [u8; 3]) -> f32 {
+        a.iter().zip(b).map(|(&x, y)| (x as f32 - y as f32).powi(2)).sum::<f32>().sqrt()
+    }
+}
+
+// ============== TESTHILFEN ==============
+
+/// Hilfen für Tests und Goldausgaben; kein Teil der öffentlichen API.
+#[doc(hidden)]
+pub mod testing {
+    /// Vergleicht zwei Ausgaben von `render_ascii` Zelle für Zelle. `None` bei Gleichheit, sonst ein Bericht:
+    /// jede abweichende Zelle mit Koordinaten (x, y; y = 0 unten) und beiden Zeichen, dazu das erhaltene Bild
+    /// mit `!` an den abweichenden Stellen.
+    pub fn ascii_diff(expected: &str, actual: &str) -> Option<String> {
+        if expected == actual {
+            return None;
+        }
+        let expected: Vec<Vec<char>> = expected.lines().map(|line| line.chars().collect()).collect();
+        let actual: Vec<Vec<char>> = actual.lines().map(|line| line.chars().collect()).collect();
+        let height = expected.len().max(actual.len());
+        let width = expected.iter().chain(&actual).map(Vec::len).max().unwrap_or(0);
+        // Fehlende Zeilen und Spalten zählen als eigenes Zeichen, damit Größenunterschiede auffallen
+        let cell = |rows: &[Vec<char>], row: usize, x: usize| rows.get(row).and_then(|line| line.get(x)).copied().unwrap_or('∅');
+
+        let mut report = String::new();
+        if expected.len() != actual.len() || expected.first().map(Vec::len) != actual.first().map(Vec::len) {
+            report.push_str(&format!(
+                "Größe: erwartet {}x{}, erhalten {}x{}\n",
+                expected.first().map_or(0, Vec::len), expected.len(), actual.first().map_or(0, Vec::len), actual.len(),
+            ));
+        }
+        let mut marked = String::new();
+        for row in 0..height {
+            for x in 0..width {
+                let (e, a) = (cell(&expected, row, x), cell(&actual, row, x));
+                if e != a {
+                    report.push_str(&format!("({}, {}): erwartet {:?}, erhalten {:?}\n", x, height - 1 - row, e, a));
+                }
+                marked.push(if e != a { '!' } else { a });
+            }
+            marked.push('\n');
+        }
+        report.push_str(&marked);
+        Some(report)
+    }
+}
+
+/// Vergleicht zwei Simulationen über `render_ascii` und bricht mit dem Bericht von `ascii_diff` ab.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! assert_world_eq {
+    ($actual:expr, $expected:expr) => {
+        if let Some(report) = $crate::testing::ascii_diff(&$expected.render_ascii(), &$actual.render_ascii()) {
+            panic!("Welten unterscheiden sich:\n{}", report);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crack_spreads_over_several_ticks() {
+        let mut stone = Object::new(1, [0.0, 0.0], [0.0, 0.0], MaterialTyp::Stein, 3, 2).unwrap();
+        assert!(stone.seed_crack(((0, 0), (0, 1)), 1000.0).is_none());
+
+        let mut ticks = 0;
+        let fragments = loop {
+            ticks += 1;
+            assert!(ticks < 20, "Riss kommt nicht voran");
+            if let Some(fragments) = stone.propagate_crack(&MaterialTable::default()) { break fragments; }
+        };
+        assert!(ticks > 1, "Stein bricht erst nach mehreren Ticks, nicht sofort");
+        assert!(fragments.len() > 1);
+    }
+
+    #[test]
+    fn object_at_skips_destroyed_objects() {
+        let mut sim = Simulation::new(10, 10, [0.0, -1.0]);
+        let live = sim.spawn_object([1.0, 1.0], MaterialTyp::Stein, 2, 2).unwrap();
+        let doomed = sim.spawn_object([5.0, 1.0], MaterialTyp::Holz, 2, 2).unwrap();
+        sim.objects[doomed].is_destroyed = true;
+
+        let (obj, i, j) = sim.object_at(2, 2).unwrap();
+        assert_eq!((obj.object_id, i, j), (sim.objects[live].object_id, 1, 1));
+        // Die Gridzellen zeigen noch auf das zerstörte Objekt
+        assert!(matches!(sim.world.give_occupation_on_position(5, 1), Some(ParticleRef::InObject(..))));
+        assert!(sim.object_at(5, 1).is_none());
+        assert!(sim.object_at(0, 0).is_none());
+        assert!(sim.object_at(10, 0).is_none());
+    }
+
+    #[test]
+    fn builder_registers_scene_consistently() {
+        let sim = SimulationBuilder::new()
+            .world(8, 6)
+            .seed(7)
+            .add_static(0, 0, 1000.0)
+            .add_object([2.0, 1.0], MaterialTyp::Stein, 2, 3)
+            .add_particle([0.0, 5.0], [0.0, 0.0], MaterialTyp::Sand)
+            .add_particle([5.0, 5.0], [0.0, 0.0], MaterialTyp::Wasser)
+            .build().unwrap();
+
+        assert_eq!(sim.world.give_occupation_on_position(0, 0), Some(ParticleRef::Static));
+        assert_eq!(sim.world.cell(0, 0).unwrap().mass, 1000.0);
+        for (idx, p) in sim.particles.iter().enumerate() {
+            let (x, y) = World::cell_of(p.position);
+            assert_eq!(sim.world.give_occupation_on_position(x, y), Some(p.particle_ref));
+            assert!(matches!(p.particle_ref, ParticleRef::Free(i, _) if i == idx));
+            assert_eq!(sim.world.cell(x, y).unwrap().mass, p.mass(&sim.world.materials));
+        }
+        let obj = &sim.objects[0];
+        for p in obj.get_object_elements() {
+            let (x, y) = World::cell_of(p.position);
+            assert_eq!(sim.world.give_occupation_on_position(x, y), Some(p.particle_ref));
+        }
+        let occupied = (0..8).flat_map(|y| (0..6).map(move |x| (x, y)))
+            .filter(|&(x, y)| sim.world.give_occupation_on_position(x, y).is_some())
+            .count();
+        assert_eq!(occupied, 1 + 6 + 2);
+    }
+
+    #[test]
+    fn stale_refs_do_not_resolve_after_removal() {
+        let mut sim = Simulation::new(5, 5, [0.0, -1.0]);
+        for x in 0..3 {
+            sim.spawn_particle([x as f32, 0.0], [0.0, 0.0], MaterialTyp::Sand);
+        }
+        let removed_ref = sim.particles[0].particle_ref;
+        let moved_ref = sim.particles[2].particle_ref;
+        let moved_id = sim.particles[2].id;
+
+        let removed = sim.remove_particle(0).unwrap();
+        assert_eq!(removed.particle_ref, removed_ref);
+        assert!(sim.resolve_particle(removed_ref).is_none());
+        assert!(sim.resolve_particle(moved_ref).is_none());
+
+        // Die Gridzelle des nachgerückten Partikels führt zum richtigen Partikel
+        let current = sim.world.give_occupation_on_position(2, 0).unwrap();
+        assert_eq!(sim.resolve_particle(current).unwrap().id, moved_id);
+        assert_eq!(sim.world.give_occupation_on_position(0, 0), None);
+    }
+
+    /// Strecke, die ein Steinblock mit Anfangstempo 2 auf einer Platte aus `floor` rutscht.
+    fn slide_distance(floor: MaterialTyp) -> f32 {
+        let mut sim = SimulationBuilder::new()
+            .world(10, 60)
+            .add_object([0.0, 0.0], floor, 1, 60)
+            .add_object([2.0, 1.0], MaterialTyp::Stein, 2, 2)
+            .build().unwrap();
+        sim.objects[1].velocity = [2.0, 0.0];
+        for _ in 0..200 {
+            sim.step();
+        }
+        assert_eq!(sim.objects[1].get_object_velocity()[0], 0.0);
+        sim.objects[1].get_object_position()[0] - 2.0
+    }
+
+    #[test]
+    fn objects_slide_farther_on_ice() {
+        let on_ice = slide_distance(MaterialTyp::Eis);
+        let on_stone = slide_distance(MaterialTyp::Stein);
+        assert!(on_ice > 2.0 * on_stone, "Eis {} vs. Stein {}", on_ice, on_stone);
+    }
+
+    #[test]
+    fn trace_drop_reports_overhanging_object() {
+        let sim = SimulationBuilder::new()
+            .world(10, 8)
+            .add_object([2.0, 5.0], MaterialTyp::Holz, 1, 3)
+            .add_particle([3.0, 0.0], [0.0, 0.0], MaterialTyp::Sand)
+            .add_particle([6.0, 0.0], [0.0, 0.0], MaterialTyp::Sand)
+            .build().unwrap();
+        let id = sim.objects[0].object_id;
+
+        assert_eq!(sim.trace_drop(3), Some(ParticleRef::InObject(id, 0, 1)));
+        assert!(matches!(sim.trace_drop(6), Some(ParticleRef::Free(..))));
+        assert_eq!(sim.trace_drop(0), None);
+        assert_eq!(sim.trace_drop(8), None);
+    }
+
+    /// Alle Bindungen eines h×w-Gitters ohne Luft.
+    fn all_bonds(h: usize, w: usize) -> Vec<Bond> {
+        let mut bonds = Vec::new();
+        for i in 0..h {
+            for j in 0..w {
+                if j + 1 < w { bonds.push(((i, j), (i, j + 1))); }
+                if i + 1 < h { bonds.push(((i, j), (i + 1, j))); }
+            }
+        }
+        bonds
+    }
+
+    #[test]
+    fn find_fragments_without_breaks_is_one_piece() {
+        let block = Object::new(1, [0.0, 0.0], [0.0, 0.0], MaterialTyp::Stein, 2, 3).unwrap();
+        let fragments = block.find_fragments(&[]);
+        assert_eq!(fragments, vec![vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]]);
+    }
+
+    #[test]
+    fn find_fragments_single_cut() {
+        let block = Object::new(1, [0.0, 0.0], [0.0, 0.0], MaterialTyp::Stein, 2, 3).unwrap();
+        let cut = [((0, 0), (0, 1)), ((1, 0), (1, 1))];
+        assert_eq!(block.find_fragments(&cut), vec![vec![(0, 0), (1, 0)], vec![(0, 1), (0, 2), (1, 1), (1, 2)]]);
+    }
+
+    #[test]
+    fn find_fragments_accepts_reversed_bonds() {
+        let block = Object::new(1, [0.0, 0.0], [0.0, 0.0], MaterialTyp::Stein, 2, 3).unwrap();
+        let cut = [((0, 0), (0, 1)), ((1, 0), (1, 1))];
+        let reversed: Vec<Bond> = cut.iter().map(|&(a, b)| (b, a)).collect();
+        assert_eq!(block.find_fragments(&reversed), block.find_fragments(&cut));
+        assert_eq!(block.find_fragments(&reversed).len(), 2);
+    }
+
+    #[test]
+    fn find_fragments_full_isolation_gives_single_cells() {
+        let block = Object::new(1, [0.0, 0.0], [0.0, 0.0], MaterialTyp::Stein, 2, 2).unwrap();
+        let fragments = block.find_fragments(&all_bonds(2, 2));
+        assert_eq!(fragments, vec![vec![(0, 0)], vec![(0, 1)], vec![(1, 0)], vec![(1, 1)]]);
+
+        // Einzelzellen werden beim Zerlegen zu freien Partikeln
+        let mut sim = Simulation::new(10, 10, [0.0, -1.0]);
+        let idx = sim.spawn_object([3.0, 0.0], MaterialTyp::Stein, 2, 2).unwrap();
+        let pieces = sim.split_object(idx, &fragments);
+        assert_eq!(pieces.len(), 4);
+        assert!(pieces.iter().all(|piece| matches!(piece, Piece::Particle(_))));
+        assert_eq!(sim.particles.len(), 4);
+        assert!(sim.objects[idx].is_destroyed);
+    }
+
+    #[test]
+    fn find_fragments_checkerboard_breaks() {
+        // 4×4 aus 2×2-Kacheln im Schachbrett: Die dunklen Kacheln verlieren alle Bindungen nach außen,
+        // die hellen berühren sich danach nur noch an einer Ecke. Ergibt genau die vier Kacheln.
+        let block = Object::new(1, [0.0, 0.0], [0.0, 0.0], MaterialTyp::Stein, 4, 4).unwrap();
+        let tile = |(i, j): (usize, usize)| (i / 2, j / 2);
+        let dark = |cell: (usize, usize)| { let (a, b) = tile(cell); (a + b) % 2 == 0 };
+        let broken: Vec<Bond> = all_bonds(4, 4).into_iter()
+            .filter(|&(a, b)| tile(a) != tile(b) && (dark(a) || dark(b)))
+            .collect();
+
+        let fragments = block.find_fragments(&broken);
+        assert_eq!(fragments, vec![
+            vec![(0, 0), (0, 1), (1, 0), (1, 1)],
+            vec![(0, 2), (0, 3), (1, 2), (1, 3)],
+            vec![(2, 0), (2, 1), (3, 0), (3, 1)],
+            vec![(2, 2), (2, 3), (3, 2), (3, 3)],
+        ]);
+    }
+
+    #[test]
+    fn find_fragments_ignores_diagonal_contact() {
+        // Zwei Zellen, die sich nur an der Ecke berühren, gehören nicht zusammen
+        let diagonal = Object::new_with(1, [0.0, 0.0], [0.0, 0.0], 2, 2, |i, j| {
+            if i == j { MaterialTyp::Stein } else { MaterialTyp::Luft }
+        }).unwrap();
+        assert_eq!(diagonal.find_fragments(&[]), vec![vec![(0, 0)], vec![(1, 1)]]);
+    }
+
+    #[test]
+    fn find_fragments_skips_air_and_sorts_output() {
+        // Luftspalte in der Mitte trennt die Seiten, auch ohne gebrochene Bindung
+        let split = Object::new_with(1, [0.0, 0.0], [0.0, 0.0], 3, 3, |_, j| {
+            if j == 1 { MaterialTyp::Luft } else { MaterialTyp::Holz }
+        }).unwrap();
+        let fragments = split.find_fragments(&[]);
+        assert_eq!(fragments, vec![vec![(0, 0), (1, 0), (2, 0)], vec![(0, 2), (1, 2), (2, 2)]]);
+        assert!(fragments.iter().flatten().all(|&(_, j)| j != 1));
+
+        let mut sorted = fragments.clone();
+        sorted.sort();
+        assert_eq!(fragments, sorted);
+        for fragment in &fragments {
+            assert!(fragment.windows(2).all(|w| w[0] < w[1]));
+        }
+    }
+
+    #[test]
+    fn flood_fill_stops_at_walls() {
+        // 6×4-Welt, statische Wand in Spalte 2 mit einer Lücke ganz oben
+        let mut world = World::new(4, 6);
+        for y in 0..3 {
+            world.update_occupation_on_position([2.0, y as f32], ParticleRef::Static);
+        }
+        let empty = |cell: Option<ParticleRef>| cell.is_none();
+
+        assert_eq!(world.flood_fill((0, 0), empty).len(), 6 * 4 - 3);
+
+        world.update_occupation_on_position([2.0, 3.0], ParticleRef::Static);
+        let left = world.flood_fill((0, 0), empty);
+        assert_eq!(left.len(), 2 * 4);
+        assert!(left.iter().all(|&(x, _)| x < 2));
+        assert_eq!(world.flood_fill((5, 3), empty).len(), 3 * 4);
+        assert!(world.flood_fill((2, 1), empty).is_empty());
+        assert!(world.flood_fill((6, 0), empty).is_empty());
+    }
+
+    #[test]
+    fn pinned_object_swings_down_below_pivot() {
+        let mut sim = Simulation::new(10, 10, [0.0, -1.0]);
+        // Stab steht senkrecht auf seinem Gelenk; nach der halben Drehung ist die Gelenkzelle seine oberste Mitte
+        let idx = sim.spawn_object([5.0, 7.0], MaterialTyp::Holz, 3, 1).unwrap();
+        let object_id = sim.objects[idx].object_id;
+        sim.joints.push(Joint { object_id, anchor_cell: (0, 0), world_point: [5.0, 7.0] });
+
+        let mut swings = 0;
+        while !sim.resolve_joints().is_empty() {
+            swings += 1;
+            assert!(swings <= 2, "Objekt kommt nicht zur Ruhe");
+        }
+        assert_eq!(swings, 2);
+
+        let obj = &sim.objects[idx];
+        assert_eq!(sim.joints[0].anchor_cell, (obj.object_h - 1, obj.object_w / 2));
+        assert_eq!(obj.get_particle_at(2, 0).position, [5.0, 7.0]);
+        let com = obj.center_of_mass(&sim.world.materials);
+        assert!((com[0] - 5.0).abs() < 1e-4);
+        assert!(com[1] < 7.0);
+        assert_eq!(sim.object_at(5, 5).map(|(o, i, j)| (o.object_id, i, j)), Some((object_id, 0, 0)));
+        assert!(sim.world.give_occupation_on_position(5, 8).is_none());
+    }
+
+    #[test]
+    fn spawn_particles_rect_fills_free_cells() {
+        let mut sim = Simulation::new(12, 12, [0.0, -1.0]);
+        let spawned = sim.spawn_particles_rect((1, 1), (10, 10), MaterialTyp::Sand);
+        assert_eq!(spawned.len(), 100);
+        assert_eq!(sim.particles.len(), 100);
+        for &idx in &spawned {
+            let p = &sim.particles[idx];
+            let (x, y) = World::cell_of(p.position);
+            assert!((1..=10).contains(&x) && (1..=10).contains(&y));
+            assert_eq!(sim.world.give_occupation_on_position(x, y), Some(p.particle_ref));
+            assert_eq!(sim.world.cell(x, y).unwrap().mass, p.mass(&sim.world.materials));
+        }
+        assert!(sim.world.give_occupation_on_position(0, 0).is_none());
+        assert!(sim.world.give_occupation_on_position(11, 11).is_none());
+
+        // Belegte Zellen werden übersprungen, über den Rand hinaus wird abgeschnitten
+        let more = sim.spawn_particles_rect((8, 8), (20, 20), MaterialTyp::Wasser);
+        assert_eq!(more.len(), 4 * 4 - 3 * 3);
+        assert_eq!(sim.particles.len(), 107);
+    }
+
+    fn drop_stone(materials: MaterialTable) -> usize {
+        let mut sim = SimulationBuilder::new().world(30, 10).build().unwrap();
+        for x in 0..10 {
+            sim.world.update_occupation_on_position([x as f32, 0.0], ParticleRef::Static);
+        }
+        sim.set_materials(materials);
+        sim.spawn_object([3.0, 24.0], MaterialTyp::Stein, 4, 4).unwrap();
+        for _ in 0..60 {
+            sim.step();
+        }
+        sim.objects.iter().filter(|obj| !obj.is_destroyed).count() + sim.particles.len()
+    }
+
+    #[test]
+    fn material_config_strengthens_stone_per_simulation() {
+        assert!(drop_stone(MaterialTable::default()) > 1, "Stein zerbricht mit eingebauten Werten");
+
+        let strong = MaterialTable::from_config("{ Stein: (binding_strength: Some(100000.0)) }").unwrap();
+        assert_eq!(strong.overridden_count(), 1);
+        assert_eq!(strong.binding_strength(MaterialTyp::Stein), 100000.0);
+        assert_eq!(strong.binding_strength(MaterialTyp::Holz), MaterialTyp::Holz.binding_strength());
+        assert_eq!(drop_stone(strong), 1);
+
+        // Die andere Simulation behält die eingebauten Werte
+        assert!(drop_stone(MaterialTable::default()) > 1);
+        assert!(MaterialTable::from_config("{ Stein: (density: Some(true)) }").is_err());
+    }
+
+    #[test]
+    fn mass_center_weights_cells_by_mass() {
+        let mut world = World::new(5, 6);
+        assert_eq!(world.mass_center(), None);
+
+        for (pos, mass) in [([0.0, 0.0], 1.0), ([4.0, 2.0], 3.0)] {
+            world.update_occupation_on_position(pos, ParticleRef::Free(0, 0));
+            world.update_mass_on_position(pos, mass);
+        }
+        // Statische Zellen zählen nicht, auch wenn sie Masse tragen
+        world.update_occupation_on_position([5.0, 4.0], ParticleRef::Static);
+        world.update_mass_on_position([5.0, 4.0], 1000.0);
+
+        assert_eq!(world.mass_center(), Some([3.0, 1.5]));
+    }
+
+    #[test]
+    fn wood_grain_dampens_impacts_along_the_fibers() {
+        let materials = MaterialTable::default();
+        let along = Object::new(1, [0.0, 0.0], [0.0, 0.0], MaterialTyp::Holz, 3, 3).unwrap()
+            .with_grain(Grain::Along(Axis::Horizontal));
+        let mut across = along.clone();
+        across.rotate_quarter(true);
+        assert_eq!(across.get_grain(), Grain::Along(Axis::Vertical));
+
+        // Seitlicher Stoß knapp über der Bindungsstärke von Holz
+        let force = MaterialTyp::Holz.binding_strength() * 1.25;
+        assert!(along.check_fracture_along(force, 1.0, Axis::Horizontal, &materials).is_empty());
+        assert!(!across.check_fracture_along(force, 1.0, Axis::Horizontal, &materials).is_empty());
+
+        // Ohne Faser zählt die volle Kraft, wie vorher
+        let isotropic = along.clone().with_grain(Grain::Isotropic);
+        assert!(!isotropic.check_fracture_along(force, 1.0, Axis::Horizontal, &materials).is_empty());
+        assert!(isotropic.check_fracture_along(force * 0.75, 1.0, Axis::Horizontal, &materials).is_empty());
+    }
+
+    #[test]
+    fn gases_render_translucent_and_solids_opaque() {
+        assert!(MaterialTyp::Luft.render_alpha() < 1.0);
+        assert!(MaterialTyp::Luft.render_scale() > 1.0);
+        for material in MaterialTyp::ALL.into_iter().filter(|m| m.is_solid()) {
+            assert_eq!(material.render_alpha(), 1.0, "{:?}", material);
+            assert_eq!(material.render_scale(), 1.0, "{:?}", material);
+        }
+        assert_eq!(MaterialTable::default().render_alpha(MaterialTyp::Luft), MaterialTyp::Luft.render_alpha());
+    }
+
+    #[test]
+    fn relocate_moves_into_free_space_and_rolls_back_when_blocked() {
+        let mut sim = Simulation::new(10, 10, [0.0, -1.0]);
+        let idx = sim.spawn_object([1.0, 1.0], MaterialTyp::Stein, 2, 2).unwrap();
+        sim.spawn_object([6.0, 6.0], MaterialTyp::Holz, 2, 2).unwrap();
+        let object_id = sim.objects[idx].object_id;
+        sim.objects[idx].velocity = [1.0, 0.0];
+
+        assert!(sim.objects[idx].relocate([3.0, 4.0], &mut sim.world));
+        assert_eq!(sim.objects[idx].get_object_position(), [3.0, 4.0]);
+        assert_eq!(sim.objects[idx].get_object_velocity(), [0.0, 0.0]);
+        assert!(sim.world.give_occupation_on_position(1, 1).is_none());
+        assert_eq!(sim.world.give_occupation_on_position(4, 5), Some(ParticleRef::InObject(object_id, 1, 1)));
+
+        let before = sim.objects[idx].clone();
+        let grid = sim.world.grid.clone();
+        assert!(!sim.objects[idx].relocate([5.0, 5.0], &mut sim.world));
+        assert!(!sim.objects[idx].relocate([9.0, 0.0], &mut sim.world));
+        assert_eq!(sim.objects[idx].get_object_position(), before.get_object_position());
+        assert_eq!(sim.world.grid, grid);
+    }
+
+    #[test]
+    fn jitter_column_stays_within_symmetric_spread() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut seen = HashSet::new();
+        for _ in 0..2000 {
+            let x = jitter_column(10, 2, 40, &mut rng);
+            assert!((8..=12).contains(&x), "{}", x);
+            seen.insert(x);
+        }
+        assert_eq!(seen.len(), 5, "alle Spalten der Streuung kommen vor");
+        assert_eq!(jitter_column(5, 0, 40, &mut rng), 5);
+        for _ in 0..100 {
+            assert!(jitter_column(0, 3, 4, &mut rng) < 4);
+        }
+    }
+
+    /// Restgeschwindigkeit eines 4×4-Steinblocks, der mit fester Aufprallkraft auf den Boden schlägt.
+    fn residual_after_impact(force_profile: ForceProfile) -> (f32, Object, World) {
+        let mut world = World::new(200, 6);
+        for x in 0..6 {
+            world.update_occupation_on_position([x as f32, 0.0], ParticleRef::Static);
+        }
+        let mut obj = Object::new(1, [1.0, 100.0], [0.0, 0.0], MaterialTyp::Stein, 4, 4).unwrap()
+            .with_force_profile(force_profile);
+        obj.velocity = [0.0, -5000.0 / obj.mass(&world.materials)];
+        obj.update_object_velocity([0.0, 0.0], &world, f32::MAX);
+        (obj.get_residual_velocity(), obj, world)
+    }
+
+    #[test]
+    fn heavier_fracture_leaves_less_residual_speed() {
+        // Nur die unterste Zeile bricht gegen alle Zeilen
+        let (light, mut obj, world) = residual_after_impact(ForceProfile::Exponential(5.0));
+        let (heavy, _, _) = residual_after_impact(ForceProfile::Uniform);
+        assert!(light < 0.0 && heavy < 0.0, "Restgeschwindigkeit zeigt in Fallrichtung");
+        assert!(heavy.abs() < light.abs(), "schwer {} vs. leicht {}", heavy, light);
+
+        // Im nächsten Tick ist der Aufprall vorbei
+        obj.update_object_position(&mut World::new(200, 6));
+        obj.update_object_velocity([0.0, 0.0], &world, f32::MAX);
+        assert_eq!(obj.get_residual_velocity(), 0.0);
+    }
+
+    #[test]
+    fn contains_world_cell_maps_to_grid_coordinates() {
+        // 3×3 mit Luftloch in der Mitte
+        let mut obj = Object::new_with(1, [4.0, 2.0], [0.0, 0.0], 3, 3, |i, j| {
+            if (i, j) == (1, 1) { MaterialTyp::Luft } else { MaterialTyp::Holz }
+        }).unwrap();
+        assert_eq!(obj.contains_world_cell(4, 2), Some((0, 0)));
+        assert_eq!(obj.contains_world_cell(6, 3), Some((1, 2)));
+        assert_eq!(obj.contains_world_cell(5, 4), Some((2, 1)));
+        assert_eq!(obj.contains_world_cell(5, 3), None);
+        assert_eq!(obj.contains_world_cell(3, 2), None);
+        assert_eq!(obj.contains_world_cell(7, 2), None);
+        assert_eq!(obj.contains_world_cell(4, 5), None);
+
+        obj.is_destroyed = true;
+        assert_eq!(obj.contains_world_cell(4, 2), None);
+    }
+
+    /// Ufer aus `bank` in Zeile 0, darüber Wasser mit `speed` nach rechts; zählt nach `passes` Erosionsdurchgängen
+    /// die übrigen Ufer-Partikel.
+    fn bank_after_erosion(bank: MaterialTyp, speed: f32, passes: usize) -> usize {
+        let mut sim = SimulationBuilder::new().world(4, 20).seed(11).build().unwrap();
+        sim.spawn_particles_rect((0, 0), (19, 0), bank);
+        let water = sim.spawn_particles_rect((0, 1), (19, 1), MaterialTyp::Wasser);
+        for _ in 0..passes {
+            for &idx in &water {
+                sim.particles[idx].velocity = [speed, 0.0];
+            }
+            sim.erode();
+        }
+        sim.particles.iter().filter(|p| p.material == bank).count()
+    }
+
+    #[test]
+    fn moving_water_erodes_sand_but_still_water_does_not() {
+        let early = bank_after_erosion(MaterialTyp::Sand, 1.5, 5);
+        assert!(early < 20, "fließendes Wasser trägt Sand ab");
+        assert!(early > 0, "nicht alles auf einmal");
+        assert!(bank_after_erosion(MaterialTyp::Sand, 1.5, 20) < early);
+        assert_eq!(bank_after_erosion(MaterialTyp::Sand, 0.0, 20), 20);
+        assert_eq!(bank_after_erosion(MaterialTyp::Stein, 3.0, 20), 20);
+        assert_eq!(bank_after_erosion(MaterialTyp::Metall, 3.0, 20), 20);
+    }
+
+    #[test]
+    fn frozen_water_stays_while_sand_falls() {
+        let mut sim = Simulation::new(10, 5, [0.0, -1.0]);
+        let water = sim.spawn_particle([1.0, 5.0], [0.0, 0.0], MaterialTyp::Wasser);
+        let sand = sim.spawn_particle([3.0, 5.0], [0.0, 0.0], MaterialTyp::Sand);
+        let on_top = sim.spawn_particle([1.0, 8.0], [0.0, 0.0], MaterialTyp::Sand);
+        sim.frozen_materials.insert(MaterialTyp::Wasser);
+
+        for _ in 0..20 {
+            sim.step();
+        }
+        assert_eq!(sim.particles[water].position, [1.0, 5.0]);
+        assert_eq!(sim.world.give_occupation_on_position(1, 5), Some(sim.particles[water].particle_ref));
+        assert_eq!(sim.particles[sand].position[1], 0.0);
+        // Eingefrorenes Wasser blockiert weiter; Sand darauf rutscht daneben ab oder bleibt liegen
+        assert_ne!(World::cell_of(sim.particles[on_top].position), (1, 5));
+        assert!(sim.particles[on_top].position[1] < 8.0);
+
+        sim.frozen_materials.clear();
+        for _ in 0..20 {
+            sim.step();
+        }
+        assert!(sim.particles[water].position[1] < 5.0);
+    }
+
+    #[test]
+    fn metal_block_crushes_wood_below() {
+        let mut sim = SimulationBuilder::new().world(40, 10).build().unwrap();
+        for x in 0..10 {
+            sim.world.update_occupation_on_position([x as f32, 0.0], ParticleRef::Static);
+        }
+        let wood = sim.spawn_object([2.0, 1.0], MaterialTyp::Holz, 2, 6).unwrap();
+        let wood_id = sim.objects[wood].object_id;
+        for _ in 0..10 {
+            sim.step();
+        }
+        assert!(sim.live_object_by_id(wood_id).is_some(), "ohne Last hält das Holz");
+
+        let metal = sim.spawn_object([3.0, 30.0], MaterialTyp::Metall, 4, 4).unwrap();
+        let metal_id = sim.objects[metal].object_id;
+
+        for _ in 0..60 {
+            sim.step();
+        }
+        assert!(sim.live_object_by_id(wood_id).is_none(), "Holz ist unter dem Metall zerbrochen");
+        assert!(sim.objects.iter().any(|obj| !obj.is_destroyed && obj.object_id != metal_id
+            && obj.get_object_elements().iter().any(|p| p.material == MaterialTyp::Holz))
+            || sim.particles.iter().any(|p| p.material == MaterialTyp::Holz));
+    }
+
+    #[test]
+    fn heightmap_of_a_staircase() {
+        let mut world = World::new(5, 6);
+        for x in 0..6 {
+            for y in 0..x {
+                world.update_occupation_on_position([x as f32, y as f32], ParticleRef::Static);
+            }
+        }
+        let heights: Vec<usize> = (0..6).map(|x| world.column_height(x)).collect();
+        assert_eq!(heights, [0, 1, 2, 3, 4, 5]);
+        assert_eq!(world.to_heightmap(), [0, 51, 102, 153, 204, 255]);
+    }
+
+    #[test]
+    fn oversized_object_is_rejected_without_touching_the_grid() {
+        let mut sim = Simulation::new(30, 30, [0.0, -1.0]);
+        sim.max_object_cells = 20;
+        let grid = sim.world.grid.clone();
+
+        assert!(sim.spawn_object([0.0, 0.0], MaterialTyp::Stein, 5, 5).is_err());
+        assert!(sim.spawn_object([0.0, 0.0], MaterialTyp::Stein, 21, 1).is_err());
+        assert!(sim.objects.is_empty());
+        assert_eq!(sim.world.grid, grid);
+
+        assert!(sim.spawn_object([0.0, 0.0], MaterialTyp::Stein, 2, 10).is_ok());
+        sim.max_object_cells = 10;
+        assert!(sim.spawn_quadrant([12.0, 12.0]).is_err());
+        assert_eq!(sim.objects.len(), 1);
+    }
+
+    #[test]
+    fn impact_fracture_is_not_repeated_by_pressure_in_the_same_tick() {
+        // Ein Sandstapel bricht beim Aufprall und wäre liegend auch unter seinem eigenen Gewicht überlastet
+        let mut sim = Simulation::new(20, 4, [0.0, -1.0]);
+        let idx = sim.spawn_object([1.0, 6.0], MaterialTyp::Sand, 4, 1).unwrap();
+        assert!(!sim.objects[idx].check_pressure_fracture(&sim.world).is_empty());
+
+        let mut ticks = 0;
+        let events = loop {
+            ticks += 1;
+            assert!(ticks < 20, "Sandstapel bricht nie");
+            let events = sim.step();
+            if !events.splits.is_empty() { break events; }
+        };
+        assert_eq!(events.splits.len(), 1);
+        assert_eq!(events.splits[0].object_idx, idx);
+        assert!(sim.objects[idx].is_destroyed);
+        let cells: usize = events.splits[0].pieces.iter().map(|piece| match *piece {
+            Piece::Particle(_) => 1,
+            Piece::Object(o) => sim.objects[o].get_object_elements().len(),
+        }).sum();
+        assert_eq!(cells, 4);
+    }
+
+    #[test]
+    fn repainting_wood_to_metal_adds_mass() {
+        let mut sim = Simulation::new(10, 10, [0.0, -1.0]);
+        let idx = sim.spawn_object([2.0, 2.0], MaterialTyp::Holz, 2, 2).unwrap();
+        let materials = sim.world.materials.clone();
+        let before = sim.objects[idx].mass(&materials);
+
+        let Simulation { world, objects, .. } = &mut sim;
+        assert!(objects[idx].set_material_at(1, 0, MaterialTyp::Metall, world));
+        let added = MaterialTyp::Metall.density() - MaterialTyp::Holz.density();
+        assert!(added > 0.0);
+        assert!((sim.objects[idx].mass(&materials) - (before + added)).abs() < 1e-4);
+        assert_eq!(sim.world.cell(2, 3).unwrap().mass, MaterialTyp::Metall.density());
+
+        // Zu Luft gemalt verlässt die Zelle das Grid
+        let Simulation { world, objects, .. } = &mut sim;
+        assert!(objects[idx].set_material_at(1, 0, MaterialTyp::Luft, world));
+        assert!(sim.world.give_occupation_on_position(2, 3).is_none());
+        assert!((sim.objects[idx].mass(&materials) - 3.0 * MaterialTyp::Holz.density()).abs() < 1e-4);
+    }
+
+    /// Sandblock 10×10, von oben nach unten angelegt (die Vektorreihenfolge beginnt also oben), fällt auf den Boden.
+    /// Liefert die Oberflächenrauheit (Summe der Höhensprünge) nach `probe` Ticks und die Ticks bis zur Ruhe.
+    fn settle_pile(bottom_up: bool, probe: usize) -> (usize, usize) {
+        let mut sim = SimulationBuilder::new().world(30, 20).seed(5).settle_bottom_up(bottom_up).build().unwrap();
+        for y in (10..20).rev() {
+            sim.spawn_particles_rect((5, y), (14, y), MaterialTyp::Sand);
+        }
+        let mut roughness = 0;
+        for tick in 1..200 {
+            sim.step();
+            if tick == probe {
+                let heights: Vec<usize> = (0..20).map(|x| sim.world.column_height(x)).collect();
+                roughness = heights.windows(2).map(|w| w[0].abs_diff(w[1])).sum();
+            }
+            if sim.activity() == 0 {
+                return (roughness, tick);
+            }
+        }
+        panic!("Haufen kommt nicht zur Ruhe");
+    }
+
+    #[test]
+    fn bottom_up_settling_is_flatter_and_calmer() {
+        let (naive_roughness, naive_ticks) = settle_pile(false, 10);
+        let (sorted_roughness, sorted_ticks) = settle_pile(true, 10);
+        assert!(sorted_roughness < naive_roughness, "{} vs. {}", sorted_roughness, naive_roughness);
+        assert!(sorted_ticks < naive_ticks, "{} vs. {}", sorted_ticks, naive_ticks);
+    }
+
+    #[test]
+    fn infinite_position_is_flagged() {
+        let mut sim = Simulation::new(10, 10, [0.0, -1.0]);
+        let idx = sim.spawn_particle([3.0, 5.0], [0.0, 0.0], MaterialTyp::Sand);
+        assert!(sim.stability_check().is_stable());
+        sim.particles[idx].position[1] = f32::INFINITY;
+        let report = sim.stability_check();
+        assert!(report.issues.contains(&StabilityIssue::NonFinite(Piece::Particle(idx))), "{:?}", report.issues);
+    }
+
+    #[test]
+    fn activity_drops_to_zero_once_a_pile_settles() {
+        let mut sim = Simulation::new(20, 10, [0.0, -1.0]);
+        sim.spawn_particles_rect((3, 10), (6, 15), MaterialTyp::Sand);
+        sim.step();
+        assert!(sim.activity() > 0);
+        let settled = (0..200).any(|_| {
+            sim.step();
+            sim.activity() == 0
+        });
+        assert!(settled, "Haufen kommt nicht zur Ruhe");
+        for _ in 0..5 {
+            sim.step();
+            assert_eq!(sim.activity(), 0);
+        }
+    }
+
+    #[test]
+    fn dropped_object_rests_one_above_the_floor() {
+        let mut sim = Simulation::new(30, 10, [0.0, -1.0]);
+        for x in 0..10 {
+            sim.world.update_occupation_on_position([x as f32, 0.0], ParticleRef::Static);
+        }
+        let idx = sim.spawn_object([3.0, 8.0], MaterialTyp::Metall, 2, 2).unwrap();
+        for _ in 0..60 {
+            sim.step();
+        }
+        let obj = &sim.objects[idx];
+        assert!(!obj.is_destroyed);
+        assert_eq!(obj.position[1], 1.0);
+        assert_eq!(obj.velocity, [0.0, 0.0]);
+    }
+
+    #[test]
+    fn same_force_moves_wood_faster_than_metal() {
+        let materials = MaterialTable::default();
+        let mut wood = Particle::new(0, [0.0, 0.0], [0.0, 0.0], MaterialTyp::Holz, ParticleRef::Free(0, 0));
+        let mut metal = Particle::new(1, [0.0, 0.0], [0.0, 0.0], MaterialTyp::Metall, ParticleRef::Free(1, 0));
+        wood.apply_force([2.0, 1.0], f32::INFINITY, &materials);
+        metal.apply_force([2.0, 1.0], f32::INFINITY, &materials);
+        assert!(wood.velocity[0] > metal.velocity[0] && metal.velocity[0] > 0.0);
+        assert!(wood.velocity[1] > metal.velocity[1] && metal.velocity[1] > 0.0);
+    }
+
+    #[test]
+    fn scaling_keeps_the_quadrant_layout_and_respects_the_cap() {
+        use MaterialTyp::*;
+        let layout = [[Holz, Stein], [Metall, Sand]];
+        let mut sim = Simulation::new(20, 20, [0.0, -1.0]);
+        sim.place_object(Object::filled(1, [2.0, 3.0], [0.0, 0.0], 2, 2, |i, j| layout[i][j])).unwrap();
+
+        sim.scale_object(0, 2).unwrap();
+        let obj = &sim.objects[0];
+        assert_eq!((obj.object_h, obj.object_w, obj.position), (4, 4, [2.0, 3.0]));
+        for i in 0..4 {
+            for j in 0..4 {
+                assert_eq!(obj.object_grid[i][j].0.material, layout[i / 2][j / 2], "Zelle ({}, {})", i, j);
+                assert_eq!(sim.world.give_occupation_on_position(2 + j, 3 + i), Some(ParticleRef::InObject(1, i, j)));
+            }
+        }
+
+        sim.max_object_cells = 32;
+        let grid = sim.world.grid.clone();
+        assert!(sim.scale_object(0, 2).is_err());
+        assert_eq!(sim.objects[0].object_h, 4);
+        assert_eq!(sim.world.grid, grid);
+    }
+
+    #[test]
+    fn same_position_maps_to_the_same_cell_everywhere() {
+        for pos in [[3.9999, 5.0], [4.0, 4.9995], [4.4, 5.2]] {
+            assert_eq!(World::cell_of(pos), (4, 5), "{:?}", pos);
+
+            let mut sim = Simulation::new(10, 10, [0.0, -1.0]);
+            let idx = sim.spawn_particle(pos, [0.0, 0.0], MaterialTyp::Sand);
+            assert_eq!(sim.world.give_occupation_on_position(4, 5), Some(sim.particles[idx].particle_ref));
+            sim.world.update_mass_on_position(pos, 7.0);
+            assert_eq!(sim.world.grid[5][4].1, 7.0);
+
+            let mut sim = Simulation::new(10, 10, [0.0, -1.0]);
+            sim.spawn_object(pos, MaterialTyp::Stein, 1, 1).unwrap();
+            assert_eq!(sim.world.give_occupation_on_position(4, 5), Some(ParticleRef::InObject(1, 0, 0)));
+            assert_eq!(sim.objects[0].contains_world_cell(4, 5), Some((0, 0)));
+        }
+    }
+
+    #[test]
+    fn hollowing_a_block_leaves_a_one_cell_wall() {
+        let mut sim = Simulation::new(10, 10, [0.0, -1.0]);
+        sim.spawn_object([2.0, 2.0], MaterialTyp::Stein, 4, 4).unwrap();
+        let before = sim.objects[0].mass(&sim.world.materials);
+
+        let Simulation { objects, world, .. } = &mut sim;
+        objects[0].hollow(world);
+
+        let obj = &sim.objects[0];
+        for i in 0..4 {
+            for j in 0..4 {
+                let inside = (1..3).contains(&i) && (1..3).contains(&j);
+                let expected = if inside { MaterialTyp::Luft } else { MaterialTyp::Stein };
+                assert_eq!(obj.object_grid[i][j].0.material, expected, "Zelle ({}, {})", i, j);
+                assert_eq!(sim.world.give_occupation_on_position(2 + j, 2 + i).is_none(), inside);
+            }
+        }
+        let density = sim.world.materials.density(MaterialTyp::Stein);
+        assert_eq!(obj.mass(&sim.world.materials), before - 4.0 * density);
+    }
+
+    #[test]
+    fn striped_object_alternates_materials() {
+        let stripes = |i: usize, _: usize| [MaterialTyp::Holz, MaterialTyp::Metall][i % 2];
+        let obj = Object::new_with(1, [0.0, 0.0], [0.0, 0.0], 4, 3, stripes).unwrap();
+        for (i, row) in obj.object_grid.iter().enumerate() {
+            assert!(row.iter().all(|cell| cell.0.material == stripes(i, 0)), "Zeile {}", i);
+        }
+        let materials = MaterialTable::default();
+        let expected = 6.0 * materials.density(MaterialTyp::Holz) + 6.0 * materials.density(MaterialTyp::Metall);
+        assert_eq!(obj.mass(&materials), expected);
+        assert!(Object::new_with(1, [0.0, 0.0], [0.0, 0.0], 0, 3, stripes).is_err());
+    }
+
+    #[test]
+    fn step_hook_runs_once_per_step_with_live_state() {
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut sim = Simulation::new(10, 10, [0.0, -1.0]);
+        sim.spawn_particle([2.0, 5.0], [0.0, 0.0], MaterialTyp::Sand);
+        sim.spawn_particle([6.0, 5.0], [0.0, 0.0], MaterialTyp::Wasser);
+        let log = seen.clone();
+        sim.add_step_hook(move |_, particles, _| log.lock().unwrap().push(particles.len()));
+
+        sim.step();
+        sim.step();
+        sim.spawn_particle([4.0, 8.0], [0.0, 0.0], MaterialTyp::Sand);
+        sim.step();
+        assert_eq!(*seen.lock().unwrap(), [2, 2, 3]);
+    }
+
+    #[test]
+    fn water_flows_down_an_incline() {
+        // Treppe, die nach rechts abfällt: Spalte x ist bis Höhe 12 - x gefüllt
+        let mut sim = Simulation::new(20, 12, [0.0, -1.0]);
+        for x in 0..12 {
+            for y in 0..12 - x {
+                sim.world.update_occupation_on_position([x as f32, y as f32], ParticleRef::Static);
+            }
+        }
+        let water: Vec<usize> = (0..3).map(|x| sim.spawn_particle([x as f32, 13.0], [0.0, 0.0], MaterialTyp::Wasser)).collect();
+        let mut last_x: Vec<f32> = water.iter().map(|&idx| sim.particles[idx].position[0]).collect();
+
+        for _ in 0..40 {
+            sim.step();
+            for (k, &idx) in water.iter().enumerate() {
+                let x = sim.particles[idx].position[0];
+                assert!(x >= last_x[k], "Wasser {} fließt bergauf: {} -> {}", k, last_x[k], x);
+                last_x[k] = x;
+            }
+        }
+        assert!(last_x.iter().all(|&x| x >= 9.0), "{:?}", last_x);
+    }
+
+    #[test]
+    fn freezing_a_region_turns_it_static_and_thawing_restores_it() {
+        use MaterialTyp::*;
+        let mut sim = Simulation::new(10, 10, [0.0, -1.0]);
+        sim.spawn_particles_rect((2, 4), (3, 4), Sand);
+        sim.spawn_object([5.0, 4.0], Holz, 1, 2).unwrap();
+        sim.world.update_occupation_on_position([0.0, 4.0], ParticleRef::Static);
+
+        let mut frozen = sim.freeze_region((0, 4), (5, 4));
+        frozen.sort_by_key(|&(x, y, _)| (x, y));
+        assert_eq!(frozen, [(2, 4, Sand), (3, 4, Sand), (5, 4, Holz), (6, 4, Holz)]);
+        assert!(sim.particles.is_empty());
+        assert!(sim.objects[0].is_destroyed);
+        for _ in 0..5 {
+            sim.step();
+        }
+        assert!([2, 3, 5, 6].iter().all(|&x| sim.world.give_occupation_on_position(x, 4) == Some(ParticleRef::Static)));
+
+        let thawed = sim.unfreeze_region((0, 0), (9, 9));
+        assert_eq!(thawed.len(), 4);
+        let mut restored: Vec<_> = sim.particles.iter().map(|p| (World::cell_of(p.position), p.material)).collect();
+        restored.sort_by_key(|&(cell, _)| cell);
+        assert_eq!(restored, [((2, 4), Sand), ((3, 4), Sand), ((5, 4), Holz), ((6, 4), Holz)]);
+        assert_eq!(sim.world.give_occupation_on_position(0, 4), Some(ParticleRef::Static));
+    }
+
+    /// Sandschicht mit Wasser darauf in einem 4 breiten Grid; nach dem Aushärten werden alle Partikel ein Objekt.
+    fn wet_sand(mix: bool) -> Simulation {
+        let mut sim = Simulation::new(6, 4, [0.0, -1.0]);
+        if !mix {
+            sim.mixing_recipes.clear();
+        }
+        sim.spawn_particles_rect((0, 0), (3, 0), MaterialTyp::Sand);
+        sim.spawn_particles_rect((0, 1), (3, 1), MaterialTyp::Wasser);
+        sim.step();
+        sim
+    }
+
+    #[test]
+    fn sand_and_water_cure_into_concrete_that_resists_fracture() {
+        let mut sim = wet_sand(true);
+        assert!(sim.particles.iter().all(|p| p.material == MaterialTyp::Beton));
+        assert!(sim.particles.iter().all(|p| sim.is_curing(p.id)));
+        for _ in 0..BETON_CURE_TICKS {
+            sim.step();
+        }
+        assert!(sim.particles.iter().all(|p| !sim.is_curing(p.id)));
+
+        let bonded = sim.bond_clusters(1, 2);
+        assert_eq!(bonded.len(), 1);
+        let concrete = &sim.objects[bonded[0]];
+        let sand = Object::new(9, [0.0, 0.0], [0.0, 0.0], MaterialTyp::Sand, concrete.object_h, concrete.object_w).unwrap();
+        let materials = MaterialTable::default();
+        assert!(concrete.check_fracture(100.0, 1.0, &materials).is_empty());
+        assert!(!sand.check_fracture(100.0, 1.0, &materials).is_empty());
+    }
+
+    #[test]
+    fn scene_without_mixing_recipes_keeps_sand_and_water() {
+        let sim = wet_sand(false);
+        assert!(sim.particles.iter().all(|p| matches!(p.material, MaterialTyp::Sand | MaterialTyp::Wasser)));
+    }
+
+    #[test]
+    fn hundred_objects_step_without_panic() {
+        let mut sim = SimulationBuilder::new().world(100, 100).seed(3).build().unwrap();
+        let spawned = sim.spawn_object_grid((0, 50), 10, 10, 1, 5.0);
+        assert_eq!(spawned.len(), 100);
+
+        let start = std::time::Instant::now();
+        sim.step();
+        assert!(start.elapsed() < std::time::Duration::from_secs(5), "{:?}", start.elapsed());
+    }
+
+    #[test]
+    fn every_material_but_air_is_paintable_once() {
+        let mut paintable = MaterialTyp::PAINTABLE.to_vec();
+        paintable.sort_by_key(|m| MaterialTyp::ALL.iter().position(|a| a == m));
+        paintable.dedup();
+        let expected: Vec<_> = MaterialTyp::ALL.into_iter().filter(|&m| m != MaterialTyp::Luft).collect();
+        assert_eq!(paintable, expected);
+        assert_eq!(MaterialTyp::PAINTABLE[..5], [MaterialTyp::Sand, MaterialTyp::Stein, MaterialTyp::Metall, MaterialTyp::Holz, MaterialTyp::Wasser]);
+    }
+
+    #[test]
+    fn gc_shrinks_objects_and_grid_refs_still_resolve() {
+        let mut sim = Simulation::new(10, 12, [0.0, -1.0]);
+        for x in [1.0, 5.0, 9.0] {
+            sim.spawn_object([x, 2.0], MaterialTyp::Stein, 2, 2).unwrap();
+        }
+        let Simulation { objects, world, .. } = &mut sim;
+        objects[1].clear_from_world(world);
+        objects[1].is_destroyed = true;
+
+        assert_eq!(sim.gc_objects(), [Some(0), None, Some(1)]);
+        assert_eq!(sim.objects.len(), 2);
+        let mut refs = 0;
+        for y in 0..sim.world.height {
+            for x in 0..sim.world.width {
+                let Some(ParticleRef::InObject(id, i, j)) = sim.world.give_occupation_on_position(x, y) else { continue; };
+                let obj = sim.live_object_by_id(id).expect("Referenz auf entferntes Objekt");
+                assert_eq!(obj.contains_world_cell(x, y), Some((i, j)));
+                refs += 1;
+            }
+        }
+        assert_eq!(refs, 8);
+    }
+
+    #[test]
+    fn predicted_fragments_match_the_actual_impact() {
+        let materials = MaterialTable::default();
+        for force in [80.0, 160.0] {
+            let mut stone = Object::new(1, [0.0, 0.0], [0.0, 0.0], MaterialTyp::Stein, 4, 2).unwrap();
+            let predicted = stone.predict_fragments(force, 1.0, &materials);
+            assert_eq!(stone.check_fracture(force, 1.0, &materials).is_empty(), predicted.len() == 1);
+
+            let mut actual = stone.receive_impact(force, &materials);
+            for _ in 0..20 {
+                if actual.is_some() || stone.check_fracture(force, 1.0, &materials).is_empty() { break; }
+                actual = stone.propagate_crack(&materials);
+            }
+            match actual {
+                Some(fragments) => assert_eq!(fragments, predicted, "Kraft {}", force),
+                None => assert_eq!(predicted.len(), 1, "Kraft {}", force),
+            }
+        }
+    }
+
+    #[test]
+    fn particle_accelerates_by_the_sum_of_all_gravity_sources() {
+        let mut sim = Simulation::new(40, 40, [0.0, -1.0]);
+        sim.add_gravity_source(GravitySource::Well { center: [20.0, 20.0], strength: 4.0, radius: 20.0 });
+        let start = [14.0, 12.0];
+        let idx = sim.spawn_particle(start, [0.0, 0.0], MaterialTyp::Sand);
+
+        // Abstand 10 bei Radius 20: halbe Stärke in Richtung (0.6, 0.8), dazu die Grundschwerkraft
+        let expected = [1.2, 1.6 - 1.0];
+        let net = sim.net_gravity(start);
+        assert!((net[0] - expected[0]).abs() < 1e-5 && (net[1] - expected[1]).abs() < 1e-5, "{:?}", net);
+
+        sim.step();
+        let v = sim.particles[idx].velocity;
+        assert!((v[0] - expected[0]).abs() < 1e-5 && (v[1] - expected[1]).abs() < 1e-5, "{:?}", v);
+    }
+
+    #[test]
+    fn sand_column_over_its_limit_collapses_from_the_top() {
+        let column = |material: MaterialTyp| {
+            let mut sim = Simulation::new(10, 11, [0.0, -1.0]);
+            let idxs: Vec<usize> = (0..7).map(|y| sim.spawn_particle([5.0, y as f32], [0.0, 0.0], material)).collect();
+            sim.topple_columns();
+            idxs.iter().map(|&idx| World::cell_of(sim.particles[idx].position)).collect::<Vec<_>>()
+        };
+
+        let sand = column(MaterialTyp::Sand);
+        assert!(sand[..6].iter().enumerate().all(|(y, &cell)| cell == (5, y)), "{:?}", sand);
+        assert!(sand[6] == (4, 5) || sand[6] == (6, 5), "{:?}", sand[6]);
+
+        let stone = column(MaterialTyp::Stein);
+        assert!(stone.iter().enumerate().all(|(y, &cell)| cell == (5, y)), "{:?}", stone);
+    }
+
+    #[test]
+    fn stats_csv_has_one_row_per_tick() {
+        let path = std::env::temp_dir().join(format!("world_stats_{}.csv", std::process::id()));
+        let mut recorder = StatsRecorder::new(&path).unwrap();
+        let mut sim = Simulation::new(10, 10, [0.0, -1.0]);
+        sim.spawn_particles_rect((2, 5), (4, 6), MaterialTyp::Sand);
+        sim.spawn_quadrant([5.0, 4.0]).unwrap();
+        for _ in 0..5 {
+            let events = sim.step();
+            recorder.record(&sim, &events).unwrap();
+        }
+        recorder.flush().unwrap();
+
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 6);
+        assert_eq!(rows[0], StatsRecorder::HEADER);
+        assert!(rows.iter().all(|row| row.split(',').count() == 7), "{:?}", rows);
+        assert!(rows[5].starts_with("5,6,1,"), "{}", rows[5]);
+    }
+
+    #[test]
+    fn rightward_gravity_accumulates_pressure_horizontally() {
+        let mut world = World::new(5, 8);
+        for x in 1..4 {
+            world.update_occupation_on_position([x as f32, 2.0], ParticleRef::InObject(1, 0, x - 1));
+            world.update_mass_on_position([x as f32, 2.0], 2.0);
+        }
+        world.calc_pressure_along([1.0, 0.0]);
+
+        let row: Vec<f32> = (0..8).map(|x| world.give_pressure_on_position(x, 2)).collect();
+        assert_eq!(row, [0.0, 2.0, 4.0, 6.0, 6.0, 6.0, 6.0, 6.0]);
+        assert!((0..8).all(|x| world.give_pressure_on_position(x, 1) == 0.0 && world.give_pressure_on_position(x, 3) == 0.0));
+    }
+
+    /// Metallblock auf einem 10 Zellen tiefen Bett aus `bed`; liefert die Ankerhöhe nach 60 Ticks.
+    fn metal_on_bed(bed: MaterialTyp) -> f32 {
+        let mut sim = SimulationBuilder::new().world(30, 10).seed(9).build().unwrap();
+        sim.spawn_particles_rect((0, 0), (9, 9), bed);
+        let idx = sim.spawn_object([3.0, 10.0], MaterialTyp::Metall, 2, 3).unwrap();
+        for _ in 0..60 {
+            sim.step();
+        }
+        sim.objects[idx].position[1]
+    }
+
+    #[test]
+    fn metal_sinks_into_deep_sand_but_not_into_stone() {
+        assert!(metal_on_bed(MaterialTyp::Sand) < 10.0);
+        assert_eq!(metal_on_bed(MaterialTyp::Stein), 10.0);
+    }
+
+    #[test]
+    fn degenerate_objects_are_rejected_cleanly() {
+        assert!(Object::new(1, [0.0, 0.0], [0.0, 0.0], MaterialTyp::Stein, 0, 3).is_err());
+        assert!(Object::new(1, [0.0, 0.0], [0.0, 0.0], MaterialTyp::Stein, 3, 0).is_err());
+        assert!(Object::new_from_fragment(1, &[], [0.0, 0.0]).is_err());
+
+        let mut sim = Simulation::new(10, 10, [0.0, -1.0]);
+        assert!(sim.spawn_object([1.0, 1.0], MaterialTyp::Stein, 0, 2).is_err());
+        assert!(sim.objects.is_empty());
+    }
+
+    #[test]
+    fn builder_reports_degenerate_objects() {
+        let built = SimulationBuilder::new()
+            .world(10, 10)
+            .add_object([1.0, 1.0], MaterialTyp::Stein, 2, 2)
+            .add_object([5.0, 1.0], MaterialTyp::Holz, 0, 2)
+            .build();
+        assert!(built.is_err());
+
+        let sim = SimulationBuilder::new().world(10, 10).add_object([1.0, 1.0], MaterialTyp::Stein, 2, 2).build().unwrap();
+        assert_eq!(sim.objects.len(), 1);
+    }
+
+    #[test]
+    fn scene_with_a_stone_block_above_a_water_pool() {
+        let (world, particles, objects) = SceneBuilder::new(10, 8)
+            .floor()
+            .pool(MaterialTyp::Wasser, (0, 1), (7, 2))
+            .block(MaterialTyp::Stein, [3.0, 5.0], 2, 2)
+            .build()
+            .unwrap();
+
+        assert_eq!((particles.len(), objects.len()), (16, 1));
+        let id = objects[0].object_id;
+        for y in 0..10 {
+            for x in 0..8 {
+                let expected = match (x, y) {
+                    (_, 0) => Some(ParticleRef::Static),
+                    (_, 1..=2) => Some(particles[(y - 1) * 8 + x].particle_ref),
+                    (3..=4, 5..=6) => Some(ParticleRef::InObject(id, y - 5, x - 3)),
+                    _ => None,
+                };
+                assert_eq!(world.give_occupation_on_position(x, y), expected, "Zelle ({}, {})", x, y);
+            }
+        }
+
+        assert!(SceneBuilder::new(10, 8).pool(MaterialTyp::Wasser, (0, 0), (8, 0)).build().is_err());
+        assert!(SceneBuilder::new(10, 8).floor().block(MaterialTyp::Stein, [0.0, 0.0], 1, 1).build().is_err());
+    }
+
+    #[test]
+    fn object_sweeping_through_particles_keeps_grid_mass() {
+        let mut sim = SimulationBuilder::new().world(12, 20).seed(4).build().unwrap();
+        for x in 0..20 {
+            sim.world.update_occupation_on_position([x as f32, 0.0], ParticleRef::Static);
+        }
+        sim.spawn_particles_rect((8, 1), (12, 2), MaterialTyp::Sand);
+        let idx = sim.spawn_object([4.0, 1.0], MaterialTyp::Metall, 2, 2).unwrap();
+        sim.objects[idx].velocity = [3.0, 0.0];
+        sim.record_mass_baseline();
+        let before = sim.total_mass();
+
+        for _ in 0..20 {
+            sim.step();
+            let report = sim.stability_check();
+            assert!(report.is_stable(), "{:?}", report.issues);
+        }
+        assert!(sim.objects[idx].position[0] >= 8.0, "{:?}", sim.objects[idx].position);
+        assert!((sim.total_mass() - before).abs() < 1e-3);
+    }
+
+    #[test]
+    fn uniform_profile_breaks_upper_rows_that_inverse_spares() {
+        let materials = MaterialTable::default();
+        let column = |profile| Object::new(1, [0.0, 0.0], [0.0, 0.0], MaterialTyp::Stein, 4, 1).unwrap().with_force_profile(profile);
+        let upper: Bond = ((2, 0), (3, 0));
+
+        let inverse = column(ForceProfile::Inverse).check_fracture(160.0, 1.0, &materials);
+        let uniform = column(ForceProfile::Uniform).check_fracture(160.0, 1.0, &materials);
+        assert!(inverse.contains(&((0, 0), (1, 0))));
+        assert!(!inverse.contains(&upper));
+        assert!(uniform.contains(&upper));
+        assert!(uniform.len() > inverse.len());
+    }
+
+    #[test]
+    fn quadrant_composition_counts_four_cells_per_material() {
+        use MaterialTyp::*;
+        let quadrant = Object::new_quadrant(1, [0.0, 0.0], [0.0, 0.0]);
+        assert_eq!(quadrant.composition(), HashMap::from([(Holz, 4), (Stein, 4), (Metall, 4), (Sand, 4)]));
+        // Gleichstand: das frühere Material in `MaterialTyp::ALL`
+        assert_eq!(quadrant.dominant_material(), Sand);
+
+        let mixed = Object::new_quadrant_with(2, [0.0, 0.0], [0.0, 0.0], QUADRANT_PRESETS[1]);
+        assert_eq!(mixed.composition(), HashMap::from([(Stein, 8), (Holz, 8)]));
+    }
+
+    #[test]
+    fn clusters_one_cell_apart_merge_at_radius_two_only() {
+        let scene = || {
+            let mut sim = Simulation::new(5, 10, [0.0, -1.0]);
+            sim.spawn_particles_rect((0, 0), (2, 1), MaterialTyp::Stein);
+            sim.spawn_particles_rect((4, 0), (6, 1), MaterialTyp::Stein);
+            sim
+        };
+        assert_eq!(scene().find_clusters(1).len(), 2);
+        assert_eq!(scene().find_clusters(2).len(), 1);
+
+        let mut sim = scene();
+        assert_eq!(sim.bond_clusters(1, 6).len(), 2);
+        let mut sim_bridged = scene();
+        let bonded = sim_bridged.bond_clusters(2, 6);
+        assert_eq!(bonded.len(), 1);
+        assert_eq!(sim_bridged.objects[bonded[0]].composition()[&MaterialTyp::Stein], 12);
+        assert!(sim.particles.is_empty() && sim_bridged.particles.is_empty());
+    }
+
+    #[test]
+    fn strong_flow_carries_sand_downstream() {
+        let mut sim = SimulationBuilder::new().world(6, 40).seed(0).build().unwrap();
+        // Nur Erosion: Sand unter Wasser würde sonst zu Beton
+        sim.mixing_recipes.clear();
+        for x in 0..40 {
+            sim.world.update_occupation_on_position([x as f32, 0.0], ParticleRef::Static);
+        }
+        // Statische Rampe bis x = 3, dahinter ein Sandbett auf gleicher Höhe; das Wasser läuft in Zeile 3 darüber
+        for (x, y) in (0..4).flat_map(|x| (1..3).map(move |y| (x, y))) {
+            sim.world.update_occupation_on_position([x as f32, y as f32], ParticleRef::Static);
+        }
+        sim.spawn_particles_rect((4, 1), (9, 2), MaterialTyp::Sand);
+        sim.emitters.push(Emitter { pos: (0, 3), material: MaterialTyp::Wasser, rate: 1, velocity: [4.0, 0.0] });
+        // Pumpe: hält die Strömung gegen den Widerstand in Gang
+        sim.add_step_hook(|_, particles, _| {
+            for p in particles.iter_mut().filter(|p| p.material == MaterialTyp::Wasser) {
+                p.velocity[0] = 4.0;
+            }
+        });
+
+        let sand = |sim: &Simulation, xs: std::ops::Range<usize>| sim.particles.iter()
+            .filter(|p| p.material == MaterialTyp::Sand || p.sediment == Some(MaterialTyp::Sand))
+            .filter(|p| xs.contains(&World::cell_of(p.position).0))
+            .count();
+        assert_eq!((sand(&sim, 0..10), sand(&sim, 10..40)), (12, 0));
+        for _ in 0..20 {
+            sim.step();
+        }
+        assert!(sand(&sim, 0..10) < 12);
+        assert!(sand(&sim, 10..40) > 0);
+        assert_eq!(sand(&sim, 0..40), 12);
+    }
+
+    #[test]
+    fn material_neighbors_lists_cells_from_top_left_to_bottom_right() {
+        use MaterialTyp::*;
+        let mut sim = Simulation::new(5, 5, [0.0, -1.0]);
+        sim.spawn_particle([1.0, 3.0], [0.0, 0.0], Sand);
+        sim.spawn_particle([2.0, 3.0], [0.0, 0.0], Wasser);
+        sim.spawn_particle([1.0, 1.0], [0.0, 0.0], Metall);
+        sim.world.update_occupation_on_position([1.0, 2.0], ParticleRef::Static);
+        sim.spawn_object([3.0, 1.0], Holz, 2, 1).unwrap();
+        sim.spawn_particle([2.0, 1.0], [0.0, 0.0], Stein);
+
+        use Neighbor::{Empty, Material, Static};
+        let around = sim.world.material_neighbors(2, 2, &sim.particles, &sim.objects);
+        assert_eq!(around, [Material(Sand), Material(Wasser), Empty, Static, Material(Holz), Material(Metall), Material(Stein), Material(Holz)]);
+
+        let corner = sim.world.material_neighbors(0, 0, &sim.particles, &sim.objects);
+        assert_eq!(corner, [Empty, Empty, Material(Metall), Empty, Empty, Empty, Empty, Empty]);
+    }
+
+    #[test]
+    fn bodies_count_the_pieces_of_a_fracture() {
+        let mut sim = Simulation::new(10, 10, [0.0, -1.0]);
+        let idx = sim.spawn_object([2.0, 0.0], MaterialTyp::Stein, 4, 2).unwrap();
+        sim.spawn_particle([7.0, 0.0], [0.0, 0.0], MaterialTyp::Sand);
+        assert_eq!(sim.bodies().len(), 2);
+
+        // Unterste Zeile bricht ab: zwei Einzelzellen als Partikel (ein Haufen) und ein 3x2-Rest
+        let fragments = sim.objects[idx].predict_fragments(160.0, 1.0, &sim.world.materials);
+        assert_eq!(fragments.len(), 3);
+        sim.split_object(idx, &fragments);
+
+        let bodies = sim.bodies();
+        assert_eq!(bodies.len(), 3);
+        let mut cells: Vec<usize> = bodies.iter().map(|body| body.cells).collect();
+        cells.sort_unstable();
+        assert_eq!(cells, [1, 2, 6]);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn png_scene_maps_pixels_to_materials() {
+        let pixels = [
+            [[200, 180, 100, 255], [0, 0, 0, 0], [60, 60, 200, 255]],
+            [[128, 128, 128, 255], [205, 175, 105, 255], [0, 0, 0, 0]],
+        ];
+        let image = image::RgbaImage::from_fn(3, 2, |x, y| image::Rgba(pixels[y as usize][x as usize]));
+        let path = std::env::temp_dir().join(format!("world_scene_{}.png", std::process::id()));
+        image.save(&path).unwrap();
+
+        let palette = HashMap::from([
+            ([200, 180, 100], MaterialTyp::Sand),
+            ([128, 128, 128], MaterialTyp::Stein),
+            ([60, 60, 200], MaterialTyp::Wasser),
+        ]);
+        let loaded = World::from_image(&path, &palette);
+        std::fs::remove_file(&path).unwrap();
+        let (world, particles) = loaded.unwrap();
+
+        assert_eq!((world.width, world.height, particles.len()), (3, 2, 4));
+        let material = |x: usize, y: usize| world.give_occupation_on_position(x, y)
+            .map(|r| particles.iter().find(|p| p.particle_ref == r).unwrap().material);
+        // Die oberste Bildzeile ist die oberste Gridzeile
+        assert_eq!([material(0, 1), material(1, 1), material(2, 1)], [Some(MaterialTyp::Sand), None, Some(MaterialTyp::Wasser)]);
+        assert_eq!([material(0, 0), material(1, 0), material(2, 0)], [Some(MaterialTyp::Stein), Some(MaterialTyp::Sand), None]);
+    }
+
+    #[test]
+    fn doubled_gravity_doubles_the_fall_per_tick() {
+        let fall = |g: f32| {
+            let mut sim = Simulation::new(200, 3, [0.0, -g]);
+            let idx = sim.spawn_particle([1.0, 190.0], [0.0, 0.0], MaterialTyp::Sand);
+            for _ in 0..6 {
+                sim.step();
+            }
+            190.0 - sim.particles[idx].position[1]
+        };
+        // 1 + 2 + … + 6 Zellen in sechs Ticks
+        assert_eq!(fall(1.0), 21.0);
+        assert_eq!(fall(2.0), 2.0 * fall(1.0));
+    }
+
+    #[test]
+    fn falling_particle_loses_its_potential_energy() {
+        let mut sim = Simulation::new(20, 5, [0.0, -2.0]);
+        for x in 0..5 {
+            sim.world.update_occupation_on_position([x as f32, 0.0], ParticleRef::Static);
+        }
+        sim.spawn_particle([2.0, 8.0], [0.0, 0.0], MaterialTyp::Metall);
+        let density = sim.world.materials.density(MaterialTyp::Metall);
+        let before = sim.world.potential_energy(sim.gravity);
+        assert_eq!(before, density * 2.0 * 8.0);
+
+        for _ in 0..20 {
+            sim.step();
+        }
+        assert_eq!(sim.activity(), 0);
+        assert_eq!(sim.kinetic_energy(), 0.0);
+        // Das Metall landet auf dem statischen Boden in Höhe 1
+        assert_eq!(before - sim.world.potential_energy(sim.gravity), density * 2.0 * 7.0);
+    }
+
+    #[test]
+    fn set_position_moves_the_object_or_leaves_everything_unchanged() {
+        let mut sim = Simulation::new(10, 10, [0.0, -1.0]);
+        let idx = sim.spawn_object([1.0, 1.0], MaterialTyp::Holz, 2, 2).unwrap();
+        sim.spawn_particle([7.0, 6.0], [0.0, 0.0], MaterialTyp::Sand);
+        let id = sim.objects[idx].object_id;
+
+        let Simulation { objects, world, .. } = &mut sim;
+        objects[idx].set_position(world, [5.0, 2.0]).unwrap();
+        assert_eq!(objects[idx].position, [5.0, 2.0]);
+        assert_eq!(world.give_occupation_on_position(1, 1), None);
+        assert_eq!(world.give_occupation_on_position(6, 3), Some(ParticleRef::InObject(id, 1, 1)));
+
+        let grid = world.grid.clone();
+        for anchor in [[6.0, 5.0], [9.0, 0.0], [-1.0, 0.0]] {
+            assert!(objects[idx].set_position(world, anchor).is_err(), "{:?}", anchor);
+            assert_eq!(objects[idx].position, [5.0, 2.0]);
+            assert_eq!(world.grid, grid);
+        }
+    }
+
+    #[test]
+    fn disabled_particle_pass_keeps_particles_while_objects_fall() {
+        let config = StepConfig { do_particles: false, ..StepConfig::default() };
+        let mut sim = SimulationBuilder::new().world(20, 10).step_config(config).build().unwrap();
+        let p = sim.spawn_particle([1.0, 12.0], [0.0, 0.0], MaterialTyp::Sand);
+        let obj = sim.spawn_object([5.0, 12.0], MaterialTyp::Stein, 2, 2).unwrap();
+
+        for _ in 0..5 {
+            sim.step();
+        }
+        assert_eq!(sim.particles[p].position, [1.0, 12.0]);
+        assert!(sim.objects[obj].position[1] < 12.0);
+    }
+
+    #[test]
+    fn breached_column_jets_water_away_from_the_breach() {
+        // Schacht bei x = 5 zwischen zwei Wänden, voll Wasser
+        let mut sim = SimulationBuilder::new().world(20, 14).gravity([0.0, -1.0]).seed(0).build().unwrap();
+        for x in 0..20 {
+            sim.world.update_occupation_on_position([x as f32, 0.0], ParticleRef::Static);
+        }
+        for y in 1..12 {
+            sim.world.update_occupation_on_position([4.0, y as f32], ParticleRef::Static);
+            sim.world.update_occupation_on_position([6.0, y as f32], ParticleRef::Static);
+        }
+        let water: Vec<usize> = (1..11).map(|y| sim.spawn_particle([5.0, y as f32], [0.0, 0.0], MaterialTyp::Wasser)).collect();
+        for _ in 0..5 {
+            sim.step();
+        }
+
+        // Leck unten in der rechten Wand
+        sim.world.clear_occupation_on_position([6.0, 1.0]);
+        sim.step();
+
+        let escaped: Vec<&Particle> = water.iter().map(|&idx| &sim.particles[idx]).filter(|p| p.position[0] > 6.0).collect();
+        assert!(!escaped.is_empty());
+        assert!(escaped.iter().all(|p| p.velocity[0] > 0.0));
+        assert!(water.iter().all(|&idx| sim.particles[idx].velocity[0] >= 0.0));
+    }
+
+    #[test]
+    fn quadrant_splits_along_its_material_transition() {
+        let materials = MaterialTable::default();
+        let quadrant = Object::new_quadrant(1, [0.0, 0.0], [0.0, 0.0]);
+        let (axis, k) = quadrant.weakest_plane(&materials).unwrap();
+        assert_eq!(k, 2, "{:?}", axis);
+
+        // Schnitte durch reines Material halten mehr aus
+        let strength = |k| quadrant.absorbed_force(&quadrant.plane_bonds(axis, k), &materials);
+        assert!(strength(2) < strength(1));
+        assert!(strength(2) < strength(3));
+    }
+
+    #[test]
+    fn resting_lava_solidifies_into_stone() {
+        let mut sim = Simulation::new(5, 5, [0.0, -1.0]);
+        let lava = sim.spawn_particle([2.0, 0.0], [0.0, 0.0], MaterialTyp::Lava);
+
+        for _ in 0..LAVA_COOL_TICKS - 1 {
+            sim.step();
+        }
+        assert_eq!(sim.particles[lava].material, MaterialTyp::Lava);
+        assert!(sim.particles[lava].age > 0);
+
+        sim.step();
+        assert_eq!(sim.particles[lava].material, MaterialTyp::Stein);
+        assert_eq!(sim.particles[lava].age, 0);
+        assert_eq!(sim.world.grid[0][2].1, sim.world.materials.density(MaterialTyp::Stein));
+    }
+
+    /// Schüttet `grains` Sandkörner nacheinander in Spalte 10 und zählt, wie viele links bzw. rechts davon landen.
+    fn pour_column(order: SlideOrder, grains: usize) -> (usize, usize) {
+        let mut sim = SimulationBuilder::new().world(20, 21).seed(0).slide_order(order).build().unwrap();
+        let mut poured = 0;
+        for _ in 0..500 {
+            if poured < grains && sim.world.give_occupation_on_position(10, 19).is_none() {
+                sim.spawn_particle([10.0, 19.0], [0.0, 0.0], MaterialTyp::Sand);
+                poured += 1;
+            }
+            sim.step();
+            if poured == grains && sim.activity() == 0 {
+                break;
+            }
+        }
+        let left = sim.particles.iter().filter(|p| p.position[0] < 10.0).count();
+        let right = sim.particles.iter().filter(|p| p.position[0] > 10.0).count();
+        (left, right)
+    }
+
+    #[test]
+    fn poured_pile_is_symmetric() {
+        let (left, right) = pour_column(SlideOrder::Alternating, 40);
+        assert!(left.abs_diff(right) <= 2, "links {}, rechts {}", left, right);
+        assert!(left + right >= 30, "Haufen zu schmal: links {}, rechts {}", left, right);
+    }
+
+    #[test]
+    fn object_at_its_apex_is_not_supported() {
+        let mut sim = SimulationBuilder::new().world(20, 10).gravity([0.0, -1.0]).seed(0).build().unwrap();
+        let obj = sim.spawn_object([4.0, 0.0], MaterialTyp::Stein, 2, 2).unwrap();
+        assert!(sim.objects[obj].is_supported(&sim.world));
+
+        sim.objects[obj].velocity = [0.0, 4.0];
+        let mut apex = None;
+        for _ in 0..10 {
+            sim.step();
+            if sim.objects[obj].velocity[1] == 0.0 {
+                apex = Some(sim.objects[obj].position);
+                break;
+            }
+        }
+        let apex = apex.expect("Objekt erreicht keinen Scheitelpunkt");
+        assert!(apex[1] > 0.0 && apex[1] < 18.0, "{:?}", apex);
+        assert!(!sim.objects[obj].is_supported(&sim.world));
+    }
+
+    #[test]
+    fn predicted_path_over_empty_space_falls_straight_down() {
+        let sim = Simulation::new(20, 10, [0.0, -1.0]);
+        let particle = Particle::new(1, [3.0, 15.0], [0.0, 0.0], MaterialTyp::Sand, ParticleRef::Free(0, 0));
+
+        // Geschwindigkeit 1, 2, 3, 4: nach vier Ticks 10 Zellen tiefer
+        let path = sim.predicted_path(&particle, 4);
+        assert_eq!(path, vec![(3, 14), (3, 12), (3, 9), (3, 5)]);
+        assert!(sim.world.grid.iter().all(|row| row.iter().all(|cell| cell.0.is_none())));
+
+        // Die Vorschau hält sich an das `max_speed` der Simulation
+        let mut slow = Simulation::new(20, 10, [0.0, -1.0]);
+        slow.max_speed = 2.0;
+        assert_eq!(slow.predicted_path(&particle, 4), vec![(3, 14), (3, 12), (3, 10), (3, 8)]);
+    }
+
+    #[test]
+    fn custom_quadrant_places_materials_in_their_sub_blocks() {
+        use MaterialTyp::*;
+        let materials = MaterialTable::default();
+        let layout = [Metall, Eis, Holz, Stein];
+        let quadrant = Object::new_quadrant_with(1, [0.0, 0.0], [0.0, 0.0], layout);
+
+        for i in 0..4 {
+            for j in 0..4 {
+                assert_eq!(quadrant.object_grid[i][j].0.material, layout[(i / 2) * 2 + j / 2], "Zelle ({}, {})", i, j);
+            }
+        }
+        let expected: f32 = layout.iter().map(|&m| 4.0 * materials.density(m)).sum();
+        assert!((quadrant.mass(&materials) - expected).abs() < 1e-3, "{} vs. {}", quadrant.mass(&materials), expected);
+    }
+
+    #[test]
+    fn recycle_oldest_keeps_the_cap_and_drops_the_oldest_particle() {
+        let mut sim = Simulation::new(5, 5, [0.0, -1.0]);
+        sim.max_particles = 3;
+        for x in 0..3 {
+            sim.try_spawn_particle([x as f32, 0.0], [0.0, 0.0], MaterialTyp::Sand).unwrap();
+        }
+        let oldest = sim.particles[0].id;
+        assert!(sim.try_spawn_particle([4.0, 0.0], [0.0, 0.0], MaterialTyp::Sand).is_err());
+
+        sim.cap_policy = CapPolicy::RecycleOldest;
+        sim.try_spawn_particle([4.0, 0.0], [0.0, 0.0], MaterialTyp::Sand).unwrap();
+        assert_eq!(sim.particles.len(), 3);
+        assert!(sim.particles.iter().all(|p| p.id != oldest));
+        assert_eq!(sim.world.give_occupation_on_position(0, 0), None);
+        assert!(sim.particles.iter().all(|p| sim.resolve_particle(p.particle_ref).is_some()));
+    }
+
+    #[test]
+    fn ghost_material_passes_through_sand_while_stone_rests_on_it() {
+        let mut sim = SimulationBuilder::new().world(10, 10).seed(0).build().unwrap();
+        sim.world.materials = MaterialTable::from_config("{ Sand: (collision_layer: Some(2)), Metall: (collides_with: Some(1)) }").unwrap();
+        sim.spawn_particles_rect((0, 0), (9, 3), MaterialTyp::Sand);
+        let ghost = sim.spawn_particle([2.0, 4.0], [0.0, 0.0], MaterialTyp::Metall);
+        let stone = sim.spawn_particle([6.0, 4.0], [0.0, 0.0], MaterialTyp::Stein);
+
+        for _ in 0..10 {
+            sim.step();
+        }
+        assert_eq!(sim.particles[ghost].position, [2.0, 0.0]);
+        assert_eq!(sim.particles[stone].position, [6.0, 4.0]);
+        assert_eq!(sim.world.column_height(2), 5);
+        assert_eq!(sim.world.column_height(6), 5);
+    }
+
+    #[test]
+    fn ascii_diff_reports_the_differing_cell() {
+        let expected = "   \n S \n###\n";
+        let actual = "   \n  S\n###\n";
+        let report = testing::ascii_diff(expected, actual).unwrap();
+        let cells: Vec<&str> = report.lines().filter(|line| line.starts_with('(')).collect();
+        assert_eq!(cells, vec!["(1, 1): erwartet 'S', erhalten ' '", "(2, 1): erwartet ' ', erhalten 'S'"]);
+        assert!(report.contains(" !!\n"), "{}", report);
+        assert_eq!(testing::ascii_diff(expected, expected), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "(0, 0): erwartet ' ', erhalten 's'")]
+    fn assert_world_eq_panics_with_the_differing_cell() {
+        let empty = Simulation::new(2, 2, [0.0, -1.0]);
+        let mut sand = Simulation::new(2, 2, [0.0, -1.0]);
+        sand.spawn_particle([0.0, 0.0], [0.0, 0.0], MaterialTyp::Sand);
+        assert_world_eq!(sand, empty);
+    }
+
+    #[test]
+    fn content_bounds_tightly_enclose_a_corner() {
+        let mut sim = Simulation::new(20, 20, [0.0, -1.0]);
+        assert_eq!(sim.content_bounds(), None);
+
+        sim.spawn_particles_rect((16, 17), (19, 19), MaterialTyp::Sand);
+        sim.spawn_particle([15.0, 19.0], [0.0, 0.0], MaterialTyp::Wasser);
+        assert_eq!(sim.content_bounds(), Some((15, 17, 19, 19)));
+
+        // Boden und Wände zählen nicht
+        sim.world.update_occupation_on_position([0.0, 0.0], ParticleRef::Static);
+        assert_eq!(sim.content_bounds(), Some((15, 17, 19, 19)));
+    }
+
+    #[test]
+    fn object_id_survives_another_objects_removal_and_gc() {
+        let mut sim = Simulation::new(10, 12, [0.0, -1.0]);
+        let ids: Vec<i32> = [1.0, 5.0, 9.0].iter()
+            .map(|&x| {
+                let idx = sim.spawn_object([x, 2.0], MaterialTyp::Stein, 2, 2).unwrap();
+                sim.objects[idx].object_id
+            })
+            .collect();
+
+        assert!(sim.remove_object(0));
+        sim.gc_objects();
+        assert_eq!(sim.objects.iter().map(|obj| obj.object_id).collect::<Vec<_>>(), ids[1..]);
+        assert_eq!(sim.object_index(ids[2]), Some(1));
+        assert_eq!(sim.object_index(ids[0]), None);
+
+        // IDs werden nie wiederverwendet
+        let new_idx = sim.spawn_object([1.0, 6.0], MaterialTyp::Holz, 1, 1).unwrap();
+        assert!(ids.iter().all(|&id| sim.objects[new_idx].object_id > id));
+        assert_eq!(sim.world.give_occupation_on_position(9, 2), Some(ParticleRef::InObject(ids[2], 0, 0)));
+    }
+
+    #[test]
+    fn sticky_particle_holds_to_a_wall_until_the_load_grows() {
+        let mut sim = SimulationBuilder::new().world(20, 6).gravity([0.0, -1.0]).seed(0).build().unwrap();
+        let density = sim.world.materials.density(MaterialTyp::Sand);
+        sim.world.materials = MaterialTable::from_config(&format!("{{ Sand: (adhesion: Some({})) }}", 2.5 * density)).unwrap();
+        for y in 0..20 {
+            sim.world.update_occupation_on_position([0.0, y as f32], ParticleRef::Static);
+        }
+        let grain = sim.spawn_particle([1.0, 10.0], [0.0, 0.0], MaterialTyp::Sand);
+
+        for _ in 0..5 {
+            sim.step();
+        }
+        assert_eq!(sim.particles[grain].position, [1.0, 10.0]);
+
+        // Zwei Körner obendrauf: drei Körner Last sind mehr, als die Haftung trägt
+        sim.spawn_particle([1.0, 11.0], [0.0, 0.0], MaterialTyp::Sand);
+        sim.step();
+        assert_eq!(sim.particles[grain].position, [1.0, 10.0]);
+        sim.spawn_particle([1.0, 12.0], [0.0, 0.0], MaterialTyp::Sand);
+        sim.step();
+        assert!(sim.particles[grain].position[1] < 10.0, "{:?}", sim.particles[grain].position);
+    }
+
+    #[test]
+    fn water_and_lava_react_on_contact() {
+        use MaterialTyp::*;
+        let mut sim = SimulationBuilder::new().world(5, 8).seed(0).build().unwrap();
+        assert_eq!(sim.react(Wasser, Lava), None);
+
+        // Dampf gibt es nicht als Material, das Wasser verdampft zu Luft
+        sim.add_reaction(Wasser, Lava, (Luft, Stein));
+        assert_eq!(sim.react(Lava, Wasser), Some((Stein, Luft)));
+
+        let water = sim.spawn_particle([3.0, 0.0], [0.0, 0.0], Wasser);
+        let lava = sim.spawn_particle([4.0, 0.0], [0.0, 0.0], Lava);
+        let far_lava = sim.spawn_particle([7.0, 0.0], [0.0, 0.0], Lava);
+        sim.step();
+
+        assert_eq!(sim.particles[water].material, Luft);
+        assert_eq!(sim.particles[lava].material, Stein);
+        assert_eq!(sim.particles[far_lava].material, Lava);
+    }
+
+    #[test]
+    fn centered_anchor_centers_the_block_on_the_click() {
+        assert_eq!(centered_anchor((10, 10), 3, 3, 20, 20), Some((9, 9)));
+        // h = 4, w = 2: Mitte zwischen den Zellen, `center` ist die rechte obere der vier mittleren
+        assert_eq!(centered_anchor((10, 10), 4, 2, 20, 20), Some((9, 8)));
+
+        // Am Rand wird der Block ins Grid geschoben
+        assert_eq!(centered_anchor((0, 0), 3, 3, 20, 20), Some((0, 0)));
+        assert_eq!(centered_anchor((19, 19), 3, 3, 20, 20), Some((17, 17)));
+        assert_eq!(centered_anchor((-5, 30), 2, 2, 20, 20), Some((0, 18)));
+        assert_eq!(centered_anchor((10, 10), 21, 1, 20, 20), None);
+    }
+
+    #[test]
+    fn particle_on_a_rightward_conveyor_moves_right() {
+        let mut sim = SimulationBuilder::new().world(10, 12).seed(0).build().unwrap();
+        for x in 0..12 {
+            sim.place_conveyor(x, 0, Conveyor::Right).unwrap();
+        }
+        let grain = sim.spawn_particle([2.0, 4.0], [0.0, 0.0], MaterialTyp::Stein);
+
+        let mut last_x = sim.particles[grain].position[0];
+        let mut moved = 0;
+        for _ in 0..15 {
+            sim.step();
+            let [x, y] = sim.particles[grain].position;
+            assert!(x >= last_x, "{} -> {}", last_x, x);
+            if y == 1.0 && x > last_x {
+                moved += 1;
+            }
+            last_x = x;
+        }
+        assert!(moved >= 3, "nur {} Schritte nach rechts", moved);
+        assert!(last_x > 5.0, "{}", last_x);
+    }
+
+    #[test]
+    fn thin_fragments_get_tight_boxes_and_anchors() {
+        use MaterialTyp::*;
+        // Zeile, ungeordnet und mit gerundeten Positionen
+        let row = Object::new_from_fragment(1, &[([5.0, 3.0], Holz), ([3.0, 3.0], Stein), ([4.0000005, 2.9999995], Sand)], [0.0, 0.0]).unwrap();
+        assert_eq!((row.object_h, row.object_w, row.position), (1, 3, [3.0, 3.0]));
+        assert_eq!(row.object_grid[0].iter().map(|cell| cell.0.material).collect::<Vec<_>>(), [Stein, Sand, Holz]);
+
+        let column = Object::new_from_fragment(2, &[([7.0, 1.0], Holz), ([7.0, 0.0], Stein)], [0.0, 0.0]).unwrap();
+        assert_eq!((column.object_h, column.object_w, column.position), (2, 1, [7.0, 0.0]));
+        assert_eq!([column.object_grid[0][0].0.material, column.object_grid[1][0].0.material], [Stein, Holz]);
+
+        let single = Object::new_from_fragment(3, &[([0.0, 9.0], Metall)], [1.0, 0.0]).unwrap();
+        assert_eq!((single.object_h, single.object_w, single.position), (1, 1, [0.0, 9.0]));
+        assert_eq!(single.object_grid[0][0].0.position, [0.0, 9.0]);
+
+        assert!(Object::new_from_fragment(4, &[], [0.0, 0.0]).is_err());
+    }
+
+    #[test]
+    fn enormous_force_is_clamped_to_max_speed() {
+        let materials = MaterialTable::default();
+        let mut particle = Particle::new(1, [5.0, 5.0], [0.0, 0.0], MaterialTyp::Sand, ParticleRef::Free(0, 0));
+        particle.apply_force([1e30, -1e30], DEFAULT_MAX_SPEED, &materials);
+        assert_eq!(particle.velocity, [DEFAULT_MAX_SPEED, -DEFAULT_MAX_SPEED]);
+
+        // Auch über die Simulation: nach einem Schritt ist nichts schneller als `max_speed`
+        let mut sim = SimulationBuilder::new().world(100, 100).seed(0).build().unwrap();
+        sim.max_speed = 8.0;
+        let p = sim.spawn_particle([50.0, 90.0], [0.0, 0.0], MaterialTyp::Sand);
+        sim.particles[p].apply_force([0.0, -1e30], sim.max_speed, &sim.world.materials);
+        let obj = sim.spawn_object([20.0, 90.0], MaterialTyp::Stein, 2, 2).unwrap();
+        sim.objects[obj].velocity = [1e30, -1e30];
+        sim.step();
+
+        assert_eq!(sim.particles[p].position, [50.0, 82.0]);
+        assert!(sim.objects[obj].velocity.iter().all(|v| v.abs() <= 8.0), "{:?}", sim.objects[obj].velocity);
+        assert!(sim.objects[obj].position[1] >= 82.0, "{:?}", sim.objects[obj].position);
+    }
+
+    #[test]
+    fn trim_drops_air_borders_and_keeps_world_positions() {
+        use MaterialTyp::*;
+        let mut sim = Simulation::new(12, 12, [0.0, -1.0]);
+        let idx = sim.spawn_object([2.0, 2.0], Holz, 4, 4).unwrap();
+        let Simulation { objects, world, .. } = &mut sim;
+        let obj = &mut objects[idx];
+        // Unterste Zeile sowie linke und rechte Spalte werden Luft, oben links ein Stein zur Orientierung
+        for k in 0..4 {
+            obj.set_material_at(0, k, Luft, world);
+            obj.set_material_at(k, 0, Luft, world);
+            obj.set_material_at(k, 3, Luft, world);
+        }
+        obj.set_material_at(3, 1, Stein, world);
+        let before: Vec<([f32; 2], MaterialTyp)> = obj.get_object_elements().iter()
+            .filter(|p| p.material != Luft)
+            .map(|p| (p.position, p.material))
+            .collect();
+
+        assert!(obj.trim(world));
+        assert_eq!((obj.object_h, obj.object_w, obj.position), (3, 2, [3.0, 3.0]));
+        let after: Vec<([f32; 2], MaterialTyp)> = obj.get_object_elements().iter().map(|p| (p.position, p.material)).collect();
+        assert_eq!(after, before);
+        assert_eq!(obj.object_grid[2][0].0.material, Stein);
+
+        for &([x, y], _) in &after {
+            let Some(ParticleRef::InObject(_, i, j)) = world.give_occupation_on_position(x as usize, y as usize) else { panic!("({}, {}) leer", x, y) };
+            assert_eq!(obj.contains_world_cell(x as usize, y as usize), Some((i, j)));
+        }
+        assert!(!obj.trim(world));
+    }
+
+    #[test]
+    fn cell_reads_populated_empty_and_out_of_bounds_cells() {
+        let mut sim = Simulation::new(4, 4, [0.0, -1.0]);
+        let lower = sim.spawn_particle([1.0, 0.0], [0.0, 0.0], MaterialTyp::Stein);
+        sim.spawn_particle([1.0, 1.0], [0.0, 0.0], MaterialTyp::Stein);
+        sim.world.calc_pressure_along(sim.gravity);
+
+        let density = sim.world.materials.density(MaterialTyp::Stein);
+        let cell = sim.world.cell(1, 0).unwrap();
+        assert_eq!(cell.occupant, Some(sim.particles[lower].particle_ref));
+        assert_eq!(cell.mass, density);
+        assert_eq!(cell.pressure, sim.world.give_pressure_on_position(1, 0));
+        assert!(cell.pressure > sim.world.cell(1, 1).unwrap().pressure);
+
+        assert_eq!(sim.world.cell(3, 3), Some(CellView { occupant: None, mass: 0.0, pressure: 0.0 }));
+        assert_eq!(sim.world.cell(4, 0), None);
+        assert_eq!(sim.world.cell(0, 4), None);
+    }
+
+    #[test]
+    fn sand_poured_into_water_settles_underneath() {
+        use MaterialTyp::*;
+        let mut sim = SimulationBuilder::new().world(14, 12).seed(0).build().unwrap();
+        sim.mixing_recipes.clear();
+        // Becken mit Innenraum x = 3..=8
+        for x in 2..10 {
+            sim.world.update_occupation_on_position([x as f32, 0.0], ParticleRef::Static);
+        }
+        for y in 1..12 {
+            sim.world.update_occupation_on_position([2.0, y as f32], ParticleRef::Static);
+            sim.world.update_occupation_on_position([9.0, y as f32], ParticleRef::Static);
+        }
+        sim.spawn_particles_rect((3, 1), (8, 3), Wasser);
+        sim.spawn_particles_rect((3, 9), (8, 10), Sand);
+
+        for _ in 0..100 {
+            sim.step();
+        }
+        let layer = |y: usize| (3..9).map(|x| sim.material_at(x, y)).collect::<Vec<_>>();
+        for y in 1..=2 {
+            assert_eq!(layer(y), vec![Some(Sand); 6], "Zeile {}", y);
+        }
+        for y in 3..=5 {
+            assert_eq!(layer(y), vec![Some(Wasser); 6], "Zeile {}", y);
+        }
+        assert_eq!(layer(6), vec![None; 6]);
+    }
+
+    #[test]
+    fn clock_counts_ticks_and_simulated_seconds() {
+        let mut sim = SimulationBuilder::new().world(5, 5).dt(0.05).build().unwrap();
+        assert_eq!((sim.elapsed_ticks(), sim.elapsed_time()), (0, 0.0));
+
+        for _ in 0..40 {
+            sim.step();
+        }
+        assert_eq!(sim.elapsed_ticks(), 40);
+        assert!((sim.elapsed_time() - 2.0).abs() < 1e-6, "{}", sim.elapsed_time());
+
+        // Ein anderes dt ändert die Sekunden, nicht die Ticks
+        sim.dt = 0.5;
+        sim.step();
+        assert_eq!(sim.elapsed_ticks(), 41);
+        assert!((sim.elapsed_time() - 2.5).abs() < 1e-6, "{}", sim.elapsed_time());
+    }
+
+    #[test]
+    fn rightward_emitter_produces_a_stream_moving_right() {
+        let emitter = Emitter { pos: (1, 15), material: MaterialTyp::Metall, rate: 2, velocity: [6.0, 0.0] };
+        let mut sim = SimulationBuilder::new().world(20, 40).seed(0).add_emitter(emitter).build().unwrap();
+
+        let mut last_x: Vec<f32> = Vec::new();
+        for _ in 0..8 {
+            sim.step();
+            for (k, p) in sim.particles.iter().enumerate() {
+                assert!(p.position[0] >= 1.0, "{:?}", p.position);
+                if let Some(&before) = last_x.get(k) {
+                    assert!(p.position[0] >= before, "Partikel {}: {} -> {}", k, before, p.position[0]);
+                }
+            }
+            last_x = sim.particles.iter().map(|p| p.position[0]).collect();
+        }
+        assert!(sim.particles.len() >= 3, "{}", sim.particles.len());
+        // Der Seitenschwung lässt nach, aber ältere Partikel sind schon weiter rechts
+        assert!(last_x.windows(2).all(|w| w[0] > w[1]), "{:?}", last_x);
+        assert!(last_x[0] > 10.0, "{:?}", last_x);
+    }
+
+    #[test]
+    fn cloned_quadrant_keeps_materials_with_new_ids() {
+        let mut sim = Simulation::new(12, 16, [0.0, -1.0]);
+        let original = sim.spawn_quadrant([1.0, 0.0]).unwrap();
+        let clone = sim.clone_object(original, [8.0, 0.0]).unwrap();
+        let (a, b) = (&sim.objects[original], &sim.objects[clone]);
+
+        assert_ne!(a.object_id, b.object_id);
+        assert_eq!(b.position, [8.0, 0.0]);
+        for i in 0..4 {
+            for j in 0..4 {
+                let (pa, pb) = (&a.object_grid[i][j].0, &b.object_grid[i][j].0);
+                assert_eq!(pa.material, pb.material);
+                assert_ne!(pa.id, pb.id);
+                assert_eq!(pb.position, [8.0 + j as f32, i as f32]);
+                assert_eq!(sim.world.give_occupation_on_position(8 + j, i), Some(ParticleRef::InObject(b.object_id, i, j)));
+            }
+        }
+
+        // Überlappung und Rand werden abgelehnt, das Grid bleibt unverändert
+        let before = sim.render_ascii();
+        assert!(sim.clone_object(original, [6.0, 0.0]).is_err());
+        assert!(sim.clone_object(original, [13.0, 0.0]).is_err());
+        assert_eq!(sim.render_ascii(), before);
+    }
+
+    #[test]
+    fn chipping_a_corner_frees_a_particle_and_lightens_the_block() {
+        let mut sim = Simulation::new(10, 10, [0.0, -1.0]);
+        let block = sim.spawn_object([2.0, 2.0], MaterialTyp::Stein, 3, 3).unwrap();
+        let mass_before = sim.objects[block].mass(&sim.world.materials);
+
+        let (particle, split) = sim.chip_object(block, 0, 0).unwrap();
+        assert!(split.is_none());
+        let density = sim.world.materials.density(MaterialTyp::Stein);
+        assert!((sim.objects[block].mass(&sim.world.materials) - (mass_before - density)).abs() < 1e-4);
+        assert_eq!((sim.particles[particle].position, sim.particles[particle].material), ([2.0, 2.0], MaterialTyp::Stein));
+        assert_eq!(sim.world.give_occupation_on_position(2, 2), Some(sim.particles[particle].particle_ref));
+        assert!(sim.chip_object(block, 0, 0).is_none());
+
+        // Die Mitte einer Zeile herausschlagen zerlegt sie in zwei Teile
+        let row = sim.spawn_object([2.0, 7.0], MaterialTyp::Holz, 1, 3).unwrap();
+        let (_, split) = sim.chip_object(row, 0, 1).unwrap();
+        assert_eq!(split.unwrap().pieces.len(), 2);
+    }
+
+    #[test]
+    fn sand_cliff_relaxes_into_a_stable_slope() {
+        let mut sim = Simulation::new(12, 11, [0.0, -1.0]);
+        sim.spawn_particles_rect((5, 0), (5, 8), MaterialTyp::Sand);
+
+        let mut passes = 0;
+        while sim.relax_terrain() > 0 {
+            passes += 1;
+            assert!(passes < 50, "Hang kommt nicht zur Ruhe");
+        }
+        assert!(passes > 1);
+        let profile: Vec<usize> = (0..11).map(|x| sim.world.column_height(x)).collect();
+        assert_eq!(profile, [0, 0, 0, 1, 2, 3, 2, 1, 0, 0, 0]);
+
+        // Stein rutscht nicht
+        let mut stone = Simulation::new(12, 11, [0.0, -1.0]);
+        stone.spawn_particles_rect((5, 0), (5, 8), MaterialTyp::Stein);
+        assert_eq!(stone.relax_terrain(), 0);
+        assert_eq!(stone.world.column_height(5), 9);
+    }
+
+    #[test]
+    fn painting_air_over_air_keeps_a_particle_in_the_hole() {
+        let mut sim = Simulation::new(8, 8, [0.0, -1.0]);
+        let idx = sim.spawn_object([2.0, 2.0], MaterialTyp::Holz, 2, 2).unwrap();
+        let Simulation { objects, world, .. } = &mut sim;
+        assert!(objects[idx].set_material_at(1, 1, MaterialTyp::Luft, world));
+        let grain = sim.spawn_particle([3.0, 3.0], [0.0, 0.0], MaterialTyp::Sand);
+        let mass = sim.world.cell(3, 3).unwrap().mass;
+
+        let Simulation { objects, world, .. } = &mut sim;
+        assert!(!objects[idx].set_material_at(1, 1, MaterialTyp::Luft, world));
+        assert!(!objects[idx].set_material_at(0, 0, MaterialTyp::Holz, world));
+        let cell = sim.world.cell(3, 3).unwrap();
+        assert_eq!(cell.occupant, Some(sim.particles[grain].particle_ref));
+        assert_eq!(cell.mass, mass);
+    }
+
+    #[test]
+    fn merge_at_the_cap_keeps_the_total_mass() {
+        let mut sim = Simulation::new(6, 6, [0.0, -1.0]);
+        sim.max_particles = 4;
+        sim.cap_policy = CapPolicy::Merge;
+        sim.spawn_particles_rect((0, 0), (1, 1), MaterialTyp::Stein);
+        let mass_before = sim.total_mass();
+
+        sim.try_spawn_particle([4.0, 5.0], [0.0, 0.0], MaterialTyp::Sand).unwrap();
+        assert_eq!(sim.particles.len(), 4);
+        let density = |m| sim.world.materials.density(m);
+        assert!((sim.total_mass() - (mass_before + density(MaterialTyp::Sand))).abs() < 1e-4);
+
+        // Ein Stein trägt jetzt die Masse von zweien, auch im Grid
+        let heavy: Vec<&Particle> = sim.particles.iter().filter(|p| p.merged_mass > 0.0).collect();
+        assert_eq!(heavy.len(), 1);
+        assert_eq!(heavy[0].mass(&sim.world.materials), 2.0 * density(MaterialTyp::Stein));
+        let (x, y) = World::cell_of(heavy[0].position);
+        assert_eq!(sim.world.cell(x, y).unwrap().mass, 2.0 * density(MaterialTyp::Stein));
+    }
+
+    #[test]
+    fn crack_in_metal_comes_to_rest() {
+        let materials = MaterialTable::default();
+        assert_eq!(materials.brittleness(MaterialTyp::Metall), 0.0);
+        let mut metal = Object::new(1, [0.0, 0.0], [0.0, 0.0], MaterialTyp::Metall, 4, 4).unwrap();
+        assert!(metal.seed_crack(((0, 1), (0, 2)), 1000.0).is_none());
+        assert!(!metal.crack_front.is_empty());
+
+        for _ in 0..3 {
+            assert!(metal.propagate_crack(&materials).is_none());
+        }
+        assert!(metal.crack_front.is_empty());
+        assert!(metal.crack_damage.is_empty());
+        assert_eq!(metal.crack_force, 0.0);
+    }
+}
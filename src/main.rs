@@ -1,12 +1,45 @@
+use bevy::app::AppExit;
+use bevy::input::mouse::MouseWheel;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
-use world::{Particle as SimParticle, Object as SimObject, World as SimWorld, MaterialTyp, ParticleRef};
-
-const GRID_WIDTH: usize = 120;
-const GRID_HEIGHT: usize = 100;
+use std::collections::VecDeque;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use world::{centered_anchor, jitter_column, CapPolicy, Conveyor, Object as SimObject, Simulation as SimCore, Joint, MaterialTyp, Particle, ParticleRef, Piece, Split, SpriteShape, SpriteStyle, StatsRecorder, World, QUADRANT_PRESETS, MaterialTable};
+
+const DEFAULT_GRID_WIDTH: usize = 120;
+const DEFAULT_GRID_HEIGHT: usize = 100;
 const CELL_SIZE: f32 = 8.0;
 const WINDOW_WIDTH: f32 = 960.0;
 const WINDOW_HEIGHT: f32 = 800.0;
 const CAMERA_SPEED: f32 = 400.0;
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 8.0;
+const MATERIAL_CONFIG: &str = "materials.ron";
+const AUTO_PAUSE_TICKS: usize = 60;
+const STRESS_STEPS: usize = 100;
+const SIM_STEP: Duration = Duration::from_millis(50);
+/// Obergrenze der Nachholschritte pro Frame.
+const MAX_STEPS_PER_FRAME: u32 = 4;
+/// Mindestgröße eines Partikelhaufens, der mit C zu einem Objekt gebunden wird.
+const CLUSTER_MIN_SIZE: usize = 8;
+/// So viele zurückliegende Positionen zeigt die Spur eines verfolgten Partikels.
+const TRACE_LENGTH: usize = 32;
+/// So viele Ticks weit zeigt die Fallvorschau unter dem Mauszeiger.
+const FALL_PREVIEW_STEPS: usize = 16;
+/// Ab so vielen zerstörten Objekten wird der Objektvektor verdichtet.
+const OBJECT_GC_THRESHOLD: usize = 32;
+/// Tasten 1-9 wählen die Materialien in der Reihenfolge von `MaterialTyp::PAINTABLE`.
+const MATERIAL_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4, KeyCode::Key5,
+    KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+];
+
+/// Texturen für Materialien mit nicht flachem `SpriteStyle`, einmal beim Start erzeugt. Ein späteres
+/// Neuladen von materials.ron ändert Farben, aber nicht mehr die Form.
+static SPRITE_TEXTURES: OnceLock<HashMap<MaterialTyp, Handle<Image>>> = OnceLock::new();
 
 #[derive(Component)]
 struct ParticleSprite(usize);
@@ -24,60 +57,384 @@ struct DebugLabel;
 #[derive(Component)]
 struct MaterialLabel;
 
+/// Materialübersicht (Taste M), Zeilen werden live aus der Registry befüllt.
+#[derive(Component)]
+struct MaterialPanel;
+
+#[derive(Component)]
+struct MaterialInfoText(MaterialTyp);
+
+#[derive(Component)]
+struct MaterialSwatch(MaterialTyp);
+
 #[derive(Component)]
 struct MainCamera;
 
-#[derive(Resource)]
-struct Simulation {
-    world: SimWorld,
-    particles: Vec<SimParticle>,
-    objects: Vec<SimObject>,
-    gravity: [f32; 2],
-}
+/// Eingefrorene Zelle (x, y) aus `freeze_region`, gezeichnet in ihrer Materialfarbe.
+#[derive(Component)]
+struct FrozenSprite(usize, usize);
+
+/// Förderband-Zelle (x, y), Farbe je nach Laufrichtung.
+#[derive(Component)]
+struct ConveyorSprite(usize, usize);
+
+/// Halbtransparenter Rahmen der Bereichsauswahl.
+#[derive(Component)]
+struct SelectionBox;
+
+/// Teilstück der Partikelspur zwischen Position k und k + 1 der Historie.
+#[derive(Component)]
+struct TraceSegment(usize);
+
+/// Teilstück des vorhergesagten Fallwegs des Partikels unter dem Mauszeiger, von Schritt k nach k + 1.
+#[derive(Component)]
+struct FallPreview(usize);
+
+#[derive(Resource, Deref, DerefMut)]
+struct Simulation(SimCore);
 
 #[derive(Resource)]
 struct Timers {
-    sim: Timer,
+    sim: StepClock,
     spawn: Timer,
 }
 
-#[derive(Resource)]
-struct ParticleCounter(i32);
+/// Feste Schrittweite unabhängig von der Bildrate: Die Frame-Zeit wird gesammelt und in ganze Schritte
+/// umgesetzt, höchstens `max_steps` pro Frame. Rückstand darüber hinaus verfällt, damit ein langsamer Frame
+/// nicht immer mehr Nachholschritte nach sich zieht.
+struct StepClock {
+    step: Duration,
+    max_steps: u32,
+    accumulated: Duration,
+    steps_this_frame: u32,
+}
 
-#[derive(Resource)]
-struct ObjectCounter(i32);
+impl StepClock {
+    fn new(step: Duration, max_steps: u32) -> StepClock {
+        StepClock { step, max_steps, accumulated: Duration::ZERO, steps_this_frame: 0 }
+    }
 
-struct FragmentEvent {
-    object_idx: usize,
-    fragments: Vec<Vec<(usize, usize)>>,
+    /// Verbucht die Frame-Zeit und liefert, wie viele Schritte jetzt fällig sind.
+    fn advance(&mut self, delta: Duration) -> u32 {
+        self.accumulated += delta;
+        let due = (self.accumulated.as_nanos() / self.step.as_nanos()) as u32;
+        self.steps_this_frame = due.min(self.max_steps);
+        self.accumulated = if due > self.max_steps {
+            Duration::from_nanos((self.accumulated.as_nanos() % self.step.as_nanos()) as u64)
+        } else {
+            self.accumulated - self.step * due
+        };
+        self.steps_this_frame
+    }
+
+    /// Lief in diesem Frame mindestens ein Schritt?
+    fn stepped(&self) -> bool {
+        self.steps_this_frame > 0
+    }
+
+    /// Anteil des angebrochenen Schritts, 0..1.
+    fn percent(&self) -> f32 {
+        self.accumulated.as_secs_f32() / self.step.as_secs_f32()
+    }
 }
 
 #[derive(Resource, Default)]
 struct FragmentEvents {
-    events: Vec<FragmentEvent>,
+    splits: Vec<Split>,
+    /// Neu gebundene Partikelhaufen; Partikel-Sprites müssen danach neu aufgebaut werden.
+    bonded: Vec<usize>,
+    /// An der Partikelgrenze wurde ein Partikel ersetzt; die Indizes haben sich verschoben.
+    recycled: bool,
+    /// Von Emittern der Simulation erzeugte Partikel, die noch ein Sprite brauchen.
+    emitted: Vec<usize>,
 }
 
 #[derive(Resource)]
 struct SelectedMaterial(MaterialTyp);
 
+/// Index in `QUADRANT_PRESETS` für Shift+Klick (Taste Q).
+#[derive(Resource, Default)]
+struct QuadrantPreset(usize);
+
+/// Laufrichtung neuer Förderbänder (V setzen, O umdrehen).
+#[derive(Resource)]
+struct ConveyorDirection(Conveyor);
+
+impl Default for ConveyorDirection {
+    fn default() -> Self {
+        ConveyorDirection(Conveyor::Right)
+    }
+}
+
+/// Kurzzeitige Meldung unter dem Material-Label, z.B. wenn ein Objekt abgelehnt wurde.
+#[derive(Resource)]
+struct StatusMessage {
+    text: String,
+    timer: Timer,
+}
+
+impl Default for StatusMessage {
+    fn default() -> Self {
+        // Abgelaufen starten, damit anfangs keine leere Meldung angezeigt wird
+        let mut timer = Timer::from_seconds(3.0, TimerMode::Once);
+        timer.tick(timer.duration());
+        StatusMessage { text: String::new(), timer }
+    }
+}
+
+impl StatusMessage {
+    fn show(&mut self, text: String) {
+        self.text = text;
+        self.timer.reset();
+    }
+}
+
+/// Debug-Färbung freier Partikel nach ihrer ID statt nach Material (Taste I).
+#[derive(Resource, Default)]
+struct IdColors(bool);
+
+/// Partikelquelle oben in der Mitte (Taste R), Streuung mit +/-.
+#[derive(Resource)]
+struct Emitter {
+    enabled: bool,
+    spread: usize,
+}
+
+impl Default for Emitter {
+    fn default() -> Self {
+        Emitter { enabled: false, spread: 2 }
+    }
+}
+
+/// Per Rechtsklick gegriffenes Objekt: (object_idx, gegriffene Zelle (i, j)).
+#[derive(Resource, Default)]
+struct DraggedObject(Option<(usize, (usize, usize))>);
+
+/// Weiche Darstellung für Flüssigkeiten (Taste L).
+#[derive(Resource, Default)]
+struct LiquidSmoothing(bool);
+
+/// Weichgezeichnete Flüssigkeitsmaske (Taste B): Alpha der Wasser-Sprites aus einem 3x3-Box-Blur.
+#[derive(Resource, Default)]
+struct FluidBlur {
+    enabled: bool,
+    mask: Vec<f32>,
+}
+
+/// CSV-Mitschnitt der Tick-Kennzahlen, nur mit `--stats`.
+#[derive(Resource)]
+struct Stats(Option<StatsRecorder>);
+
+/// Zwischenbilder (Taste T): Sprites gleiten zwischen der Position des letzten und des aktuellen Ticks.
+#[derive(Resource, Default)]
+struct Interpolation {
+    enabled: bool,
+    particles: Vec<[f32; 2]>,
+    objects: Vec<[f32; 2]>,
+}
+
+impl Interpolation {
+    /// Position zwischen vorherigem und aktuellem Tick; ohne Vorgänger die aktuelle.
+    fn lerp(&self, previous: Option<&[f32; 2]>, current: [f32; 2], t: f32) -> [f32; 2] {
+        match previous {
+            Some(prev) if self.enabled => [
+                prev[0] + (current[0] - prev[0]) * t,
+                prev[1] + (current[1] - prev[1]) * t,
+            ],
+            _ => current,
+        }
+    }
+}
+
+/// Stabilitätsprüfung (Taste K, sonst alle paar Sekunden): Referenzmasse vor dem nächsten Schritt, Prüfung danach.
+#[derive(Resource)]
+struct StabilityWatch {
+    timer: Timer,
+    armed: bool,
+    on_demand: bool,
+}
+
+impl Default for StabilityWatch {
+    fn default() -> Self {
+        StabilityWatch { timer: Timer::from_seconds(5.0, TimerMode::Repeating), armed: false, on_demand: false }
+    }
+}
+
+/// Auto-Pause: nach `AUTO_PAUSE_TICKS` Ticks ohne Aktivität ruht der Sim-Timer bis zur nächsten Eingabe oder neuen Teilen.
+#[derive(Resource, Default)]
+struct AutoPause {
+    quiet_ticks: usize,
+    paused: bool,
+    population: (usize, usize),
+}
+
+/// Bereichsauswahl per Alt+Ziehen: (Startzelle, aktuelle Zelle).
+#[derive(Resource, Default)]
+struct RegionSelection(Option<((usize, usize), (usize, usize))>);
+
+/// Mittlere Maustaste: verfolgtes freies Partikel und seine letzten Positionen (älteste zuerst).
+#[derive(Resource, Default)]
+struct Trace {
+    target: Option<ParticleRef>,
+    history: VecDeque<[f32; 2]>,
+}
+
+/// Zuletzt berechneter Fallweg für `update_fall_preview`; `key` ist das Partikel und der Tick, für die er gilt.
+#[derive(Resource, Default)]
+struct FallPreviewCache {
+    key: Option<(ParticleRef, u64)>,
+    points: Vec<(usize, usize)>,
+}
+
+/// Stresstest (Taste F9): gemessene Dauer der nächsten `STRESS_STEPS` Simulationsschritte.
+#[derive(Resource, Default)]
+struct StressTest {
+    objects: usize,
+    remaining: usize,
+    steps: usize,
+    total: Duration,
+    slowest: Duration,
+}
+
+/// Kamera folgt dem Massenschwerpunkt (Taste F).
+#[derive(Resource, Default)]
+struct CameraFollow(bool);
+
 impl Default for SelectedMaterial {
     fn default() -> Self {
         SelectedMaterial(MaterialTyp::Sand)
     }
 }
 
-fn grid_to_screen(x: f32, y: f32) -> (f32, f32) {
-    let screen_x = (x - GRID_WIDTH as f32 / 2.0 + 0.5) * CELL_SIZE;
-    let screen_y = (y - GRID_HEIGHT as f32 / 2.0 + 0.5) * CELL_SIZE;
+/// `world [BREITE HÖHE]`: Gittergröße von der Kommandozeile, sonst die Standardgröße.
+fn grid_size_from_args() -> (usize, usize) {
+    let args: Vec<usize> = std::env::args().skip(1).filter_map(|a| a.parse().ok()).collect();
+    match args[..] {
+        [w, h] if w >= 4 && h >= 4 => (w, h),
+        [] => (DEFAULT_GRID_WIDTH, DEFAULT_GRID_HEIGHT),
+        _ => {
+            eprintln!("Gittergröße ungültig, nutze {}x{}", DEFAULT_GRID_WIDTH, DEFAULT_GRID_HEIGHT);
+            (DEFAULT_GRID_WIDTH, DEFAULT_GRID_HEIGHT)
+        }
+    }
+}
+
+/// `world --stats DATEI`: Kennzahlen jedes Ticks als CSV mitschreiben; ohne die Option wird nichts geschrieben.
+fn stats_from_args() -> Stats {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(path) = args.iter().position(|a| a == "--stats").and_then(|i| args.get(i + 1)) else { return Stats(None); };
+    match StatsRecorder::new(path) {
+        Ok(recorder) => Stats(Some(recorder)),
+        Err(err) => {
+            eprintln!("{} konnte nicht angelegt werden: {}", path, err);
+            Stats(None)
+        }
+    }
+}
+
+/// Kamera-Zoom, bei dem das ganze Gitter ins Fenster passt.
+fn initial_camera_scale(world: &World) -> f32 {
+    (world.width as f32 * CELL_SIZE / WINDOW_WIDTH).max(world.height as f32 * CELL_SIZE / WINDOW_HEIGHT)
+}
+
+fn grid_to_screen(world: &World, x: f32, y: f32) -> (f32, f32) {
+    let screen_x = (x - world.width as f32 / 2.0 + 0.5) * CELL_SIZE;
+    let screen_y = (y - world.height as f32 / 2.0 + 0.5) * CELL_SIZE;
     (screen_x, screen_y)
 }
 
-fn material_to_color(material: MaterialTyp) -> Color {
-    let (r, g, b) = material.color();
-    Color::rgb(r, g, b)
+fn material_to_color(materials: &MaterialTable, material: MaterialTyp) -> Color {
+    let (r, g, b) = materials.color(material);
+    Color::rgba(r, g, b, materials.render_alpha(material))
+}
+
+/// Wie `material_to_color`, aber mit dem Farbverlauf alternder Materialien (z.B. abkühlende Lava).
+fn particle_color(materials: &MaterialTable, particle: &Particle) -> Color {
+    let (r, g, b) = particle.color(materials);
+    Color::rgba(r, g, b, materials.render_alpha(particle.material))
+}
+
+/// Feste Farbe je Partikel-ID über das ganze Farbspektrum, damit Nachbar-IDs gut unterscheidbar sind.
+fn id_to_color(id: i32) -> Color {
+    let hash = (id as u32).wrapping_mul(2_654_435_761);
+    Color::hsl((hash >> 16) as f32 / 65536.0 * 360.0, 0.85, 0.55)
+}
+
+/// Sprite für eine Zelle an Gitterposition (x, y), Größe und Deckkraft je nach Material.
+fn material_sprite(world: &World, material: MaterialTyp, x: f32, y: f32, z: f32) -> SpriteBundle {
+    let (screen_x, screen_y) = grid_to_screen(world, x, y);
+    let size = (CELL_SIZE - 1.0) * world.materials.render_scale(material);
+    let texture = SPRITE_TEXTURES.get().and_then(|textures| textures.get(&material)).cloned().unwrap_or_default();
+    SpriteBundle {
+        sprite: Sprite {
+            color: material_to_color(&world.materials, material),
+            custom_size: Some(Vec2::new(size, size)),
+            ..default()
+        },
+        transform: Transform::from_xyz(screen_x, screen_y, z),
+        texture,
+        ..default()
+    }
+}
+
+/// Erzeugt die Texturen für alle Materialien mit Form oder Glanzpunkt; flache bleiben ohne Textur.
+fn create_sprite_textures(mut images: ResMut<Assets<Image>>, sim: Res<Simulation>) {
+    let materials = &sim.world.materials;
+    let textures = MaterialTyp::ALL.into_iter()
+        .filter(|&material| !materials.sprite_style(material).is_flat())
+        .map(|material| (material, images.add(style_texture(materials.sprite_style(material)))))
+        .collect();
+    let _ = SPRITE_TEXTURES.set(textures);
+}
+
+/// 8x8-Maske für einen Stil: Alpha formt das Korn, die Helligkeit den Glanzpunkt oben links.
+/// Die Sprite-Farbe wird damit multipliziert.
+fn style_texture(style: SpriteStyle) -> Image {
+    const SIZE: usize = 8;
+    let radius = SIZE as f32 / 2.0;
+    let shade = (255.0 * (1.0 - style.highlight.clamp(0.0, 1.0))) as u8;
+    let mut data = Vec::with_capacity(SIZE * SIZE * 4);
+    // Bildzeile 0 ist oben
+    for row in 0..SIZE {
+        for col in 0..SIZE {
+            let (dx, dy) = (col as f32 + 0.5 - radius, row as f32 + 0.5 - radius);
+            let inside = style.shape == SpriteShape::Square || dx * dx + dy * dy <= radius * radius;
+            let value = if row + col < SIZE / 2 { 255 } else { shade };
+            data.extend([value, value, value, if inside { 255 } else { 0 }]);
+        }
+    }
+    Image::new(
+        Extent3d { width: SIZE as u32, height: SIZE as u32, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+/// Fenster und Kamera, um den Mauszeiger auf Gitterzellen abzubilden.
+#[derive(SystemParam)]
+struct Cursor<'w, 's> {
+    windows: Query<'w, 's, &'static Window>,
+    camera: Query<'w, 's, (&'static Transform, &'static OrthographicProjection), With<MainCamera>>,
+}
+
+impl Cursor<'_, '_> {
+    /// Gitterzelle unter dem Mauszeiger (kann außerhalb des Grids liegen).
+    fn grid(&self, world: &World) -> Option<(i32, i32)> {
+        let cursor_pos = self.windows.single().cursor_position()?;
+        let (camera_transform, projection) = self.camera.single();
+        let (w, h) = (world.width, world.height);
+        let world_x = (cursor_pos.x - WINDOW_WIDTH / 2.0) * projection.scale + camera_transform.translation.x;
+        let world_y = (WINDOW_HEIGHT / 2.0 - cursor_pos.y) * projection.scale + camera_transform.translation.y;
+        let grid_x = (world_x / CELL_SIZE + w as f32 / 2.0).floor() as i32;
+        let grid_y = (world_y / CELL_SIZE + h as f32 / 2.0).floor() as i32;
+        Some((grid_x, grid_y))
+    }
 }
 
 fn main() {
+    let (grid_width, grid_height) = grid_size_from_args();
+
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
@@ -87,45 +444,86 @@ fn main() {
             }),
             ..default()
         }))
-        .insert_resource(Simulation {
-            world: SimWorld::new(GRID_HEIGHT, GRID_WIDTH),
-            particles: Vec::new(),
-            objects: Vec::new(),
-            gravity: [0.0, -1.0],
-        })
+        .insert_resource(Simulation(SimCore::new(grid_height, grid_width, [0.0, -1.0])))
         .insert_resource(Timers {
-            sim: Timer::from_seconds(0.05, TimerMode::Repeating),
+            sim: StepClock::new(SIM_STEP, MAX_STEPS_PER_FRAME),
             spawn: Timer::from_seconds(0.08, TimerMode::Repeating),
         })
-        .insert_resource(ParticleCounter(0))
-        .insert_resource(ObjectCounter(0))
         .insert_resource(FragmentEvents::default())
+        .insert_resource(QuadrantPreset::default())
+        .insert_resource(ConveyorDirection::default())
         .insert_resource(SelectedMaterial::default())
-        .add_systems(Startup, setup)
-        .add_systems(Update, camera_movement)
+        .insert_resource(DraggedObject::default())
+        .insert_resource(Emitter::default())
+        .insert_resource(IdColors::default())
+        .insert_resource(StatusMessage::default())
+        .insert_resource(LiquidSmoothing::default())
+        .insert_resource(FluidBlur::default())
+        .insert_resource(CameraFollow::default())
+        .insert_resource(Interpolation::default())
+        .insert_resource(StabilityWatch::default())
+        .insert_resource(AutoPause::default())
+        .insert_resource(RegionSelection::default())
+        .insert_resource(StressTest::default())
+        .insert_resource(Trace::default())
+        .insert_resource(FallPreviewCache::default())
+        .insert_resource(stats_from_args())
+        .add_systems(Startup, (create_sprite_textures, setup).chain())
+        .add_systems(Update, (camera_movement, camera_zoom, hot_reload_materials))
+        .add_systems(Last, flush_stats)
         .add_systems(Update, (
-            change_material,
+            (change_material, toggle_frozen_material, toggle_liquid_smoothing, toggle_id_colors, toggle_interpolation, toggle_clusters, cycle_quadrant_preset, cycle_cap_policy, place_conveyors),
             spawn_particles,
             spawn_object,
+            pin_object,
+            drag_object,
+            (paint_object, split_hovered_object, hollow_hovered_object, chip_hovered_cell, start_stress_test, select_trace),
+            select_region,
+            apply_region,
+            resume_auto_pause,
+            arm_stability_check,
             run_simulation,
-            handle_fragments,
+            (report_stability, report_stress_test, record_trace),
+            (handle_fragments, collect_destroyed_objects).chain(),
+            update_fluid_blur,
             update_sprites,
             update_object_sprites,
+            (update_selection_box, update_trace_segments, update_fall_preview),
             update_debug_label,
             update_material_label,
+            update_material_panel,
         ).chain())
         .run();
 }
 
+fn reload_materials(sim: &mut SimCore) {
+    if !std::path::Path::new(MATERIAL_CONFIG).exists() { return; }
+    match MaterialTable::load(MATERIAL_CONFIG) {
+        Ok(materials) => {
+            println!("{}: {} Materialien überschrieben", MATERIAL_CONFIG, materials.overridden_count());
+            sim.set_materials(materials);
+        }
+        Err(err) => eprintln!("{} konnte nicht geladen werden: {}", MATERIAL_CONFIG, err),
+    }
+}
+
+fn hot_reload_materials(keyboard: Res<Input<KeyCode>>, mut sim: ResMut<Simulation>) {
+    if keyboard.just_pressed(KeyCode::F5) { reload_materials(&mut sim); }
+}
+
 fn setup(mut commands: Commands, mut sim: ResMut<Simulation>) {
-    commands.spawn((Camera2dBundle::default(), MainCamera));
+    let mut camera = Camera2dBundle::default();
+    camera.projection.scale = initial_camera_scale(&sim.world);
+    commands.spawn((camera, MainCamera));
+    reload_materials(&mut sim);
+    sim.dt = SIM_STEP.as_secs_f32();
 
     // Boden
-    for x in 0..GRID_WIDTH {
+    for x in 0..sim.world.width {
         sim.world.update_occupation_on_position([x as f32, 0.0], ParticleRef::Static);
         sim.world.update_mass_on_position([x as f32, 0.0], 1000.0);
 
-        let (screen_x, screen_y) = grid_to_screen(x as f32, 0.0);
+        let (screen_x, screen_y) = grid_to_screen(&sim.world, x as f32, 0.0);
         commands.spawn(SpriteBundle {
             sprite: Sprite {
                 color: Color::GRAY,
@@ -137,6 +535,43 @@ fn setup(mut commands: Commands, mut sim: ResMut<Simulation>) {
         });
     }
 
+    // Auswahlrahmen, sichtbar solange eine Bereichsauswahl besteht
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite { color: Color::rgba(1.0, 1.0, 1.0, 0.2), ..default() },
+            transform: Transform::from_xyz(0.0, 0.0, 3.0),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        SelectionBox,
+    ));
+
+    // Spur des verfolgten Partikels, ausgeblendet solange keins gewählt ist
+    for k in 0..TRACE_LENGTH - 1 {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite { color: Color::rgba(1.0, 0.9, 0.2, 0.0), ..default() },
+                transform: Transform::from_xyz(0.0, 0.0, 4.0),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            TraceSegment(k),
+        ));
+    }
+
+    // Fallvorschau für das Partikel unter dem Mauszeiger
+    for k in 0..FALL_PREVIEW_STEPS {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite { color: Color::rgba(0.3, 0.9, 1.0, 0.0), ..default() },
+                transform: Transform::from_xyz(0.0, 0.0, 4.0),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            FallPreview(k),
+        ));
+    }
+
     // Debug-Label
     commands.spawn((
         TextBundle::from_section("", TextStyle { font_size: 16.0, color: Color::WHITE, ..default() })
@@ -160,16 +595,93 @@ fn setup(mut commands: Commands, mut sim: ResMut<Simulation>) {
             }),
         MaterialLabel,
     ));
+
+    // Materialübersicht, anfangs versteckt
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(10.0),
+                left: Val::Px(10.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        MaterialPanel,
+    )).with_children(|panel| {
+        for material in MaterialTyp::ALL {
+            panel.spawn(NodeBundle {
+                style: Style { flex_direction: FlexDirection::Row, align_items: AlignItems::Center, ..default() },
+                ..default()
+            }).with_children(|row| {
+                row.spawn((
+                    NodeBundle {
+                        style: Style { width: Val::Px(12.0), height: Val::Px(12.0), margin: UiRect::right(Val::Px(6.0)), ..default() },
+                        ..default()
+                    },
+                    MaterialSwatch(material),
+                ));
+                row.spawn((
+                    TextBundle::from_section("", TextStyle { font_size: 14.0, color: Color::WHITE, ..default() }),
+                    MaterialInfoText(material),
+                ));
+            });
+        }
+    });
+}
+
+/// M blendet die Materialübersicht ein; solange sie sichtbar ist, spiegelt sie die aktuellen Werte.
+fn update_material_panel(
+    keyboard: Res<Input<KeyCode>>,
+    mut panel: Query<&mut Visibility, With<MaterialPanel>>,
+    mut texts: Query<(&MaterialInfoText, &mut Text)>,
+    mut swatches: Query<(&MaterialSwatch, &mut BackgroundColor)>,
+    sim: Res<Simulation>,
+) {
+    let mut visibility = panel.single_mut();
+    if keyboard.just_pressed(KeyCode::M) {
+        *visibility = if *visibility == Visibility::Hidden { Visibility::Visible } else { Visibility::Hidden };
+    }
+    if *visibility == Visibility::Hidden { return; }
+
+    let materials = &sim.world.materials;
+    for (info, mut text) in texts.iter_mut() {
+        let m = info.0;
+        text.sections[0].value = format!(
+            "{:?}: Dichte {:.2}, Bindung {:.1}, Dämpfung {:.2}, {}",
+            m, materials.density(m), materials.binding_strength(m), materials.impact_dampening(m), if m.is_solid() { "fest" } else { "nicht fest" }
+        );
+    }
+    for (swatch, mut color) in swatches.iter_mut() {
+        *color = material_to_color(materials, swatch.0).into();
+    }
 }
 
 fn camera_movement(
     keyboard: Res<Input<KeyCode>>,
     time: Res<Time>,
+    sim: Res<Simulation>,
+    mut follow: ResMut<CameraFollow>,
     mut camera_query: Query<&mut Transform, With<MainCamera>>,
 ) {
     let mut camera_transform = camera_query.single_mut();
     let mut direction = Vec3::ZERO;
 
+    if keyboard.just_pressed(KeyCode::F) { follow.0 = !follow.0; }
+    if follow.0 {
+        // Kamera gleitet zum Massenschwerpunkt der Szene
+        if let Some(center) = sim.world.mass_center() {
+            let (target_x, target_y) = grid_to_screen(&sim.world, center[0], center[1]);
+            let target = Vec3::new(target_x, target_y, camera_transform.translation.z);
+            let t = (5.0 * time.delta_seconds()).min(1.0);
+            camera_transform.translation = camera_transform.translation.lerp(target, t);
+        }
+    }
+
     if keyboard.pressed(KeyCode::W) || keyboard.pressed(KeyCode::Up) { direction.y += 1.0; }
     if keyboard.pressed(KeyCode::S) || keyboard.pressed(KeyCode::Down) { direction.y -= 1.0; }
     if keyboard.pressed(KeyCode::A) || keyboard.pressed(KeyCode::Left) { direction.x -= 1.0; }
@@ -180,321 +692,893 @@ fn camera_movement(
     }
 }
 
+/// Mausrad zoomt (begrenzt), Pos1 zentriert das Gitter wieder mit dem Start-Zoom.
+fn camera_zoom(
+    keyboard: Res<Input<KeyCode>>,
+    mut wheel: EventReader<MouseWheel>,
+    mut follow: ResMut<CameraFollow>,
+    sim: Res<Simulation>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    let (mut camera_transform, mut projection) = camera_query.single_mut();
+    let initial = initial_camera_scale(&sim.world);
+
+    for event in wheel.read() {
+        let factor = if event.y > 0.0 { 0.9 } else if event.y < 0.0 { 1.0 / 0.9 } else { 1.0 };
+        projection.scale = (projection.scale * factor).clamp(MIN_ZOOM, MAX_ZOOM.max(initial));
+    }
+
+    if keyboard.just_pressed(KeyCode::Home) {
+        follow.0 = false;
+        camera_transform.translation.x = 0.0;
+        camera_transform.translation.y = 0.0;
+        projection.scale = initial;
+    }
+}
+
 fn change_material(keyboard: Res<Input<KeyCode>>, mut selected: ResMut<SelectedMaterial>) {
-    if keyboard.just_pressed(KeyCode::Key1) { selected.0 = MaterialTyp::Sand; }
-    else if keyboard.just_pressed(KeyCode::Key2) { selected.0 = MaterialTyp::Stein; }
-    else if keyboard.just_pressed(KeyCode::Key3) { selected.0 = MaterialTyp::Metall; }
-    else if keyboard.just_pressed(KeyCode::Key4) { selected.0 = MaterialTyp::Holz; }
-    else if keyboard.just_pressed(KeyCode::Key5) { selected.0 = MaterialTyp::Wasser; }
+    for (key, material) in MATERIAL_KEYS.iter().zip(MaterialTyp::PAINTABLE) {
+        if keyboard.just_pressed(*key) { selected.0 = material; }
+    }
 }
 
-fn update_material_label(selected: Res<SelectedMaterial>, mut query: Query<&mut Text, With<MaterialLabel>>) {
+/// Taste zum Material als Ziffer, `None` wenn es keine Taste mehr abbekommt.
+fn material_key(material: MaterialTyp) -> Option<usize> {
+    MaterialTyp::PAINTABLE.iter().position(|&m| m == material).filter(|&k| k < MATERIAL_KEYS.len()).map(|k| k + 1)
+}
+
+/// G friert das gewählte Material ein bzw. taut es wieder auf.
+fn toggle_frozen_material(keyboard: Res<Input<KeyCode>>, selected: Res<SelectedMaterial>, mut sim: ResMut<Simulation>) {
+    if !keyboard.just_pressed(KeyCode::G) { return; }
+    if !sim.frozen_materials.remove(&selected.0) {
+        sim.frozen_materials.insert(selected.0);
+    }
+}
+
+fn update_material_label(
+    selected: Res<SelectedMaterial>,
+    sim: Res<Simulation>,
+    auto_pause: Res<AutoPause>,
+    time: Res<Time>,
+    mut status: ResMut<StatusMessage>,
+    mut query: Query<&mut Text, With<MaterialLabel>>,
+) {
     let mut text = query.single_mut();
-    let mat_name = match selected.0 {
-        MaterialTyp::Sand => "Sand [1]",
-        MaterialTyp::Stein => "Stein [2]",
-        MaterialTyp::Metall => "Metall [3]",
-        MaterialTyp::Holz => "Holz [4]",
-        MaterialTyp::Wasser => "Wasser [5]",
-        MaterialTyp::Luft => "Luft",
+    let mat_name = match material_key(selected.0) {
+        Some(key) => format!("{:?} [{}]", selected.0, key),
+        None => format!("{:?}", selected.0),
     };
-    text.sections[0].value = format!("Material: {}\n\n1-5=Material\nShift+Klick=Quadrant\nWASD=Kamera", mat_name);
+    let material_hint = MaterialTyp::PAINTABLE.iter()
+        .filter_map(|&m| material_key(m).map(|key| format!("{}={:?}", key, m)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let frozen = if sim.frozen_materials.contains(&selected.0) { " (eingefroren)" } else { "" };
+    let paused = if auto_pause.paused { "\nSimulation ruht" } else { "" };
+    let cap = if sim.at_particle_cap() { " – Grenze erreicht!" } else { "" };
+    let particles = format!("\nPartikel: {}/{} ({:?}){}\nZeit: {:.1} s ({} Ticks)", sim.particles.len(), sim.max_particles, sim.cap_policy, cap, sim.elapsed_time(), sim.elapsed_ticks());
+    text.sections[0].value = format!("Material: {}{}{}{}\n\n{}\nG=Material einfrieren\nShift+Klick=Quadrant, Q=Vorlage\nStrg+Klick=Objekt übermalen\nX=Objekt teilen\nH=Objekt aushöhlen\nAlt+Ziehen=Bereich wählen, Eingabe=einfrieren, Rücktaste=auftauen\nF9=Stresstest\nL=Wasser glätten\nB=Wasser weichzeichnen\nJ=Objekt anpinnen\nI=Farbe nach ID\nT=Zwischenbilder\nC=Haufen binden\nK=Stabilität prüfen\nM=Materialübersicht\nRechtsklick ziehen=Objekt verschieben\nMittelklick=Partikelspur\nR=Quelle an/aus, +/-=Streuung\nF5=materials.ron laden\nWASD=Kamera\nMausrad=Zoom, Pos1=Ansicht zurücksetzen\nF=Kamera folgt\nP=Partikelgrenze\nV=Förderband, O=Richtung\nE=Zelle abschlagen", mat_name, frozen, particles, paused, material_hint);
+
+    status.timer.tick(time.delta());
+    if !status.timer.finished() {
+        text.sections[0].value = format!("{}\n\n{}", text.sections[0].value, status.text);
+    }
 }
 
 fn spawn_particles(
-    _commands: Commands,
-    _sim: ResMut<Simulation>,
-    _timers: ResMut<Timers>,
-    _counter: ResMut<ParticleCounter>,
-    _selected: Res<SelectedMaterial>,
-    _time: Res<Time>,
+    mut commands: Commands,
+    mut sim: ResMut<Simulation>,
+    (mut timers, mut emitter): (ResMut<Timers>, ResMut<Emitter>),
+    mut fragment_events: ResMut<FragmentEvents>,
+    keyboard: Res<Input<KeyCode>>,
+    selected: Res<SelectedMaterial>,
+    time: Res<Time>,
 ) {
-    // Deaktiviert - kein automatisches Spawning mehr
+    if keyboard.just_pressed(KeyCode::R) { emitter.enabled = !emitter.enabled; }
+    if keyboard.just_pressed(KeyCode::Equals) { emitter.spread += 1; }
+    if keyboard.just_pressed(KeyCode::Minus) { emitter.spread = emitter.spread.saturating_sub(1); }
+
+    timers.spawn.tick(time.delta());
+    if !emitter.enabled || !timers.spawn.just_finished() { return; }
+
+    let (width, height) = (sim.world.width, sim.world.height);
+    let x = jitter_column(width / 2, emitter.spread, width, &mut sim.rng);
+    let y = height - 2;
+    if sim.world.give_occupation_on_position(x, y).is_some() { return; }
+
+    let pos = [x as f32, y as f32];
+    let recycled = sim.at_particle_cap();
+    let Ok(idx) = sim.try_spawn_particle(pos, [0.0, 0.0], selected.0) else { return; };
+    if recycled {
+        fragment_events.recycled = true;
+    } else {
+        commands.spawn((material_sprite(&sim.world, selected.0, pos[0], pos[1], 1.0), ParticleSprite(idx)));
+    }
 }
 
 fn spawn_object(
     mut commands: Commands,
     mut sim: ResMut<Simulation>,
-    mut object_counter: ResMut<ObjectCounter>,
     mouse_button: Res<Input<MouseButton>>,
     keyboard: Res<Input<KeyCode>>,
-    selected: Res<SelectedMaterial>,
-    windows: Query<&Window>,
-    camera_query: Query<&Transform, With<MainCamera>>,
+    (selected, preset): (Res<SelectedMaterial>, Res<QuadrantPreset>),
+    mut status: ResMut<StatusMessage>,
+    cursor: Cursor,
 ) {
     if !mouse_button.just_pressed(MouseButton::Left) { return; }
+    // Strg+Klick gehört dem Übermalen, Alt+Ziehen der Bereichsauswahl
+    if keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight) { return; }
+    if keyboard.pressed(KeyCode::AltLeft) || keyboard.pressed(KeyCode::AltRight) { return; }
 
-    let window = windows.single();
-    let camera_transform = camera_query.single();
-    let cursor_pos = match window.cursor_position() {
-        Some(pos) => pos,
-        None => return,
-    };
-
-    let world_x = cursor_pos.x - WINDOW_WIDTH / 2.0 + camera_transform.translation.x;
-    let world_y = WINDOW_HEIGHT / 2.0 - cursor_pos.y + camera_transform.translation.y;
-    let grid_x = (world_x / CELL_SIZE + GRID_WIDTH as f32 / 2.0) as i32;
-    let grid_y = (world_y / CELL_SIZE + GRID_HEIGHT as f32 / 2.0) as i32;
+    let Some((grid_x, grid_y)) = cursor.grid(&sim.world) else { return; };
 
     let shift_held = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
     let block_size = if shift_held { 4 } else { 3 };
 
-    if grid_x < 0 || grid_x >= GRID_WIDTH as i32 - (block_size - 1)
-        || grid_y < 0 || grid_y >= GRID_HEIGHT as i32 - (block_size - 1) { return; }
+    // Klick außerhalb des Grids ignorieren, am Rand wird der Block hineingeschoben
+    if grid_x < 0 || grid_x >= sim.world.width as i32 || grid_y < 0 || grid_y >= sim.world.height as i32 { return; }
+    let Some((x, y)) = centered_anchor((grid_x, grid_y), block_size, block_size, sim.world.width, sim.world.height) else { return; };
+    if !sim.occupants_in_rect((x, y), (x + block_size - 1, y + block_size - 1)).is_empty() { return; }
 
-    for di in 0..block_size {
-        for dj in 0..block_size {
-            if sim.world.give_occupation_on_position((grid_x + dj) as usize, (grid_y + di) as usize).is_some() { return; }
-        }
+    let anchor = [x as f32, y as f32];
+    let spawned = if shift_held { sim.spawn_quadrant_with(anchor, QUADRANT_PRESETS[preset.0]) } else { sim.spawn_object(anchor, selected.0, 3, 3) };
+    match spawned {
+        Ok(obj_idx) => spawn_object_sprites(&mut commands, &sim.world, &sim.objects[obj_idx], obj_idx),
+        Err(message) => status.show(message),
     }
+}
 
-    object_counter.0 += 1;
-    let obj_id = object_counter.0;
-    let obj_idx = sim.objects.len();
-
-    if shift_held {
-        let object = SimObject::new_quadrant(obj_id, obj_idx, [grid_x as f32, grid_y as f32], [0.0, 0.0]);
-
-        for particle in object.get_object_elements() {
-            sim.world.update_occupation_on_position(particle.position, particle.particle_ref);
-            sim.world.update_mass_on_position(particle.position, particle.mass());
-        }
-
-        for i in 0..4 {
-            for j in 0..4 {
-                let particle = object.get_particle_at(i, j);
-                let (screen_x, screen_y) = grid_to_screen(grid_x as f32 + j as f32, grid_y as f32 + i as f32);
+/// Ein Sprite je Objektzelle außer Luft.
+fn spawn_object_sprites(commands: &mut Commands, world: &World, object: &SimObject, obj_idx: usize) {
+    for i in 0..object.get_height() {
+        for j in 0..object.get_width() {
+            let particle = object.get_particle_at(i, j);
+            if particle.material != MaterialTyp::Luft {
                 commands.spawn((
-                    SpriteBundle {
-                        sprite: Sprite {
-                            color: material_to_color(particle.material),
-                            custom_size: Some(Vec2::new(CELL_SIZE - 1.0, CELL_SIZE - 1.0)),
-                            ..default()
-                        },
-                        transform: Transform::from_xyz(screen_x, screen_y, 2.0),
-                        ..default()
-                    },
+                    material_sprite(world, particle.material, particle.position[0], particle.position[1], 2.0),
                     ObjectSprite { object_idx: obj_idx, grid_i: i, grid_j: j },
                 ));
             }
         }
-        sim.objects.push(object);
-    } else {
-        let material = selected.0;
-        let color = material_to_color(material);
-        let object = SimObject::new(obj_id, obj_idx, [grid_x as f32, grid_y as f32], [0.0, 0.0], material, 3, 3);
+    }
+}
 
-        for particle in object.get_object_elements() {
-            sim.world.update_occupation_on_position(particle.position, particle.particle_ref);
-            sim.world.update_mass_on_position(particle.position, particle.mass());
-        }
+/// J über einem Objekt: pinnt die Zelle unter dem Mauszeiger als Gelenk an die Welt.
+fn pin_object(
+    mut sim: ResMut<Simulation>,
+    keyboard: Res<Input<KeyCode>>,
+    cursor: Cursor,
+) {
+    if !keyboard.just_pressed(KeyCode::J) { return; }
+    let Some((grid_x, grid_y)) = cursor.grid(&sim.world) else { return; };
+    if grid_x < 0 || grid_y < 0 { return; }
 
-        for i in 0..3 {
-            for j in 0..3 {
-                let (screen_x, screen_y) = grid_to_screen(grid_x as f32 + j as f32, grid_y as f32 + i as f32);
-                commands.spawn((
-                    SpriteBundle {
-                        sprite: Sprite {
-                            color,
-                            custom_size: Some(Vec2::new(CELL_SIZE - 1.0, CELL_SIZE - 1.0)),
-                            ..default()
-                        },
-                        transform: Transform::from_xyz(screen_x, screen_y, 2.0),
-                        ..default()
-                    },
-                    ObjectSprite { object_idx: obj_idx, grid_i: i, grid_j: j },
-                ));
-            }
-        }
-        sim.objects.push(object);
-    }
+    let Some((obj, i, j)) = sim.object_at(grid_x as usize, grid_y as usize) else { return; };
+    let joint = Joint { object_id: obj.object_id, anchor_cell: (i, j), world_point: [grid_x as f32, grid_y as f32] };
+    sim.joints.push(joint);
 }
 
-fn run_simulation(
+/// Strg + linke Maustaste: Objektzellen unter dem Mauszeiger im gewählten Material übermalen.
+/// Zerfällt das Objekt dadurch (Luft), wird es in seine Teile zerlegt.
+fn paint_object(
+    mut commands: Commands,
     mut sim: ResMut<Simulation>,
-    mut timers: ResMut<Timers>,
     mut fragment_events: ResMut<FragmentEvents>,
-    time: Res<Time>,
+    (mouse_button, keyboard): (Res<Input<MouseButton>>, Res<Input<KeyCode>>),
+    selected: Res<SelectedMaterial>,
+    cursor: Cursor,
+    object_sprites: Query<&ObjectSprite>,
 ) {
-    timers.sim.tick(time.delta());
-    if !timers.sim.just_finished() { return; }
+    let ctrl_held = keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight);
+    if !ctrl_held || !mouse_button.pressed(MouseButton::Left) { return; }
+    let Some((grid_x, grid_y)) = cursor.grid(&sim.world) else { return; };
+    if grid_x < 0 || grid_y < 0 { return; }
+
+    let Some((obj_idx, (i, j))) = sim.objects.iter().enumerate()
+        .find_map(|(obj_idx, obj)| obj.contains_world_cell(grid_x as usize, grid_y as usize).map(|cell| (obj_idx, cell)))
+    else { return; };
+    if sim.objects[obj_idx].get_particle_at(i, j).material == selected.0 { return; }
+
+    let SimCore { world, objects, .. } = &mut **sim;
+    if !objects[obj_idx].set_material_at(i, j, selected.0, world) { return; }
+
+    let has_sprite = object_sprites.iter().any(|s| s.object_idx == obj_idx && (s.grid_i, s.grid_j) == (i, j));
+    if !has_sprite {
+        let particle = sim.objects[obj_idx].get_particle_at(i, j);
+        commands.spawn((
+            material_sprite(&sim.world, particle.material, particle.position[0], particle.position[1], 2.0),
+            ObjectSprite { object_idx: obj_idx, grid_i: i, grid_j: j },
+        ));
+    }
 
-    sim.world.calc_pressure_on_all_position();
+    let fragments = sim.objects[obj_idx].find_fragments(&[]);
+    if fragments.len() > 1 {
+        let pieces = sim.split_object(obj_idx, &fragments);
+        fragment_events.splits.push(Split { object_idx: obj_idx, pieces });
+    }
+}
+
+/// Demo (Taste X): Objekt unter dem Mauszeiger senkrecht in der Mitte teilen, ganz ohne Aufprall.
+fn split_hovered_object(
+    keyboard: Res<Input<KeyCode>>,
+    mut sim: ResMut<Simulation>,
+    mut fragment_events: ResMut<FragmentEvents>,
+    cursor: Cursor,
+) {
+    if !keyboard.just_pressed(KeyCode::X) { return; }
+    let Some((grid_x, grid_y)) = cursor.grid(&sim.world) else { return; };
+    if grid_x < 0 || grid_y < 0 { return; }
+
+    let Some(obj_idx) = sim.objects.iter()
+        .position(|obj| obj.contains_world_cell(grid_x as usize, grid_y as usize).is_some())
+    else { return; };
+
+    let fragments = sim.objects[obj_idx].split_vertical();
+    if fragments.len() > 1 {
+        let pieces = sim.split_object(obj_idx, &fragments);
+        fragment_events.splits.push(Split { object_idx: obj_idx, pieces });
+    }
+}
 
-    let gravity = sim.gravity;
+/// Taste H: Objekt unter dem Mauszeiger zu einem Behälter aushöhlen.
+fn hollow_hovered_object(keyboard: Res<Input<KeyCode>>, mut sim: ResMut<Simulation>, cursor: Cursor) {
+    if !keyboard.just_pressed(KeyCode::H) { return; }
+    let Some((grid_x, grid_y)) = cursor.grid(&sim.world) else { return; };
+    if grid_x < 0 || grid_y < 0 { return; }
 
-    let Simulation { world, particles, .. } = &mut *sim;
-    for p in particles.iter_mut() {
-        p.update_velocity(gravity, world);
-        p.update_position(world);
+    let SimCore { world, objects, .. } = &mut **sim;
+    if let Some(obj) = objects.iter_mut().find(|obj| obj.contains_world_cell(grid_x as usize, grid_y as usize).is_some()) {
+        obj.hollow(world);
     }
+}
 
-    for p in particles.iter_mut() {
-        p.resolve_pressure(world);
+/// Spitzhacke (Taste E): Objektzelle unter dem Mauszeiger als freies Partikel herausschlagen.
+/// Zerfällt das Objekt dadurch, wird es in seine Teile zerlegt.
+fn chip_hovered_cell(
+    mut commands: Commands,
+    keyboard: Res<Input<KeyCode>>,
+    mut sim: ResMut<Simulation>,
+    mut fragment_events: ResMut<FragmentEvents>,
+    cursor: Cursor,
+    object_sprites: Query<(Entity, &ObjectSprite)>,
+) {
+    if !keyboard.just_pressed(KeyCode::E) { return; }
+    let Some((grid_x, grid_y)) = cursor.grid(&sim.world) else { return; };
+    if grid_x < 0 || grid_y < 0 { return; }
+
+    let Some((obj_idx, (i, j))) = sim.objects.iter().enumerate()
+        .find_map(|(obj_idx, obj)| obj.contains_world_cell(grid_x as usize, grid_y as usize).map(|cell| (obj_idx, cell)))
+    else { return; };
+    let Some((idx, split)) = sim.chip_object(obj_idx, i, j) else { return; };
+
+    let particle = &sim.particles[idx];
+    commands.spawn((material_sprite(&sim.world, particle.material, particle.position[0], particle.position[1], 1.0), ParticleSprite(idx)));
+    // Bei einer Zerlegung räumt `handle_fragments` alle Sprites des Objekts ab
+    if let Some(split) = split {
+        fragment_events.splits.push(split);
+        return;
     }
+    for (entity, sprite) in object_sprites.iter() {
+        if sprite.object_idx == obj_idx && (sprite.grid_i, sprite.grid_j) == (i, j) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
 
-    for p in particles.iter_mut() {
-        p.fall_down(world);
+/// Alt + linke Maustaste ziehen: Rechteck auswählen. Esc verwirft die Auswahl.
+fn select_region(
+    mouse_button: Res<Input<MouseButton>>,
+    keyboard: Res<Input<KeyCode>>,
+    sim: Res<Simulation>,
+    cursor: Cursor,
+    mut selection: ResMut<RegionSelection>,
+) {
+    if keyboard.just_pressed(KeyCode::Escape) {
+        selection.0 = None;
+        return;
     }
+    if !mouse_button.pressed(MouseButton::Left) { return; }
+    let Some((grid_x, grid_y)) = cursor.grid(&sim.world) else { return; };
+    let cell = (grid_x.max(0) as usize, grid_y.max(0) as usize);
+
+    let alt_held = keyboard.pressed(KeyCode::AltLeft) || keyboard.pressed(KeyCode::AltRight);
+    if alt_held && mouse_button.just_pressed(MouseButton::Left) {
+        selection.0 = Some((cell, cell));
+    } else if let Some((_, end)) = selection.0.as_mut() {
+        if alt_held { *end = cell; }
+    }
+}
 
-    // Flüssigkeiten breiten sich seitlich aus
-    for p in particles.iter_mut() {
-        p.flow_sideways(world);
+/// Eingabe friert die Auswahl zu statischem Gelände ein, Rücktaste taut sie wieder auf.
+fn apply_region(
+    mut commands: Commands,
+    mut sim: ResMut<Simulation>,
+    keyboard: Res<Input<KeyCode>>,
+    mut selection: ResMut<RegionSelection>,
+    particle_sprites: Query<Entity, With<ParticleSprite>>,
+    object_sprites: Query<(Entity, &ObjectSprite)>,
+    frozen_sprites: Query<(Entity, &FrozenSprite)>,
+) {
+    let Some((a, b)) = selection.0 else { return; };
+
+    if keyboard.just_pressed(KeyCode::Return) {
+        for (x, y, material) in sim.freeze_region(a, b) {
+            commands.spawn((material_sprite(&sim.world, material, x as f32, y as f32, 0.5), FrozenSprite(x, y)));
+        }
+        for (entity, sprite) in object_sprites.iter() {
+            if sim.live_object(sprite.object_idx).is_none() {
+                commands.entity(entity).despawn();
+            }
+        }
+        // Entfernte Partikel verschieben Indizes: Partikel-Sprites komplett neu aufbauen
+        for entity in particle_sprites.iter() {
+            commands.entity(entity).despawn();
+        }
+        for (idx, particle) in sim.particles.iter().enumerate() {
+            commands.spawn((material_sprite(&sim.world, particle.material, particle.position[0], particle.position[1], 1.0), ParticleSprite(idx)));
+        }
+        selection.0 = None;
+    } else if keyboard.just_pressed(KeyCode::Back) {
+        let (x0, x1) = (a.0.min(b.0), a.0.max(b.0));
+        let (y0, y1) = (a.1.min(b.1), a.1.max(b.1));
+        for (entity, cell) in frozen_sprites.iter() {
+            if (x0..=x1).contains(&cell.0) && (y0..=y1).contains(&cell.1) {
+                commands.entity(entity).despawn();
+            }
+        }
+        for idx in sim.unfreeze_region(a, b) {
+            let particle = &sim.particles[idx];
+            commands.spawn((material_sprite(&sim.world, particle.material, particle.position[0], particle.position[1], 1.0), ParticleSprite(idx)));
+        }
+        selection.0 = None;
     }
+}
 
-    let Simulation { world, objects, .. } = &mut *sim;
-    for (obj_idx, obj) in objects.iter_mut().enumerate() {
-        if obj.is_destroyed { continue; }
+fn update_selection_box(sim: Res<Simulation>, selection: Res<RegionSelection>, mut query: Query<(&mut Transform, &mut Sprite, &mut Visibility), With<SelectionBox>>) {
+    let Ok((mut transform, mut sprite, mut visibility)) = query.get_single_mut() else { return; };
+    let Some((a, b)) = selection.0 else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    let (x0, x1) = (a.0.min(b.0) as f32, a.0.max(b.0) as f32);
+    let (y0, y1) = (a.1.min(b.1) as f32, a.1.max(b.1) as f32);
+    let (left, bottom) = grid_to_screen(&sim.world, x0, y0);
+    let (right, top) = grid_to_screen(&sim.world, x1, y1);
+    transform.translation.x = (left + right) / 2.0;
+    transform.translation.y = (bottom + top) / 2.0;
+    sprite.custom_size = Some(Vec2::new(right - left + CELL_SIZE, top - bottom + CELL_SIZE));
+    *visibility = Visibility::Visible;
+}
+
+/// Mittlere Maustaste auf ein freies Partikel: seine Spur aufzeichnen. Erneuter Klick darauf oder ins Leere beendet sie.
+fn select_trace(sim: Res<Simulation>, mouse_button: Res<Input<MouseButton>>, cursor: Cursor, mut trace: ResMut<Trace>) {
+    if !mouse_button.just_pressed(MouseButton::Middle) { return; }
+    let (w, h) = (sim.world.width, sim.world.height);
+    let clicked = cursor.grid(&sim.world)
+        .filter(|&(x, y)| x >= 0 && y >= 0 && (x as usize) < w && (y as usize) < h)
+        .and_then(|(x, y)| sim.world.give_occupation_on_position(x as usize, y as usize))
+        .filter(|&r| sim.resolve_particle(r).is_some());
+
+    trace.history.clear();
+    trace.target = if clicked == trace.target { None } else { clicked };
+}
+
+/// Hängt nach jedem Schritt die aktuelle Position des verfolgten Partikels an; ist es verschwunden, endet die Spur.
+fn record_trace(sim: Res<Simulation>, mut trace: ResMut<Trace>) {
+    let Some(target) = trace.target else { return; };
+    let Some(particle) = sim.resolve_particle(target) else {
+        trace.target = None;
+        trace.history.clear();
+        return;
+    };
 
-        if let Some(fragments) = obj.update_object_velocity(gravity, world) {
-            fragment_events.events.push(FragmentEvent { object_idx: obj_idx, fragments });
+    let position = particle.get_position();
+    if trace.history.back() == Some(&position) { return; }
+    trace.history.push_back(position);
+    if trace.history.len() > TRACE_LENGTH {
+        trace.history.pop_front();
+    }
+}
+
+/// Spannt je ein dünnes Sprite zwischen zwei aufeinanderfolgende Positionen; ältere Stücke verblassen.
+fn update_trace_segments(sim: Res<Simulation>, trace: Res<Trace>, mut query: Query<(&TraceSegment, &mut Transform, &mut Sprite, &mut Visibility)>) {
+    let segments = trace.history.len().saturating_sub(1);
+    for (segment, mut transform, mut sprite, mut visibility) in query.iter_mut() {
+        if segment.0 >= segments {
+            *visibility = Visibility::Hidden;
             continue;
         }
 
-        if !obj.is_destroyed {
-            obj.update_object_position(world);
+        let from = trace.history[segment.0];
+        let to = trace.history[segment.0 + 1];
+        let (x0, y0) = grid_to_screen(&sim.world, from[0], from[1]);
+        let (x1, y1) = grid_to_screen(&sim.world, to[0], to[1]);
+        let delta = Vec2::new(x1 - x0, y1 - y0);
+        transform.translation.x = (x0 + x1) / 2.0;
+        transform.translation.y = (y0 + y1) / 2.0;
+        transform.rotation = Quat::from_rotation_z(delta.y.atan2(delta.x));
+        sprite.custom_size = Some(Vec2::new(delta.length().max(1.0), CELL_SIZE / 4.0));
+        sprite.color.set_a((segment.0 + 1) as f32 / segments as f32);
+        *visibility = Visibility::Visible;
+    }
+}
+
+/// Zeichnet den vorhergesagten Fallweg des freien Partikels unter dem Mauszeiger; spätere Stücke verblassen.
+/// Neu gerechnet wird nur, wenn ein anderes Partikel unter dem Mauszeiger liegt oder die Simulation weiterläuft.
+fn update_fall_preview(
+    sim: Res<Simulation>,
+    cursor: Cursor,
+    mut cache: ResMut<FallPreviewCache>,
+    mut query: Query<(&FallPreview, &mut Transform, &mut Sprite, &mut Visibility)>,
+) {
+    let (w, h) = (sim.world.width, sim.world.height);
+    let particle = cursor.grid(&sim.world)
+        .filter(|&(x, y)| x >= 0 && y >= 0 && (x as usize) < w && (y as usize) < h)
+        .and_then(|(x, y)| sim.world.give_occupation_on_position(x as usize, y as usize))
+        .and_then(|r| sim.resolve_particle(r));
+    let key = particle.map(|p| (p.particle_ref, sim.elapsed_ticks()));
+    if key != cache.key {
+        cache.points = match particle {
+            Some(particle) => std::iter::once(World::cell_of(particle.position))
+                .chain(sim.predicted_path(particle, FALL_PREVIEW_STEPS))
+                .collect(),
+            None => Vec::new(),
+        };
+        cache.key = key;
+    }
+    let points = &cache.points;
+
+    for (segment, mut transform, mut sprite, mut visibility) in query.iter_mut() {
+        let Some((&from, &to)) = points.get(segment.0).zip(points.get(segment.0 + 1)) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        if from == to {
+            *visibility = Visibility::Hidden;
+            continue;
         }
+
+        let (x0, y0) = grid_to_screen(&sim.world, from.0 as f32, from.1 as f32);
+        let (x1, y1) = grid_to_screen(&sim.world, to.0 as f32, to.1 as f32);
+        let delta = Vec2::new(x1 - x0, y1 - y0);
+        transform.translation.x = (x0 + x1) / 2.0;
+        transform.translation.y = (y0 + y1) / 2.0;
+        transform.rotation = Quat::from_rotation_z(delta.y.atan2(delta.x));
+        sprite.custom_size = Some(Vec2::new(delta.length().max(1.0), CELL_SIZE / 4.0));
+        sprite.color.set_a(1.0 - segment.0 as f32 / FALL_PREVIEW_STEPS as f32);
+        *visibility = Visibility::Visible;
     }
+}
+
+/// Rechte Maustaste gedrückt halten: Objekt unter dem Mauszeiger greifen und verschieben.
+/// Blockierte Zielpositionen werden übersprungen, das Objekt bleibt dann stehen.
+fn drag_object(
+    mut sim: ResMut<Simulation>,
+    mut dragged: ResMut<DraggedObject>,
+    mouse_button: Res<Input<MouseButton>>,
+    cursor: Cursor,
+) {
+    if mouse_button.just_released(MouseButton::Right) {
+        dragged.0 = None;
+        return;
+    }
+    let Some((grid_x, grid_y)) = cursor.grid(&sim.world) else { return; };
 
-    let Simulation { world, objects, .. } = &mut *sim;
-    for (obj_idx, obj) in objects.iter_mut().enumerate() {
-        if obj.is_destroyed { continue; }
+    if mouse_button.just_pressed(MouseButton::Right) && grid_x >= 0 && grid_y >= 0 {
+        dragged.0 = sim.objects.iter().enumerate()
+            .find_map(|(obj_idx, obj)| obj.contains_world_cell(grid_x as usize, grid_y as usize).map(|cell| (obj_idx, cell)))
+            .filter(|&(obj_idx, _)| !sim.is_pinned(obj_idx));
+    }
 
-        let vel = obj.get_object_velocity();
-        if vel[1] != 0.0 { continue; }
+    let Some((obj_idx, (i, j))) = dragged.0 else { return; };
+    if sim.live_object(obj_idx).is_none() {
+        dragged.0 = None;
+        return;
+    }
 
-        let broken_bonds = obj.check_pressure_fracture(world);
-        if !broken_bonds.is_empty() {
-            let fragments = obj.find_fragments(&broken_bonds);
-            if fragments.len() > 1 {
-                fragment_events.events.push(FragmentEvent { object_idx: obj_idx, fragments });
+    let anchor = [(grid_x - j as i32) as f32, (grid_y - i as i32) as f32];
+    let SimCore { world, objects, .. } = &mut **sim;
+    objects[obj_idx].relocate(anchor, world);
+}
+
+fn run_simulation(
+    mut sim: ResMut<Simulation>,
+    mut timers: ResMut<Timers>,
+    mut fragment_events: ResMut<FragmentEvents>,
+    (mut interpolation, mut auto_pause, mut stress, mut stats): (ResMut<Interpolation>, ResMut<AutoPause>, ResMut<StressTest>, ResMut<Stats>),
+    mut object_sprites: Query<&mut ObjectSprite>,
+    time: Res<Time>,
+) {
+    if auto_pause.paused { return; }
+    let steps = timers.sim.advance(time.delta());
+
+    for _ in 0..steps {
+        interpolation.particles = sim.particles.iter().map(|p| p.get_position()).collect();
+        interpolation.objects = sim.objects.iter().map(|o| o.get_object_position()).collect();
+        let step_start = Instant::now();
+        let events = sim.step();
+        if stress.remaining > 0 {
+            let elapsed = step_start.elapsed();
+            stress.remaining -= 1;
+            stress.steps += 1;
+            stress.total += elapsed;
+            stress.slowest = stress.slowest.max(elapsed);
+        }
+        if let Some(recorder) = stats.0.as_mut() {
+            if let Err(err) = recorder.record(&sim, &events) {
+                eprintln!("Statistik: {}", err);
+                stats.0 = None;
+            }
+        }
+        auto_pause.quiet_ticks = if sim.activity() > 0 { 0 } else { auto_pause.quiet_ticks + 1 };
+        auto_pause.paused = auto_pause.quiet_ticks >= AUTO_PAUSE_TICKS;
+
+        // Gelenke: angepinnte Objekte schwingen um ihren Drehpunkt, Sprites drehen mit
+        for (obj_idx, clockwise) in events.rotations {
+            let (h, w) = (sim.objects[obj_idx].get_width(), sim.objects[obj_idx].get_height());
+            for mut sprite in object_sprites.iter_mut().filter(|s| s.object_idx == obj_idx) {
+                (sprite.grid_i, sprite.grid_j) = SimObject::rotated_cell(h, w, sprite.grid_i, sprite.grid_j, clockwise);
             }
         }
+
+        fragment_events.splits.extend(events.splits);
+        fragment_events.bonded.extend(events.bonded);
+        fragment_events.emitted.extend(events.emitted);
+        fragment_events.recycled |= events.recycled;
+        if auto_pause.paused { break; }
     }
 }
 
-fn handle_fragments(
+/// Schreibt den CSV-Puffer beim Beenden auf die Platte.
+fn flush_stats(mut exit: EventReader<AppExit>, mut stats: ResMut<Stats>) {
+    if exit.read().count() == 0 { return; }
+    if let Some(Err(err)) = stats.0.as_mut().map(|recorder| recorder.flush()) {
+        eprintln!("Statistik: {}", err);
+    }
+}
+
+/// Weckt die pausierte Simulation bei jeder Eingabe und sobald Teile hinzukommen oder verschwinden.
+fn resume_auto_pause(
+    sim: Res<Simulation>,
+    (keyboard, mouse_button): (Res<Input<KeyCode>>, Res<Input<MouseButton>>),
+    mut wheel: EventReader<MouseWheel>,
+    mut auto_pause: ResMut<AutoPause>,
+) {
+    let population = (sim.particles.len(), sim.objects.len());
+    let input = keyboard.get_just_pressed().next().is_some()
+        || mouse_button.get_pressed().next().is_some()
+        || wheel.read().count() > 0;
+    if input || population != auto_pause.population {
+        auto_pause.quiet_ticks = 0;
+        auto_pause.paused = false;
+    }
+    auto_pause.population = population;
+}
+
+/// Taste F9: ein Raster aus Quadranten-Blöcken oben in die Welt schießen und die folgenden Schritte messen.
+fn start_stress_test(
     mut commands: Commands,
     mut sim: ResMut<Simulation>,
-    mut fragment_events: ResMut<FragmentEvents>,
-    mut counter: ResMut<ParticleCounter>,
-    mut object_counter: ResMut<ObjectCounter>,
-    object_sprites: Query<(Entity, &ObjectSprite)>,
+    keyboard: Res<Input<KeyCode>>,
+    mut stress: ResMut<StressTest>,
 ) {
-    if fragment_events.events.is_empty() { return; }
+    if !keyboard.just_pressed(KeyCode::F9) { return; }
+
+    let cols = (sim.world.width.saturating_sub(4) / 5).min(12);
+    let origin = (2, sim.world.height.saturating_sub(3 * 5 + 2));
+    let spawned = sim.spawn_object_grid(origin, cols, 3, 1, 2.0);
+    for &obj_idx in &spawned {
+        spawn_object_sprites(&mut commands, &sim.world, &sim.objects[obj_idx], obj_idx);
+    }
+    *stress = StressTest { objects: spawned.len(), remaining: STRESS_STEPS, ..default() };
+}
+
+fn report_stress_test(mut stress: ResMut<StressTest>, mut status: ResMut<StatusMessage>) {
+    if stress.steps == 0 || stress.remaining > 0 { return; }
+
+    let average = stress.total.as_secs_f64() * 1000.0 / stress.steps as f64;
+    status.show(format!(
+        "Stresstest: {} Objekte, {} Schritte, Ø {:.2} ms, langsamster {:.2} ms",
+        stress.objects, stress.steps, average, stress.slowest.as_secs_f64() * 1000.0,
+    ));
+    *stress = StressTest::default();
+}
 
-    for event in fragment_events.events.drain(..) {
-        let obj_idx = event.object_idx;
-        if obj_idx >= sim.objects.len() || sim.objects[obj_idx].is_destroyed { continue; }
+/// Merkt sich nach allen Eingriffen des Frames die Gesamtmasse, damit nur der Simulationsschritt geprüft wird.
+fn arm_stability_check(keyboard: Res<Input<KeyCode>>, time: Res<Time>, mut sim: ResMut<Simulation>, mut watch: ResMut<StabilityWatch>) {
+    watch.timer.tick(time.delta());
+    let on_demand = keyboard.just_pressed(KeyCode::K);
+    if watch.armed || !(on_demand || watch.timer.just_finished()) { return; }
 
-        let old_velocity = sim.objects[obj_idx].get_object_velocity();
-        let fragment_data: Vec<Vec<([f32; 2], MaterialTyp)>> = event.fragments.iter()
-            .map(|frag| sim.objects[obj_idx].extract_fragment_data(frag))
-            .collect();
+    sim.record_mass_baseline();
+    watch.armed = true;
+    watch.on_demand = on_demand;
+}
+
+fn report_stability(sim: Res<Simulation>, timers: Res<Timers>, mut watch: ResMut<StabilityWatch>, mut status: ResMut<StatusMessage>) {
+    if !watch.armed || !timers.sim.stepped() { return; }
+    watch.armed = false;
 
-        let Simulation { world, objects, .. } = &mut *sim;
-        objects[obj_idx].clear_from_world(world);
-        objects[obj_idx].is_destroyed = true;
+    let report = sim.stability_check();
+    if let Some(first) = report.issues.first() {
+        status.show(format!("Instabil: {} Befunde, z.B. {:?}", report.issues.len(), first));
+    } else if watch.on_demand {
+        status.show("Simulation stabil".to_string());
+    }
+}
+
+/// Ersetzt die Sprites zerbrochener Objekte durch Sprites für die entstandenen Teile.
+fn handle_fragments(
+    mut commands: Commands,
+    sim: Res<Simulation>,
+    mut fragment_events: ResMut<FragmentEvents>,
+    object_sprites: Query<(Entity, &ObjectSprite)>,
+    particle_sprites: Query<Entity, With<ParticleSprite>>,
+) {
+    if fragment_events.splits.is_empty() && fragment_events.bonded.is_empty() && fragment_events.emitted.is_empty() && !fragment_events.recycled { return; }
 
+    // Gebundene Haufen und ersetzte Partikel verschieben Partikelindizes: Partikel-Sprites komplett neu aufbauen
+    let rebuild = !fragment_events.bonded.is_empty() || std::mem::take(&mut fragment_events.recycled);
+    for obj_idx in std::mem::take(&mut fragment_events.bonded) {
+        spawn_object_sprites(&mut commands, &sim.world, &sim.objects[obj_idx], obj_idx);
+    }
+    if rebuild {
+        for entity in particle_sprites.iter() {
+            commands.entity(entity).despawn();
+        }
+        for (idx, particle) in sim.particles.iter().enumerate() {
+            commands.spawn((material_sprite(&sim.world, particle.material, particle.position[0], particle.position[1], 1.0), ParticleSprite(idx)));
+        }
+    }
+    for idx in std::mem::take(&mut fragment_events.emitted) {
+        let Some(particle) = sim.particles.get(idx).filter(|_| !rebuild) else { continue; };
+        commands.spawn((material_sprite(&sim.world, particle.material, particle.position[0], particle.position[1], 1.0), ParticleSprite(idx)));
+    }
+
+    for split in fragment_events.splits.drain(..) {
         for (entity, sprite) in object_sprites.iter() {
-            if sprite.object_idx == obj_idx {
+            if sprite.object_idx == split.object_idx {
                 commands.entity(entity).despawn();
             }
         }
 
-        for frag_data in fragment_data {
-            if frag_data.len() == 1 {
-                let (pos, material) = frag_data[0];
-                counter.0 += 1;
-                let idx = sim.particles.len();
+        for piece in split.pieces {
+            match piece {
+                Piece::Particle(_) if rebuild => {}
+                Piece::Particle(idx) => {
+                    let particle = &sim.particles[idx];
+                    commands.spawn((
+                        material_sprite(&sim.world, particle.material, particle.position[0], particle.position[1], 1.0),
+                        ParticleSprite(idx),
+                    ));
+                }
+                Piece::Object(new_obj_idx) => spawn_object_sprites(&mut commands, &sim.world, &sim.objects[new_obj_idx], new_obj_idx),
+            }
+        }
+    }
+}
 
-                let particle = SimParticle::new(counter.0, pos, [0.0, 0.0], material, ParticleRef::Free(idx));
-                sim.world.update_occupation_on_position(particle.position, particle.particle_ref);
-                sim.world.update_mass_on_position(particle.position, particle.mass());
-                sim.particles.push(particle);
+/// Verdichtet den Objektvektor, sobald genug zerstörte Objekte liegen, und zieht die Sprite-Indizes nach.
+fn collect_destroyed_objects(
+    mut commands: Commands,
+    mut sim: ResMut<Simulation>,
+    mut object_sprites: Query<(Entity, &mut ObjectSprite)>,
+    mut dragged: ResMut<DraggedObject>,
+    mut interpolation: ResMut<Interpolation>,
+) {
+    if sim.objects.iter().filter(|obj| obj.is_destroyed).count() < OBJECT_GC_THRESHOLD { return; }
+
+    let remap = sim.gc_objects();
+    let new_idx = |old_idx: usize| remap.get(old_idx).copied().flatten();
+    for (entity, mut sprite) in object_sprites.iter_mut() {
+        match new_idx(sprite.object_idx) {
+            Some(idx) => sprite.object_idx = idx,
+            None => commands.entity(entity).despawn(),
+        }
+    }
+    dragged.0 = dragged.0.and_then(|(old_idx, cell)| new_idx(old_idx).map(|idx| (idx, cell)));
+    let previous = std::mem::take(&mut interpolation.objects);
+    interpolation.objects = previous.into_iter().enumerate()
+        .filter(|(old_idx, _)| new_idx(*old_idx).is_some())
+        .map(|(_, pos)| pos)
+        .collect();
+}
 
-                let color = material_to_color(material);
-                let (screen_x, screen_y) = grid_to_screen(pos[0], pos[1]);
-                commands.spawn((
-                    SpriteBundle {
-                        sprite: Sprite {
-                            color,
-                            custom_size: Some(Vec2::new(CELL_SIZE - 1.0, CELL_SIZE - 1.0)),
-                            ..default()
-                        },
-                        transform: Transform::from_xyz(screen_x, screen_y, 1.0),
-                        ..default()
-                    },
-                    ParticleSprite(idx),
-                ));
-            } else {
-                object_counter.0 += 1;
-                let new_obj_idx = sim.objects.len();
+fn toggle_liquid_smoothing(keyboard: Res<Input<KeyCode>>, mut smoothing: ResMut<LiquidSmoothing>) {
+    if keyboard.just_pressed(KeyCode::L) { smoothing.0 = !smoothing.0; }
+}
 
-                let new_object = SimObject::new_from_fragment(object_counter.0, new_obj_idx, &frag_data, old_velocity);
+fn toggle_id_colors(keyboard: Res<Input<KeyCode>>, mut id_colors: ResMut<IdColors>) {
+    if keyboard.just_pressed(KeyCode::I) { id_colors.0 = !id_colors.0; }
+}
 
-                for particle in new_object.get_object_elements() {
-                    if particle.material != MaterialTyp::Luft {
-                        sim.world.update_occupation_on_position(particle.position, particle.particle_ref);
-                        sim.world.update_mass_on_position(particle.position, particle.mass());
-                    }
-                }
+/// Q: nächste Vorlage aus `QUADRANT_PRESETS` für Shift+Klick.
+fn cycle_quadrant_preset(keyboard: Res<Input<KeyCode>>, mut preset: ResMut<QuadrantPreset>, mut status: ResMut<StatusMessage>) {
+    if !keyboard.just_pressed(KeyCode::Q) { return; }
+    preset.0 = (preset.0 + 1) % QUADRANT_PRESETS.len();
+    status.show(format!("Quadrant: {:?}", QUADRANT_PRESETS[preset.0]));
+}
 
-                let h = new_object.get_height();
-                let w = new_object.get_width();
-                for i in 0..h {
-                    for j in 0..w {
-                        let particle = new_object.get_particle_at(i, j);
-                        if particle.material != MaterialTyp::Luft {
-                            let color = material_to_color(particle.material);
-                            let (screen_x, screen_y) = grid_to_screen(particle.position[0], particle.position[1]);
-                            commands.spawn((
-                                SpriteBundle {
-                                    sprite: Sprite {
-                                        color,
-                                        custom_size: Some(Vec2::new(CELL_SIZE - 1.0, CELL_SIZE - 1.0)),
-                                        ..default()
-                                    },
-                                    transform: Transform::from_xyz(screen_x, screen_y, 2.0),
-                                    ..default()
-                                },
-                                ObjectSprite { object_idx: new_obj_idx, grid_i: i, grid_j: j },
-                            ));
-                        }
-                    }
-                }
-                sim.objects.push(new_object);
+fn conveyor_color(direction: Conveyor) -> Color {
+    match direction {
+        Conveyor::Left => Color::rgb(0.55, 0.4, 0.3),
+        Conveyor::Right => Color::rgb(0.3, 0.4, 0.55),
+    }
+}
+
+/// V (gedrückt halten) legt Förderbänder unter den Cursor, O dreht die Laufrichtung für neue Bänder um.
+fn place_conveyors(
+    mut commands: Commands,
+    mut sim: ResMut<Simulation>,
+    keyboard: Res<Input<KeyCode>>,
+    mut direction: ResMut<ConveyorDirection>,
+    mut status: ResMut<StatusMessage>,
+    conveyor_sprites: Query<(Entity, &ConveyorSprite)>,
+    cursor: Cursor,
+) {
+    if keyboard.just_pressed(KeyCode::O) {
+        direction.0 = direction.0.reversed();
+        status.show(format!("Förderband: {:?}", direction.0));
+    }
+    if !keyboard.pressed(KeyCode::V) { return; }
+
+    let Some((grid_x, grid_y)) = cursor.grid(&sim.world) else { return; };
+    if grid_x < 0 || grid_y < 0 { return; }
+    let (x, y) = (grid_x as usize, grid_y as usize);
+    if sim.conveyor_at(x, y) == Some(direction.0) || sim.place_conveyor(x, y, direction.0).is_err() { return; }
+
+    for (entity, cell) in conveyor_sprites.iter() {
+        if (cell.0, cell.1) == (x, y) {
+            commands.entity(entity).despawn();
+        }
+    }
+    let (screen_x, screen_y) = grid_to_screen(&sim.world, x as f32, y as f32);
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: conveyor_color(direction.0),
+                custom_size: Some(Vec2::new(CELL_SIZE - 1.0, CELL_SIZE - 1.0)),
+                ..default()
+            },
+            transform: Transform::from_xyz(screen_x, screen_y, 0.0),
+            ..default()
+        },
+        ConveyorSprite(x, y),
+    ));
+}
+
+/// P: Verhalten an der Partikelgrenze wechseln (ablehnen, älteste ersetzen, verschmelzen).
+fn cycle_cap_policy(keyboard: Res<Input<KeyCode>>, mut sim: ResMut<Simulation>, mut status: ResMut<StatusMessage>) {
+    if !keyboard.just_pressed(KeyCode::P) { return; }
+    sim.cap_policy = match sim.cap_policy {
+        CapPolicy::Reject => CapPolicy::RecycleOldest,
+        CapPolicy::RecycleOldest => CapPolicy::Merge,
+        CapPolicy::Merge => CapPolicy::Reject,
+    };
+    status.show(format!("Partikelgrenze: {:?}", sim.cap_policy));
+}
+
+/// C: ruhende Partikelhaufen regelmäßig zu Objekten binden (an/aus).
+fn toggle_clusters(keyboard: Res<Input<KeyCode>>, mut sim: ResMut<Simulation>) {
+    if !keyboard.just_pressed(KeyCode::C) { return; }
+    sim.cluster_min_size = if sim.cluster_min_size == 0 { CLUSTER_MIN_SIZE } else { 0 };
+}
+
+fn toggle_interpolation(keyboard: Res<Input<KeyCode>>, mut interpolation: ResMut<Interpolation>) {
+    if keyboard.just_pressed(KeyCode::T) { interpolation.enabled = !interpolation.enabled; }
+}
+
+fn liquid_neighbors(sim: &SimCore, x: usize, y: usize) -> usize {
+    let mut count = 0;
+    for dy in -1i32..=1 {
+        for dx in -1i32..=1 {
+            if dx == 0 && dy == 0 { continue; }
+            let nx = x as i32 + dx;
+            let ny = y as i32 + dy;
+            if nx < 0 || ny < 0 { continue; }
+            if let Some(material) = sim.material_at(nx as usize, ny as usize) {
+                if !material.is_solid() && material != MaterialTyp::Luft { count += 1; }
             }
         }
     }
+    count
+}
+
+fn update_fluid_blur(keyboard: Res<Input<KeyCode>>, sim: Res<Simulation>, mut blur: ResMut<FluidBlur>) {
+    if keyboard.just_pressed(KeyCode::B) { blur.enabled = !blur.enabled; }
+    if !blur.enabled { return; }
+
+    let (w, h) = (sim.world.width, sim.world.height);
+    let mut mask = vec![0.0; w * h];
+    for p in sim.particles.iter() {
+        if p.material.is_solid() { continue; }
+        let (x, y) = World::cell_of(p.position);
+        if x < w && y < h { mask[y * w + x] = 1.0; }
+    }
+
+    // Separierbarer Box-Blur: erst horizontal, dann vertikal
+    let mut tmp = vec![0.0; w * h];
+    for y in 0..h {
+        for x in 0..w {
+            let lo = x.saturating_sub(1);
+            let hi = (x + 1).min(w - 1);
+            tmp[y * w + x] = (lo..=hi).map(|xx| mask[y * w + xx]).sum::<f32>() / 3.0;
+        }
+    }
+    for y in 0..h {
+        for x in 0..w {
+            let lo = y.saturating_sub(1);
+            let hi = (y + 1).min(h - 1);
+            mask[y * w + x] = (lo..=hi).map(|yy| tmp[yy * w + x]).sum::<f32>() / 3.0;
+        }
+    }
+    blur.mask = mask;
 }
 
-fn update_sprites(sim: Res<Simulation>, mut query: Query<(&ParticleSprite, &mut Transform)>) {
-    for (particle_sprite, mut transform) in query.iter_mut() {
+fn update_sprites(
+    sim: Res<Simulation>,
+    smoothing: Res<LiquidSmoothing>,
+    blur: Res<FluidBlur>,
+    id_colors: Res<IdColors>,
+    (interpolation, timers): (Res<Interpolation>, Res<Timers>),
+    mut query: Query<(&ParticleSprite, &mut Transform, &mut Sprite)>,
+) {
+    let t = timers.sim.percent();
+    for (particle_sprite, mut transform, mut sprite) in query.iter_mut() {
         if particle_sprite.0 >= sim.particles.len() { continue; }
         let particle = &sim.particles[particle_sprite.0];
-        let (screen_x, screen_y) = grid_to_screen(particle.position[0], particle.position[1]);
+        let [x, y] = interpolation.lerp(interpolation.particles.get(particle_sprite.0), particle.position, t);
+        let (screen_x, screen_y) = grid_to_screen(&sim.world, x, y);
         transform.translation.x = screen_x;
         transform.translation.y = screen_y;
-    }
-}
 
-fn update_object_sprites(sim: Res<Simulation>, mut query: Query<(&ObjectSprite, &mut Transform, &mut Visibility)>) {
-    for (obj_sprite, mut transform, mut visibility) in query.iter_mut() {
-        if obj_sprite.object_idx >= sim.objects.len() {
-            *visibility = Visibility::Hidden;
+        let color = if id_colors.0 { id_to_color(particle.id) } else { particle_color(&sim.world.materials, particle) };
+        if particle.material.is_solid() {
+            sprite.color = color;
             continue;
         }
 
-        let object = &sim.objects[obj_sprite.object_idx];
-        if object.is_destroyed {
+        // Flüssigkeiten: je mehr flüssige Nachbarn, desto größer (überlappend) und deckender
+        let base_size = (CELL_SIZE - 1.0) * sim.world.materials.render_scale(particle.material);
+        let (size, mut alpha) = if smoothing.0 {
+            let (x, y) = World::cell_of(particle.position);
+            let fill = liquid_neighbors(&sim, x, y) as f32 / 8.0;
+            (base_size + 3.0 * fill, 0.6 + 0.4 * fill)
+        } else {
+            (base_size, 1.0)
+        };
+        if blur.enabled {
+            let (x, y) = World::cell_of(particle.position);
+            let idx = y * sim.world.width + x;
+            if let Some(&value) = blur.mask.get(idx) { alpha = value.max(0.3); }
+        }
+        sprite.custom_size = Some(Vec2::new(size, size));
+        sprite.color = color.with_a(alpha * sim.world.materials.render_alpha(particle.material));
+    }
+}
+
+fn update_object_sprites(
+    sim: Res<Simulation>,
+    interpolation: Res<Interpolation>,
+    timers: Res<Timers>,
+    mut query: Query<(&ObjectSprite, &mut Transform, &mut Visibility, &mut Sprite)>,
+) {
+    let t = timers.sim.percent();
+    for (obj_sprite, mut transform, mut visibility, mut sprite) in query.iter_mut() {
+        let Some(object) = sim.live_object(obj_sprite.object_idx) else {
             *visibility = Visibility::Hidden;
             continue;
-        }
+        };
 
-        *visibility = Visibility::Visible;
+        // Alle Zellen eines Objekts gleiten mit dem Versatz seines Ankers
+        let anchor = object.get_object_position();
+        let [ax, ay] = interpolation.lerp(interpolation.objects.get(obj_sprite.object_idx), anchor, t);
+
+        // Übermalte Zellen: Farbe nachziehen, zu Luft gewordene ausblenden
         let particle = object.get_particle_at(obj_sprite.grid_i, obj_sprite.grid_j);
-        let (screen_x, screen_y) = grid_to_screen(particle.position[0], particle.position[1]);
+        *visibility = if particle.material == MaterialTyp::Luft { Visibility::Hidden } else { Visibility::Visible };
+        sprite.color = material_to_color(&sim.world.materials, particle.material);
+        let (screen_x, screen_y) = grid_to_screen(&sim.world, particle.position[0] + ax - anchor[0], particle.position[1] + ay - anchor[1]);
         transform.translation.x = screen_x;
         transform.translation.y = screen_y;
     }
@@ -502,25 +1586,19 @@ fn update_object_sprites(sim: Res<Simulation>, mut query: Query<(&ObjectSprite,
 
 fn update_debug_label(
     sim: Res<Simulation>,
-    windows: Query<&Window>,
-    camera_query: Query<&Transform, With<MainCamera>>,
+    cursor: Cursor,
     mut query: Query<&mut Text, With<DebugLabel>>,
 ) {
-    let window = windows.single();
-    let camera_transform = camera_query.single();
     let mut text = query.single_mut();
 
-    let cursor_pos = match window.cursor_position() {
-        Some(pos) => pos,
-        None => { text.sections[0].value = "".to_string(); return; }
+    let Some((grid_x, grid_y)) = cursor.grid(&sim.world) else {
+        text.sections[0].value = "".to_string();
+        return;
     };
+    let grid_x = grid_x.max(0) as usize;
+    let grid_y = grid_y.max(0) as usize;
 
-    let world_x = cursor_pos.x - WINDOW_WIDTH / 2.0 + camera_transform.translation.x;
-    let world_y = WINDOW_HEIGHT / 2.0 - cursor_pos.y + camera_transform.translation.y;
-    let grid_x = ((world_x / CELL_SIZE + GRID_WIDTH as f32 / 2.0) as i32).max(0) as usize;
-    let grid_y = ((world_y / CELL_SIZE + GRID_HEIGHT as f32 / 2.0) as i32).max(0) as usize;
-
-    if grid_x >= GRID_WIDTH || grid_y >= GRID_HEIGHT {
+    if grid_x >= sim.world.width || grid_y >= sim.world.height {
         text.sections[0].value = "".to_string();
         return;
     }
@@ -528,23 +1606,24 @@ fn update_debug_label(
     let pressure = sim.world.give_pressure_on_position(grid_x, grid_y);
 
     match sim.world.give_occupation_on_position(grid_x, grid_y) {
-        Some(ParticleRef::Free(idx)) => {
-            if idx < sim.particles.len() {
-                let p = &sim.particles[idx];
+        Some(particle_ref @ ParticleRef::Free(idx, _)) => {
+            if let Some(p) = sim.resolve_particle(particle_ref) {
                 text.sections[0].value = format!(
                     "PARTIKEL #{}\nMaterial: {:?}\nDruck: {:.1}",
                     idx, p.material, pressure
                 );
             }
         }
-        Some(ParticleRef::InObject(obj_idx, i, j)) => {
-            if obj_idx < sim.objects.len() && !sim.objects[obj_idx].is_destroyed {
-                let obj = &sim.objects[obj_idx];
+        Some(ParticleRef::InObject(object_id, _, _)) => {
+            if let Some((obj, i, j)) = sim.object_at(grid_x, grid_y) {
                 let vel = obj.get_object_velocity();
                 let particle = obj.get_particle_at(i, j);
+                let mut composition: Vec<(MaterialTyp, usize)> = obj.composition().into_iter().collect();
+                composition.sort_by_key(|&(material, count)| (std::cmp::Reverse(count), material_key(material)));
+                let composition: Vec<String> = composition.iter().map(|(material, count)| format!("{:?} {}", material, count)).collect();
                 text.sections[0].value = format!(
-                    "OBJECT #{}\nMaterial: {:?}\nVel: [{:.1}, {:.1}]\nDruck: {:.1}",
-                    obj_idx, particle.material, vel[0], vel[1], pressure
+                    "OBJECT #{}\nMaterial: {:?} (meist {:?})\nAnteile: {}\nVel: [{:.1}, {:.1}]\nDruck: {:.1}",
+                    object_id, particle.material, obj.dominant_material(), composition.join(", "), vel[0], vel[1], pressure
                 );
             }
         }
@@ -555,4 +1634,23 @@ fn update_debug_label(
             text.sections[0].value = format!("Leer [{}, {}]\nDruck: {:.1}", grid_x, grid_y, pressure);
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_clock_catches_up_capped_and_skips_tiny_deltas() {
+        let mut clock = StepClock::new(Duration::from_millis(50), 4);
+        assert_eq!(clock.advance(Duration::from_millis(10)), 0);
+        assert!(!clock.stepped());
+        assert_eq!(clock.advance(Duration::from_millis(45)), 1);
+        assert!((clock.percent() - 0.1).abs() < 1e-4);
+
+        // Eine Sekunde Rückstand: höchstens vier Schritte, der Rest verfällt bis auf den angebrochenen Schritt
+        assert_eq!(clock.advance(Duration::from_millis(1000)), 4);
+        assert!(clock.stepped());
+        assert!(clock.percent() < 1.0);
+        assert_eq!(clock.advance(Duration::from_millis(1)), 0);
+    }
+}
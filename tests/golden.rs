@@ -0,0 +1,46 @@
+//! Regressionscheck für die Physik: feste, deterministische Szenarien laufen lassen und das Ergebnis von
+//! `render_ascii` mit den eingecheckten Goldausgaben in `examples/golden/` vergleichen.
+//!
+//! `BLESS=1 cargo test --test golden --no-default-features` schreibt die Goldausgaben neu.
+
+use std::fs;
+use std::path::PathBuf;
+use world::testing::ascii_diff;
+use world::{MaterialTyp, Simulation, SimulationBuilder};
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("examples").join("golden").join(format!("{}.txt", name))
+}
+
+/// Lässt `sim` `ticks` Schritte laufen und vergleicht das Bild mit der Goldausgabe `name`.
+fn check_golden(name: &str, ticks: usize, mut sim: Simulation) {
+    for _ in 0..ticks {
+        sim.step();
+    }
+    let actual = sim.render_ascii();
+    let path = golden_path(name);
+
+    if std::env::var_os("BLESS").is_some() {
+        fs::write(&path, &actual).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    if let Some(report) = ascii_diff(&expected, &actual) {
+        panic!("{}: Abweichung nach {} Ticks\n{}", name, ticks, report);
+    }
+}
+
+#[test]
+fn stacking() {
+    // Das Szenario der terminal-Binary: zwei Sandkörner fallen und stapeln sich
+    let sim = SimulationBuilder::new()
+        .world(20, 20)
+        .gravity([0.0, -0.5])
+        .seed(0)
+        .add_particle([0.0, 10.0], [0.0, 0.0], MaterialTyp::Sand)
+        .add_particle([0.0, 12.0], [0.0, 0.0], MaterialTyp::Sand)
+        .build()
+        .unwrap();
+    check_golden("stacking", 20, sim);
+}